@@ -0,0 +1,152 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::mutator::InputDatabase;
+use crate::rng::Rng;
+use crate::schedule::SeedMeta;
+
+/// Inputs that reached new coverage points
+#[derive(Clone, Debug)]
+pub struct Corpus<F> {
+    // Inputs kept for mutation, in the order they were added
+    inputs: Vec<Vec<F>>,
+    // Inputs of `inputs`, to skip the duplicates
+    known: HashSet<Vec<F>>,
+    // Coverage points (edges) already reached
+    coverage: HashSet<u64>,
+    // Scheduling metadata of the inputs, set when a power schedule is used
+    meta: HashMap<Vec<F>, SeedMeta>,
+}
+
+impl<F> Default for Corpus<F> {
+    fn default() -> Self {
+        Corpus {
+            inputs: Vec::new(),
+            known: HashSet::new(),
+            coverage: HashSet::new(),
+            meta: HashMap::new(),
+        }
+    }
+}
+
+impl<F: Clone + Eq + Hash> Corpus<F> {
+    /// Adds the input to the corpus if it reached a coverage point never seen before
+    /// Returns true if the input reached new coverage
+    pub fn add(&mut self, input: &[F], coverage: &[u64]) -> bool {
+        let mut new_coverage = false;
+        for point in coverage {
+            new_coverage |= self.coverage.insert(*point);
+        }
+        if new_coverage {
+            self.add_input(input);
+        }
+        new_coverage
+    }
+
+    /// Adds the input to the corpus whatever its coverage
+    /// Returns false if the input was already in the corpus
+    pub fn add_input(&mut self, input: &[F]) -> bool {
+        if !self.known.insert(input.to_vec()) {
+            return false;
+        }
+        self.inputs.push(input.to_vec());
+        true
+    }
+
+    /// Returns true if the input is in the corpus
+    pub fn contains(&self, input: &[F]) -> bool {
+        self.known.contains(input)
+    }
+
+    /// Input added at position `idx`
+    pub fn get(&self, idx: usize) -> Option<&Vec<F>> {
+        self.inputs.get(idx)
+    }
+
+    /// Picks a random input from the corpus
+    pub fn pick(&self, rng: &Rng) -> Option<&Vec<F>> {
+        if self.inputs.is_empty() {
+            return None;
+        }
+        self.inputs.get(rng.gen_range(0..=self.inputs.len() - 1))
+    }
+
+    /// Sets the scheduling metadata of an input
+    pub fn set_meta(&mut self, input: &[F], meta: SeedMeta) {
+        self.meta.insert(input.to_vec(), meta);
+    }
+
+    /// Scheduling metadata of an input, the default one if it was never set
+    pub fn meta(&self, input: &[F]) -> SeedMeta {
+        self.meta.get(input).cloned().unwrap_or_default()
+    }
+
+    /// Inputs of the corpus, in the order they were added
+    pub fn inputs(&self) -> &[Vec<F>] {
+        &self.inputs
+    }
+
+    /// Number of inputs in the corpus
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    /// Returns true if no input has been kept yet
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    /// Number of distinct coverage points reached
+    pub fn coverage(&self) -> usize {
+        self.coverage.len()
+    }
+
+    /// Marks the coverage points reached by the inputs of `other` as already seen
+    pub fn merge_coverage(&mut self, other: &Corpus<F>) {
+        self.coverage.extend(&other.coverage);
+    }
+}
+
+/// Gives the splice strategies of the mutator access to the inputs of the corpus
+impl<F: Clone + Eq + Hash> InputDatabase<F> for Corpus<F> {
+    fn num_inputs(&self) -> usize {
+        self.inputs.len()
+    }
+
+    fn input(&self, idx: usize) -> Option<Vec<F>> {
+        self.inputs.get(idx).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Corpus;
+    use crate::rng::Rng;
+
+    #[test]
+    fn test_corpus_dedup_by_coverage() {
+        let mut corpus: Corpus<u64> = Corpus::default();
+        assert!(corpus.pick(&Rng::seeded(1)).is_none());
+        assert!(corpus.add(&[1, 2], &[42, 44]));
+        assert!(!corpus.add(&[3, 4], &[44, 42]));
+        assert!(!corpus.add(&[3, 4], &[]));
+        assert!(corpus.add(&[3, 4], &[42, 43]));
+        assert!(corpus.add_input(&[5, 6]));
+        // The inputs are kept once
+        assert!(!corpus.add_input(&[1, 2]));
+        assert!(corpus.contains(&[5, 6]));
+        assert_eq!(corpus.get(1), Some(&vec![3, 4]));
+        assert_eq!(corpus.len(), 3);
+        assert_eq!(corpus.coverage(), 3);
+        assert!(corpus
+            .inputs()
+            .contains(corpus.pick(&Rng::seeded(1)).unwrap()));
+
+        // The inputs of another corpus reaching the merged coverage are not kept
+        let mut other: Corpus<u64> = Corpus::default();
+        assert!(other.add(&[7, 8], &[42, 45]));
+        corpus.merge_coverage(&other);
+        assert_eq!(corpus.coverage(), 4);
+        assert!(!corpus.add(&[7, 8], &[45]));
+    }
+}
@@ -0,0 +1,436 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+/// Key of the statements annotations emitted by the compiler in the Sierra debug info
+pub const COVERAGE_ANNOTATIONS: &str = "github.com/software-mansion/cairo-coverage";
+
+/// Position in a source file, lines and columns start at 0
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub line: usize,
+    pub col: usize,
+}
+
+/// Span of source code
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct CodeSpan {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// Annotations of the Sierra statements, emitted by Scarb with the
+/// `unstable-add-statements-code-locations-debug-info` and
+/// `unstable-add-statements-functions-debug-info` options
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct CoverageAnnotations {
+    /// Source spans of each statement, by statement index, the inlined code has several spans
+    #[serde(default)]
+    pub statements_code_locations: HashMap<String, Vec<(String, CodeSpan, bool)>>,
+    /// Functions of each statement, by statement index
+    #[serde(default)]
+    pub statements_functions: HashMap<String, Vec<String>>,
+}
+
+/// Line of a source file
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SourceLine {
+    pub file: String,
+    /// Line number, starting at 1
+    pub line: usize,
+}
+
+/// Source lines and functions of the Sierra statements
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    lines: HashMap<usize, Vec<SourceLine>>,
+    functions: HashMap<usize, String>,
+}
+
+impl SourceMap {
+    /// Builds the source map from the annotations of the Sierra debug info
+    pub fn from_annotations(annotations: &CoverageAnnotations) -> Result<Self, String> {
+        let statement_idx = |key: &String| {
+            key.parse::<usize>()
+                .map_err(|_| format!("invalid statement index {}", key))
+        };
+        let mut source_map = SourceMap::default();
+        for (key, locations) in &annotations.statements_code_locations {
+            let lines = locations
+                .iter()
+                .map(|(file, span, _)| SourceLine {
+                    file: file.clone(),
+                    line: span.start.line + 1,
+                })
+                .collect();
+            source_map.lines.insert(statement_idx(key)?, lines);
+        }
+        for (key, functions) in &annotations.statements_functions {
+            if let Some(function) = functions.first() {
+                source_map
+                    .functions
+                    .insert(statement_idx(key)?, function.clone());
+            }
+        }
+        Ok(source_map)
+    }
+
+    /// Sets the source lines and the function of a statement
+    pub fn insert(&mut self, statement: usize, lines: Vec<SourceLine>, function: Option<String>) {
+        self.lines.insert(statement, lines);
+        if let Some(function) = function {
+            self.functions.insert(statement, function);
+        }
+    }
+
+    /// Returns true if no statement has a source location
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+}
+
+/// Coverage of a function
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionCoverage {
+    pub name: String,
+    /// First line of the function
+    pub line: usize,
+    /// Highest number of executions of a statement of the function
+    pub hits: u64,
+}
+
+/// Coverage of a source file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileCoverage {
+    pub path: String,
+    /// Highest number of executions of a statement of each line
+    pub lines: BTreeMap<usize, u64>,
+    /// Functions starting in the file, sorted by line
+    pub functions: Vec<FunctionCoverage>,
+}
+
+impl FileCoverage {
+    /// Number of executed lines
+    pub fn lines_hit(&self) -> usize {
+        self.lines.values().filter(|hits| **hits > 0).count()
+    }
+
+    /// Number of executed functions
+    pub fn functions_hit(&self) -> usize {
+        self.functions.iter().filter(|f| f.hits > 0).count()
+    }
+}
+
+/// Coverage of the source files of a contract
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CoverageReport {
+    /// Covered files, sorted by path
+    pub files: Vec<FileCoverage>,
+}
+
+impl CoverageReport {
+    /// Builds the report from the number of executions of the Sierra statements
+    /// `hits` has an entry for every executable statement, the statements never executed
+    /// have 0 hits. The statements without source location are left out.
+    pub fn new(source_map: &SourceMap, hits: &HashMap<usize, u64>) -> Self {
+        let mut files: BTreeMap<String, FileCoverage> = BTreeMap::new();
+        // First line and hits of the functions
+        let mut functions: BTreeMap<String, (SourceLine, u64)> = BTreeMap::new();
+        for (statement, count) in hits {
+            let lines = match source_map.lines.get(statement) {
+                Some(lines) if !lines.is_empty() => lines,
+                _ => continue,
+            };
+            for line in lines {
+                let file = files.entry(line.file.clone()).or_default();
+                let line_hits = file.lines.entry(line.line).or_default();
+                *line_hits = (*line_hits).max(*count);
+            }
+            if let Some(name) = source_map.functions.get(statement) {
+                let function = functions
+                    .entry(name.clone())
+                    .or_insert_with(|| (lines[0].clone(), 0));
+                function.0 = function.0.clone().min(lines[0].clone());
+                function.1 = function.1.max(*count);
+            }
+        }
+        for (name, (line, hits)) in functions {
+            if let Some(file) = files.get_mut(&line.file) {
+                file.functions.push(FunctionCoverage {
+                    name,
+                    line: line.line,
+                    hits,
+                });
+            }
+        }
+        CoverageReport {
+            files: files
+                .into_iter()
+                .map(|(path, mut file)| {
+                    file.path = path;
+                    file.functions.sort_by_key(|function| function.line);
+                    file
+                })
+                .collect(),
+        }
+    }
+
+    /// Number of executable lines
+    pub fn lines_found(&self) -> usize {
+        self.files.iter().map(|file| file.lines.len()).sum()
+    }
+
+    /// Number of executed lines
+    pub fn lines_hit(&self) -> usize {
+        self.files.iter().map(FileCoverage::lines_hit).sum()
+    }
+
+    /// Formats the report as an lcov tracefile
+    pub fn to_lcov(&self, test_name: &str) -> String {
+        let mut lcov = String::new();
+        for file in &self.files {
+            let _ = writeln!(lcov, "TN:{}", test_name);
+            let _ = writeln!(lcov, "SF:{}", file.path);
+            for function in &file.functions {
+                let _ = writeln!(lcov, "FN:{},{}", function.line, function.name);
+            }
+            for function in &file.functions {
+                let _ = writeln!(lcov, "FNDA:{},{}", function.hits, function.name);
+            }
+            let _ = writeln!(lcov, "FNF:{}", file.functions.len());
+            let _ = writeln!(lcov, "FNH:{}", file.functions_hit());
+            for (line, hits) in &file.lines {
+                let _ = writeln!(lcov, "DA:{},{}", line, hits);
+            }
+            let _ = writeln!(lcov, "LF:{}", file.lines.len());
+            let _ = writeln!(lcov, "LH:{}", file.lines_hit());
+            lcov.push_str("end_of_record\n");
+        }
+        lcov
+    }
+
+    /// Formats the report as a standalone HTML page with the hits of each function and line
+    /// The source files are read to display the code, the files that cannot be read only
+    /// list their executable lines.
+    pub fn to_html(&self, title: &str) -> String {
+        let mut html = String::new();
+        let _ = writeln!(
+            html,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n\
+             body {{ font-family: sans-serif; }}\n\
+             table {{ border-collapse: collapse; }}\n\
+             td, th {{ padding: 2px 8px; text-align: left; }}\n\
+             pre {{ margin: 0; }}\n\
+             .hit {{ background: #c8f0c8; }}\n\
+             .miss {{ background: #f8c8c8; }}\n\
+             .count {{ text-align: right; color: #666; }}\n\
+             </style>\n</head>\n<body>\n<h1>{}</h1>",
+            escape_html(title),
+            escape_html(title)
+        );
+        let _ = writeln!(
+            html,
+            "<p>Lines: {}/{} ({})</p>",
+            self.lines_hit(),
+            self.lines_found(),
+            percent(self.lines_hit(), self.lines_found())
+        );
+
+        // Summary of the files
+        html.push_str("<table>\n<tr><th>File</th><th>Lines</th><th>Functions</th></tr>\n");
+        for (idx, file) in self.files.iter().enumerate() {
+            let _ = writeln!(
+                html,
+                "<tr><td><a href=\"#file{}\">{}</a></td><td>{}/{} ({})</td><td>{}/{}</td></tr>",
+                idx,
+                escape_html(&file.path),
+                file.lines_hit(),
+                file.lines.len(),
+                percent(file.lines_hit(), file.lines.len()),
+                file.functions_hit(),
+                file.functions.len()
+            );
+        }
+        html.push_str("</table>\n");
+
+        for (idx, file) in self.files.iter().enumerate() {
+            let _ = writeln!(
+                html,
+                "<h2 id=\"file{}\">{}</h2>",
+                idx,
+                escape_html(&file.path)
+            );
+            if !file.functions.is_empty() {
+                html.push_str("<table>\n<tr><th>Function</th><th>Line</th><th>Hits</th></tr>\n");
+                for function in &file.functions {
+                    let _ = writeln!(
+                        html,
+                        "<tr class=\"{}\"><td>{}</td><td>{}</td><td>{}</td></tr>",
+                        if function.hits > 0 { "hit" } else { "miss" },
+                        escape_html(&function.name),
+                        function.line,
+                        function.hits
+                    );
+                }
+                html.push_str("</table>\n");
+            }
+
+            html.push_str("<table>\n");
+            match fs::read_to_string(&file.path) {
+                Ok(source) => {
+                    for (line_idx, code) in source.lines().enumerate() {
+                        write_line(
+                            &mut html,
+                            line_idx + 1,
+                            file.lines.get(&(line_idx + 1)),
+                            code,
+                        );
+                    }
+                }
+                Err(_) => {
+                    for (line, hits) in &file.lines {
+                        write_line(&mut html, *line, Some(hits), "");
+                    }
+                }
+            }
+            html.push_str("</table>\n");
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    /// Writes the report in `dir` as an lcov tracefile, `lcov.info`, and an HTML page, `index.html`
+    pub fn write(&self, dir: &str, title: &str) -> Result<(), String> {
+        fs::create_dir_all(dir).map_err(|e| format!("Could not create {}: {}", dir, e))?;
+        for (file, contents) in [
+            ("lcov.info", self.to_lcov(title)),
+            ("index.html", self.to_html(title)),
+        ] {
+            let path = Path::new(dir).join(file);
+            fs::write(&path, contents)
+                .map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes a source line and its hits, the lines without statement have no hits
+fn write_line(html: &mut String, line: usize, hits: Option<&u64>, code: &str) {
+    let (class, count) = match hits {
+        Some(0) => ("miss", "0".to_string()),
+        Some(hits) => ("hit", hits.to_string()),
+        None => ("", String::new()),
+    };
+    let _ = writeln!(
+        html,
+        "<tr class=\"{}\"><td class=\"count\">{}</td><td class=\"count\">{}</td><td><pre>{}</pre></td></tr>",
+        class,
+        line,
+        count,
+        escape_html(code)
+    );
+}
+
+fn percent(hit: usize, found: usize) -> String {
+    match found {
+        0 => "-".to_string(),
+        _ => format!("{:.1}%", hit as f64 * 100.0 / found as f64),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(file: &str, line: usize) -> Vec<SourceLine> {
+        vec![SourceLine {
+            file: file.to_string(),
+            line,
+        }]
+    }
+
+    #[test]
+    fn test_coverage_report() {
+        let mut source_map = SourceMap::default();
+        source_map.insert(0, line("lib.cairo", 3), Some("lib::f".to_string()));
+        source_map.insert(1, line("lib.cairo", 4), Some("lib::f".to_string()));
+        source_map.insert(2, line("lib.cairo", 4), Some("lib::f".to_string()));
+        source_map.insert(3, line("lib.cairo", 8), Some("lib::g".to_string()));
+        source_map.insert(4, line("utils.cairo", 1), None);
+        let hits: HashMap<usize, u64> = [(0, 2), (1, 0), (2, 5), (3, 0), (4, 1), (5, 7)]
+            .into_iter()
+            .collect();
+
+        let report = CoverageReport::new(&source_map, &hits);
+        assert_eq!(report.files.len(), 2);
+        let lib = &report.files[0];
+        assert_eq!(lib.path, "lib.cairo");
+        assert_eq!(
+            lib.lines.iter().map(|(l, h)| (*l, *h)).collect::<Vec<_>>(),
+            vec![(3, 2), (4, 5), (8, 0)]
+        );
+        assert_eq!(lib.functions.len(), 2);
+        assert_eq!(
+            lib.functions[0],
+            FunctionCoverage {
+                name: "lib::f".to_string(),
+                line: 3,
+                hits: 5
+            }
+        );
+        assert_eq!(lib.functions[1].hits, 0);
+        assert_eq!(report.lines_found(), 4);
+        assert_eq!(report.lines_hit(), 3);
+
+        let lcov = report.to_lcov("test");
+        assert!(lcov.starts_with("TN:test\nSF:lib.cairo\nFN:3,lib::f\nFN:8,lib::g\n"));
+        assert!(lcov.contains("FNDA:5,lib::f\nFNDA:0,lib::g\nFNF:2\nFNH:1\n"));
+        assert!(lcov.contains("DA:4,5\nDA:8,0\nLF:3\nLH:2\nend_of_record\n"));
+        assert!(lcov.ends_with("SF:utils.cairo\nFNF:0\nFNH:0\nDA:1,1\nLF:1\nLH:1\nend_of_record\n"));
+
+        let html = report.to_html("<contract>");
+        assert!(html.contains("<title>&lt;contract&gt;</title>"));
+        assert!(html.contains("Lines: 3/4 (75.0%)"));
+
+        let dir = std::env::temp_dir().join("cairo_fuzzer_test_coverage_report");
+        let _ = fs::remove_dir_all(&dir);
+        report.write(dir.to_str().unwrap(), "test").unwrap();
+        assert_eq!(fs::read_to_string(dir.join("lcov.info")).unwrap(), lcov);
+        assert!(dir.join("index.html").exists());
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_source_map_from_annotations() {
+        let span = CodeSpan {
+            start: Position { line: 9, col: 4 },
+            end: Position { line: 9, col: 12 },
+        };
+        let mut annotations = CoverageAnnotations::default();
+        annotations.statements_code_locations.insert(
+            "7".to_string(),
+            vec![("src/lib.cairo".to_string(), span, false)],
+        );
+        annotations
+            .statements_functions
+            .insert("7".to_string(), vec!["lib::f".to_string()]);
+        let source_map = SourceMap::from_annotations(&annotations).unwrap();
+        assert_eq!(source_map.lines[&7], line("src/lib.cairo", 10));
+        assert_eq!(source_map.functions[&7], "lib::f");
+
+        annotations
+            .statements_functions
+            .insert("x".to_string(), vec!["lib::f".to_string()]);
+        assert!(SourceMap::from_annotations(&annotations).is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+/// A distinct crash, identified by its error message
+#[derive(Clone, Debug)]
+pub struct Crash<F> {
+    // First input that triggered the crash
+    pub input: Vec<F>,
+    // Error message returned by the program
+    pub error_msg: String,
+    // Return values of the first crashing execution
+    pub return_values: Vec<F>,
+    // Number of inputs that triggered this crash
+    pub hits: usize,
+}
+
+/// Crashes deduplicated by error message
+#[derive(Clone, Debug)]
+pub struct CrashTriage<F> {
+    // Distinct crashes, in the order they were found
+    crashes: Vec<Crash<F>>,
+    // Maps an error message to its index in `crashes`
+    by_message: HashMap<String, usize>,
+}
+
+impl<F> Default for CrashTriage<F> {
+    fn default() -> Self {
+        CrashTriage {
+            crashes: Vec::new(),
+            by_message: HashMap::new(),
+        }
+    }
+}
+
+impl<F: Clone> CrashTriage<F> {
+    /// Records a crash, returns the new crash if its error message was never seen
+    pub fn record(
+        &mut self,
+        input: &[F],
+        error_msg: String,
+        return_values: &[F],
+    ) -> Option<&Crash<F>> {
+        if let Some(&idx) = self.by_message.get(&error_msg) {
+            self.crashes[idx].hits += 1;
+            return None;
+        }
+
+        self.by_message
+            .insert(error_msg.clone(), self.crashes.len());
+        self.crashes.push(Crash {
+            input: input.to_vec(),
+            error_msg,
+            return_values: return_values.to_vec(),
+            hits: 1,
+        });
+        self.crashes.last()
+    }
+
+    /// Distinct crashes, in the order they were found
+    pub fn crashes(&self) -> &[Crash<F>] {
+        &self.crashes
+    }
+
+    /// Error message and number of hits of each distinct crash
+    pub fn buckets(&self) -> Vec<(String, u64)> {
+        self.crashes
+            .iter()
+            .map(|crash| (crash.error_msg.clone(), crash.hits as u64))
+            .collect()
+    }
+
+    /// Number of distinct crashes
+    pub fn len(&self) -> usize {
+        self.crashes.len()
+    }
+
+    /// Returns true if no crash has been found
+    pub fn is_empty(&self) -> bool {
+        self.crashes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CrashTriage;
+
+    #[test]
+    fn test_crash_triage() {
+        let mut triage: CrashTriage<u64> = CrashTriage::default();
+        assert!(triage.record(&[1], "overflow".to_string(), &[]).is_some());
+        assert!(triage.record(&[2], "overflow".to_string(), &[]).is_none());
+        assert!(triage
+            .record(&[3], "division by zero".to_string(), &[])
+            .is_some());
+        assert_eq!(triage.len(), 2);
+        assert_eq!(triage.crashes()[0].input, vec![1]);
+        assert_eq!(triage.crashes()[0].hits, 2);
+        assert_eq!(
+            triage.buckets(),
+            vec![
+                ("overflow".to_string(), 2),
+                ("division by zero".to_string(), 1)
+            ]
+        );
+    }
+}
@@ -0,0 +1,385 @@
+use std::collections::VecDeque;
+use std::io::{self, IsTerminal, Write};
+
+/// Number of samples kept in the histories of the dashboard
+const HISTORY_LEN: usize = 512;
+/// Height of the history charts, in rows
+const CHART_HEIGHT: usize = 4;
+/// Maximum number of rows of the lists of the dashboard
+const MAX_ROWS: usize = 8;
+/// Width used when the size of the terminal is unknown
+const DEFAULT_WIDTH: usize = 120;
+/// Blocks of the charts, from empty to full
+const BLOCKS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Status of a fuzzing worker
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkerStatus {
+    pub id: usize,
+    /// Number of executions of the worker
+    pub executions: u64,
+    pub running: bool,
+}
+
+/// State of a function fuzzed in property testing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FunctionState {
+    Pending,
+    Running,
+    Done,
+}
+
+/// Progress of a function fuzzed in property testing
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionProgress {
+    pub name: String,
+    pub state: FunctionState,
+    /// Number of executions of the function
+    pub executions: u64,
+}
+
+impl FunctionProgress {
+    pub fn new(name: &str) -> Self {
+        FunctionProgress {
+            name: name.to_string(),
+            state: FunctionState::Pending,
+            executions: 0,
+        }
+    }
+}
+
+/// Snapshot of a fuzzing session displayed by the dashboard
+#[derive(Debug, Clone, Default)]
+pub struct DashboardState {
+    pub title: String,
+    /// Seconds since the start of the session
+    pub uptime: f64,
+    pub executions: u64,
+    pub coverage: usize,
+    pub corpus: usize,
+    pub crashes: u64,
+    /// Crashes grouped by error message, with their number of hits
+    pub crash_buckets: Vec<(String, u64)>,
+    pub workers: Vec<WorkerStatus>,
+    /// Functions of the property testing session, empty otherwise
+    pub functions: Vec<FunctionProgress>,
+    /// Input and error of the last crash
+    pub last_crash: Option<String>,
+}
+
+/// Terminal dashboard of a fuzzing session, redrawn with the state of the session
+///
+/// The dashboard keeps the history of the execution speed and of the coverage to
+/// chart them over time. It only uses ANSI escape sequences, the plain line output is
+/// kept when the standard output is not a terminal (see `is_terminal`).
+#[derive(Debug, Clone, Default)]
+pub struct Dashboard {
+    /// Executions per second at each update
+    speed: VecDeque<u64>,
+    /// Coverage at each update
+    coverage: VecDeque<u64>,
+    /// Uptime and executions at the last update
+    last: (f64, u64),
+}
+
+impl Dashboard {
+    /// Records the execution speed and the coverage of the session
+    pub fn update(&mut self, state: &DashboardState) {
+        let (last_uptime, last_executions) = self.last;
+        let elapsed = state.uptime - last_uptime;
+        let speed = match elapsed > 0.0 {
+            true => (state.executions.saturating_sub(last_executions) as f64 / elapsed) as u64,
+            false => 0,
+        };
+        self.last = (state.uptime, state.executions);
+        for (history, value) in [
+            (&mut self.speed, speed),
+            (&mut self.coverage, state.coverage as u64),
+        ] {
+            if history.len() == HISTORY_LEN {
+                history.pop_front();
+            }
+            history.push_back(value);
+        }
+    }
+
+    /// Renders the dashboard on `width` columns
+    pub fn render(&self, state: &DashboardState, width: usize) -> String {
+        let width = width.max(40);
+        let half = width / 2;
+        let mut lines = Vec::new();
+
+        let speed = self.speed.back().copied().unwrap_or_default();
+        lines.extend(boxed(
+            &state.title,
+            &[
+                format!(
+                    "uptime {:.0}s | {} executions | {} exec/s | {} coverage | {} inputs | {} crashes [{} unique]",
+                    state.uptime,
+                    state.executions,
+                    speed,
+                    state.coverage,
+                    state.corpus,
+                    state.crashes,
+                    state.crash_buckets.len()
+                ),
+            ],
+            width,
+        ));
+
+        let speed_chart = chart(&self.speed, half - 2);
+        let coverage_chart = chart(&self.coverage, width - half - 2);
+        lines.extend(side_by_side(
+            boxed(
+                &format!("exec/s (max {})", max(&self.speed)),
+                &speed_chart,
+                half,
+            ),
+            boxed(
+                &format!("coverage ({})", state.coverage),
+                &coverage_chart,
+                width - half,
+            ),
+        ));
+
+        let mut buckets: Vec<&(String, u64)> = state.crash_buckets.iter().collect();
+        buckets.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let mut bucket_lines: Vec<String> = buckets
+            .iter()
+            .take(MAX_ROWS)
+            .map(|(message, hits)| format!("{:>8} {}", hits, message))
+            .collect();
+        if buckets.len() > MAX_ROWS {
+            bucket_lines.push(format!("... {} more", buckets.len() - MAX_ROWS));
+        }
+        let mut worker_lines: Vec<String> = state
+            .workers
+            .iter()
+            .take(MAX_ROWS)
+            .map(|worker| {
+                format!(
+                    "worker {:<3} {:>12} executions  {}",
+                    worker.id,
+                    worker.executions,
+                    if worker.running { "running" } else { "stopped" }
+                )
+            })
+            .collect();
+        if state.workers.len() > MAX_ROWS {
+            worker_lines.push(format!("... {} more", state.workers.len() - MAX_ROWS));
+        }
+        lines.extend(side_by_side(
+            boxed("crash buckets", &bucket_lines, half),
+            boxed("workers", &worker_lines, width - half),
+        ));
+
+        if !state.functions.is_empty() {
+            let done = state
+                .functions
+                .iter()
+                .filter(|function| function.state == FunctionState::Done)
+                .count();
+            let function_lines: Vec<String> = state
+                .functions
+                .iter()
+                .map(|function| {
+                    let status = match function.state {
+                        FunctionState::Pending => "pending",
+                        FunctionState::Running => "running",
+                        FunctionState::Done => "done",
+                    };
+                    format!(
+                        "{:<8} {:>12} executions  {}",
+                        status, function.executions, function.name
+                    )
+                })
+                .collect();
+            lines.extend(boxed(
+                &format!("functions ({}/{} done)", done, state.functions.len()),
+                &function_lines,
+                width,
+            ));
+        }
+
+        let last_crash = state.last_crash.clone().unwrap_or_default();
+        lines.extend(boxed("last crash", &wrap(&last_crash, width - 4, 3), width));
+
+        let mut screen = lines.join("\n");
+        screen.push('\n');
+        screen
+    }
+
+    /// Clears the terminal and draws the dashboard
+    pub fn draw(&self, state: &DashboardState) -> io::Result<()> {
+        let mut stdout = io::stdout().lock();
+        // Move the cursor home and clear the screen
+        write!(
+            stdout,
+            "\x1b[H\x1b[2J{}",
+            self.render(state, terminal_width())
+        )?;
+        stdout.flush()
+    }
+}
+
+/// Returns true if the standard output is a terminal the dashboard can be drawn on
+pub fn is_terminal() -> bool {
+    io::stdout().is_terminal()
+}
+
+/// Returns the number of columns of the terminal
+pub fn terminal_width() -> usize {
+    terminal_columns()
+        .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+/// Number of columns of the terminal of the standard output, from the TIOCGWINSZ ioctl
+#[cfg(unix)]
+fn terminal_columns() -> Option<usize> {
+    let mut size = libc::winsize {
+        ws_row: 0,
+        ws_col: 0,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    // SAFETY: TIOCGWINSZ only writes the window size of the terminal to `size`
+    let ret = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+    match ret == 0 && size.ws_col > 0 {
+        true => Some(size.ws_col as usize),
+        false => None,
+    }
+}
+
+#[cfg(not(unix))]
+fn terminal_columns() -> Option<usize> {
+    None
+}
+
+fn max(history: &VecDeque<u64>) -> u64 {
+    history.iter().copied().max().unwrap_or_default()
+}
+
+/// Charts the last values of the history, one column per value, scaled to the maximum
+fn chart(history: &VecDeque<u64>, width: usize) -> Vec<String> {
+    let values: Vec<u64> = history
+        .iter()
+        .skip(history.len().saturating_sub(width))
+        .copied()
+        .collect();
+    let max = values.iter().copied().max().unwrap_or_default().max(1);
+    (0..CHART_HEIGHT)
+        .rev()
+        .map(|row| {
+            values
+                .iter()
+                .map(|value| {
+                    // Height of the bar in eighths of a row
+                    let eighths = (*value * (CHART_HEIGHT as u64 * 8) / max) as usize;
+                    BLOCKS[eighths.saturating_sub(row * 8).min(8)]
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Draws a box of `width` columns with a title around the lines, truncated to fit
+fn boxed(title: &str, lines: &[String], width: usize) -> Vec<String> {
+    let inner = width.saturating_sub(2);
+    let title: String = format!(" {} ", title).chars().take(inner).collect();
+    let mut boxed = vec![format!(
+        "┌{}{}┐",
+        title,
+        "─".repeat(inner - title.chars().count())
+    )];
+    for line in lines {
+        let line: String = line.chars().take(inner).collect();
+        boxed.push(format!(
+            "│{}{}│",
+            line,
+            " ".repeat(inner - line.chars().count())
+        ));
+    }
+    boxed.push(format!("└{}┘", "─".repeat(inner)));
+    boxed
+}
+
+/// Places two boxes side by side, the shortest one is padded with blank lines
+fn side_by_side(mut left: Vec<String>, mut right: Vec<String>) -> Vec<String> {
+    let pad = |lines: &mut Vec<String>, height: usize| {
+        let width = lines[0].chars().count();
+        let bottom = lines.pop().expect("Empty box");
+        while lines.len() + 1 < height {
+            lines.push(format!("│{}│", " ".repeat(width - 2)));
+        }
+        lines.push(bottom);
+    };
+    let height = left.len().max(right.len());
+    pad(&mut left, height);
+    pad(&mut right, height);
+    left.into_iter()
+        .zip(right)
+        .map(|(left, right)| left + &right)
+        .collect()
+}
+
+/// Splits the text into at most `rows` lines of `width` characters
+fn wrap(text: &str, width: usize, rows: usize) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .chunks(width.max(1))
+        .take(rows)
+        .map(|chunk| chunk.iter().collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dashboard() {
+        let mut state = DashboardState {
+            title: "cairo-fuzzer".to_string(),
+            uptime: 1.0,
+            executions: 1000,
+            coverage: 3,
+            corpus: 3,
+            crashes: 5,
+            crash_buckets: vec![("overflow".to_string(), 1), ("assert".to_string(), 4)],
+            workers: vec![WorkerStatus {
+                id: 0,
+                executions: 1000,
+                running: true,
+            }],
+            functions: vec![FunctionProgress::new("Fuzz_a")],
+            last_crash: Some("worker 0 -- input [1, 2] -- assert".to_string()),
+        };
+        let mut dashboard = Dashboard::default();
+        dashboard.update(&state);
+        state.uptime = 3.0;
+        state.executions = 5000;
+        state.coverage = 6;
+        dashboard.update(&state);
+        assert_eq!(dashboard.speed, [1000, 2000]);
+        assert_eq!(dashboard.coverage, [3, 6]);
+
+        let screen = dashboard.render(&state, 80);
+        let lines: Vec<&str> = screen.lines().collect();
+        // Every line fills the width of the terminal
+        assert!(lines.iter().all(|line| line.chars().count() == 80));
+        assert!(screen.contains("2000 exec/s"));
+        assert!(screen.contains("exec/s (max 2000)"));
+        // The buckets are sorted by hits
+        let assert_line = lines.iter().position(|l| l.contains("assert")).unwrap();
+        let overflow_line = lines.iter().position(|l| l.contains("overflow")).unwrap();
+        assert!(assert_line < overflow_line);
+        assert!(screen.contains("functions (0/1 done)"));
+        assert!(screen.contains("worker 0 -- input [1, 2] -- assert"));
+
+        let chart = chart(&dashboard.coverage, 10);
+        assert_eq!(chart.len(), CHART_HEIGHT);
+        assert_eq!(chart[0], " █");
+        assert_eq!(chart[CHART_HEIGHT - 1], "██");
+    }
+}
@@ -0,0 +1,328 @@
+use std::fmt;
+use std::fs;
+
+use crate::felt::{Field, ParseFeltError};
+use crate::rng::Rng;
+
+/// Arguments a dictionary entry applies to
+#[derive(Debug, Clone, PartialEq)]
+pub enum DictScope {
+    /// Every argument
+    Global,
+    /// The argument at the given index
+    Argument(usize),
+    /// Every argument of the given type, e.g. `core::integer::u8`
+    Type(String),
+}
+
+/// A value of the dictionary
+#[derive(Debug, Clone, PartialEq)]
+pub struct DictEntry<T> {
+    pub value: T,
+    pub scope: DictScope,
+}
+
+/// Dictionary of values used by the mutator
+///
+/// Each line is `key=value`, `key[scope]=value` or a bare `value`, where the scope is an
+/// argument index or an argument type. Values are decimal or hex felts, negative values
+/// (mod p), `'cairo short strings'` or `"strings"`. Lines starting with `#` are comments.
+///
+/// ```text
+/// # Global values
+/// key1=9999999999999999999999999999999
+/// key2=0x1337
+/// owner='admin'
+/// minus_one=-1
+/// # Only for the first argument
+/// amount[0]=1000000
+/// # Only for the u8 arguments
+/// small[core::integer::u8]=255
+/// ```
+pub struct Dict<F: Field> {
+    pub entries: Vec<DictEntry<F::Felt>>,
+}
+
+impl<F: Field> Default for Dict<F> {
+    fn default() -> Self {
+        Dict {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<F: Field> Clone for Dict<F> {
+    fn clone(&self) -> Self {
+        Dict {
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+impl<F: Field> fmt::Debug for Dict<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dict")
+            .field("entries", &self.entries)
+            .finish()
+    }
+}
+
+impl<F: Field> Dict<F> {
+    /// Reads and parses a dictionary file, the error gives the offending line
+    pub fn read_dict(path: &str) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Could not read dictionnary {}: {}", path, e))?;
+        Dict::parse(&contents).map_err(|e| format!("Could not parse dictionnary {}: {}", path, e))
+    }
+
+    /// Parses the content of a dictionary
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut entries = Vec::new();
+        for (line_idx, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry =
+                parse_entry::<F>(line).map_err(|e| format!("line {}: {}", line_idx + 1, e))?;
+            entries.push(entry);
+        }
+        Ok(Dict { entries })
+    }
+
+    /// Writes the dictionary to `path` in the format read by `read_dict`
+    pub fn dump(&self, path: &str) -> Result<(), String> {
+        let mut contents = String::new();
+        for (idx, entry) in self.entries.iter().enumerate() {
+            let scope = match &entry.scope {
+                DictScope::Global => String::new(),
+                DictScope::Argument(arg_idx) => format!("[{}]", arg_idx),
+                DictScope::Type(arg_type) => format!("[{}]", arg_type),
+            };
+            contents.push_str(&format!(
+                "value{}{}=0x{}\n",
+                idx,
+                scope,
+                F::to_hex(&entry.value)
+            ));
+        }
+        fs::write(path, contents)
+            .map_err(|e| format!("Could not write dictionnary {}: {}", path, e))
+    }
+
+    /// Returns the values applicable to the argument at `arg_idx` of type `arg_type`
+    pub fn values_for(&self, arg_idx: usize, arg_type: Option<&str>) -> Vec<&F::Felt> {
+        self.entries
+            .iter()
+            .filter(|entry| match &entry.scope {
+                DictScope::Global => true,
+                DictScope::Argument(idx) => *idx == arg_idx,
+                DictScope::Type(scope_type) => Some(scope_type.as_str()) == arg_type,
+            })
+            .map(|entry| &entry.value)
+            .collect()
+    }
+
+    /// Builds initial inputs so that every value of the dictionary is used at least once
+    /// The other arguments are filled with random values applicable to them
+    pub fn seed_inputs(&self, types: &[String], rng: &Rng) -> Vec<Vec<F::Felt>> {
+        let candidates: Vec<Vec<&F::Felt>> = types
+            .iter()
+            .enumerate()
+            .map(|(idx, arg_type)| self.values_for(idx, Some(arg_type)))
+            .collect();
+
+        let mut inputs = Vec::new();
+        for entry in &self.entries {
+            // Put the value in the first argument it applies to
+            let position = match candidates
+                .iter()
+                .position(|values| values.contains(&&entry.value))
+            {
+                Some(position) => position,
+                None => continue,
+            };
+            let input = candidates
+                .iter()
+                .enumerate()
+                .map(|(idx, values)| {
+                    if idx == position {
+                        entry.value.clone()
+                    } else if values.is_empty() {
+                        F::from_u64(0)
+                    } else {
+                        values[rng.rand_usize() % values.len()].clone()
+                    }
+                })
+                .collect();
+            inputs.push(input);
+        }
+        inputs
+    }
+}
+
+/// Parses a `key=value`, `key[scope]=value` or `value` line
+fn parse_entry<F: Field>(line: &str) -> Result<DictEntry<F::Felt>, String> {
+    // The value may contain `=` inside a string, split on the first one outside of quotes
+    let (key, value) = match line.find('=') {
+        Some(idx) if !line[..idx].contains(['"', '\'']) => (Some(&line[..idx]), &line[idx + 1..]),
+        _ => (None, line),
+    };
+
+    let scope = match key.map(str::trim) {
+        Some(key) if key.ends_with(']') => {
+            let start = key
+                .find('[')
+                .ok_or(format!("missing '[' in key \"{}\"", key))?;
+            let scope = key[start + 1..key.len() - 1].trim();
+            if scope.is_empty() {
+                return Err(format!("empty scope in key \"{}\"", key));
+            }
+            match scope.parse::<usize>() {
+                Ok(idx) => DictScope::Argument(idx),
+                Err(_) => DictScope::Type(scope.to_string()),
+            }
+        }
+        _ => DictScope::Global,
+    };
+
+    Ok(DictEntry {
+        value: parse_value::<F>(value.trim())?,
+        scope,
+    })
+}
+
+/// Parses a decimal, hex, negative, short string or string value into a felt
+pub fn parse_value<F: Field>(value: &str) -> Result<F::Felt, String> {
+    if value.is_empty() {
+        return Err("empty value".to_string());
+    }
+    if let Some(negated) = value.strip_prefix('-') {
+        return Ok(F::neg(&parse_value::<F>(negated.trim())?));
+    }
+    if let Some(string) = strip_quotes(value, '\'').or_else(|| strip_quotes(value, '"')) {
+        let bytes = unescape(string)?;
+        if bytes.len() > 31 {
+            return Err(format!("string {} is longer than 31 bytes", value));
+        }
+        let mut felt = [0; 32];
+        felt[32 - bytes.len()..].copy_from_slice(&bytes);
+        return Ok(F::from_be_bytes(&felt));
+    }
+
+    let number = match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => F::from_str_radix(hex, 16),
+        None => F::from_str_radix(value, 10),
+    };
+    number.map_err(|e| match e {
+        ParseFeltError::InvalidDigit => format!("invalid value {}", value),
+        ParseFeltError::Overflow => {
+            format!("value {} is not smaller than the field prime", value)
+        }
+    })
+}
+
+/// Returns the content of a string delimited by `quote`
+fn strip_quotes(value: &str, quote: char) -> Option<&str> {
+    value
+        .strip_prefix(quote)
+        .and_then(|value| value.strip_suffix(quote))
+}
+
+/// Decodes the `\\`, `\"`, `\'` and `\xNN` escape sequences of a string
+fn unescape(string: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    let mut chars = string.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+        match chars.next() {
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                let byte = u8::from_str_radix(&hex, 16)
+                    .map_err(|_| format!("invalid escape sequence \\x{}", hex))?;
+                bytes.push(byte);
+            }
+            Some(c @ ('\\' | '"' | '\'')) => bytes.push(c as u8),
+            Some(c) => return Err(format!("invalid escape sequence \\{}", c)),
+            None => return Err("string ends with '\\'".to_string()),
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dict, DictEntry, DictScope};
+    use crate::felt::{Field, RawField};
+
+    /// Felt of a short string
+    fn short_string(string: &[u8]) -> [u8; 32] {
+        let mut felt = [0; 32];
+        felt[32 - string.len()..].copy_from_slice(string);
+        felt
+    }
+
+    #[test]
+    fn test_parse_dict() {
+        let dict = Dict::<RawField>::parse(
+            "# comment\n\
+             key1=9999999999999999999999999999999\n\
+             \n\
+             key2 = 0x1337\n\
+             owner='admin'\n\
+             name=\"a=b\\x00\"\n\
+             minus_one=-1\n\
+             amount[0]=1000000\n\
+             small[core::integer::u8]=255\n\
+             42\n",
+        )
+        .expect("Could not parse dict");
+
+        let values: Vec<[u8; 32]> = dict.entries.iter().map(|e| e.value).collect();
+        assert_eq!(
+            values,
+            vec![
+                RawField::from_str_radix("9999999999999999999999999999999", 10).unwrap(),
+                RawField::from_u64(0x1337),
+                short_string(b"admin"),
+                short_string(b"a=b\x00"),
+                RawField::from_i64(-1),
+                RawField::from_u64(1000000),
+                RawField::from_u64(255),
+                RawField::from_u64(42),
+            ]
+        );
+        assert_eq!(dict.entries[5].scope, DictScope::Argument(0));
+        assert_eq!(
+            dict.entries[6].scope,
+            DictScope::Type("core::integer::u8".to_string())
+        );
+        assert_eq!(dict.values_for(1, Some("core::integer::u8")).len(), 7);
+        assert_eq!(dict.values_for(1, Some("core::felt252")).len(), 6);
+    }
+
+    #[test]
+    fn test_parse_dict_errors() {
+        assert!(Dict::<RawField>::parse("key=abc").is_err());
+        assert!(Dict::<RawField>::parse("key='this string is way too long for a felt'").is_err());
+        assert!(Dict::<RawField>::parse(
+            "key=0x800000000000011000000000000000000000000000000000000000000000001"
+        )
+        .is_err());
+        assert!(Dict::<RawField>::parse("key[]=1").is_err());
+        assert_eq!(
+            Dict::<RawField>::parse("key=1").unwrap().entries,
+            vec![DictEntry {
+                value: RawField::from_u64(1),
+                scope: DictScope::Global
+            }]
+        );
+    }
+}
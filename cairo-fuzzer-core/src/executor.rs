@@ -0,0 +1,351 @@
+//! Mutation and execution of the fuzzed inputs, shared by the cairo-vm and cairo-native fuzzers
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::corpus::Corpus;
+use crate::dict::Dict;
+use crate::felt::Field;
+use crate::mutator::{MutationStrategy, Mutator};
+use crate::resources::{ExecutionCost, ResourceLimits};
+use crate::rng::Rng;
+use crate::runner::{ExecutionResult, Runner};
+use crate::schedule::{trace_edges, Schedule, Scheduler, SeedMeta};
+
+/// Recovers values of an execution worth splicing into the inputs, e.g. the operands
+/// of its comparisons (CmpLog)
+pub trait CmpFeedback<F> {
+    /// Values compared by the execution of `input` which reached `coverage`
+    fn operands(&mut self, coverage: &[u64], input: &[F]) -> Vec<F>;
+}
+
+/// Execution of a single input
+#[derive(Debug, Clone)]
+pub struct Step<F> {
+    /// Executed input
+    pub input: Vec<F>,
+    /// Result of the execution, `Err` if the runner could not execute the function
+    pub result: Result<ExecutionResult<F>, String>,
+    /// Corpus entry the input was mutated from, if any
+    pub parent: Option<Vec<F>>,
+    /// Names of the strategies used to mutate the input, comma separated
+    pub mutation: String,
+    /// Coverage points given to the corpus
+    pub coverage: Vec<u64>,
+    /// Cost of the execution
+    pub cost: ExecutionCost,
+    /// Set if the input was added to the corpus, for its coverage or for its cost
+    pub new_coverage: bool,
+    /// Set when maximizing the cost and the input has the highest cost so far
+    pub max_cost: bool,
+    /// Set if the cost of the execution exceeds the resource limits
+    pub exhausted: bool,
+}
+
+/// Picks, mutates and executes the inputs of the fuzzed function on a runner
+///
+/// The inputs reaching new coverage are added to the corpus given to `step`, the
+/// engine crates triage the crashes and the resource exhaustions from the returned
+/// `Step`.
+pub struct Executor<R: Runner + Clone> {
+    runner: R,
+    rng: Rng,
+    mutator: Mutator<R::Field>,
+    /// Recovers the comparison operands of the executions, if set
+    cmplog: Option<Box<dyn CmpFeedback<R::Felt>>>,
+    /// Power schedule of the corpus entries
+    schedule: Schedule,
+    /// Corpus entries picked by the power schedule, unused by the uniform schedule
+    scheduler: Scheduler<Vec<R::Felt>>,
+    /// Resource exhaustion detection settings
+    limits: ResourceLimits,
+    /// Length of the inputs, the mutated inputs are resized to it if set
+    input_len: Option<usize>,
+    /// Input mutated while the corpus is empty
+    initial_input: Vec<R::Felt>,
+    /// The coverage of an execution is the hash of its coverage points, every new path
+    /// is kept instead of every new point
+    path_coverage: bool,
+    /// Highest cost of an execution so far
+    max_cost: u64,
+}
+
+impl<R: Runner + Clone> Executor<R> {
+    /// Creates an executor running the inputs mutated by `mutator` on `runner`
+    pub fn new(runner: R, mutator: Mutator<R::Field>, seed: u64) -> Self {
+        Executor {
+            runner,
+            rng: Rng::seeded(seed),
+            mutator: mutator.seed(seed),
+            cmplog: None,
+            schedule: Schedule::Uniform,
+            scheduler: Scheduler::new(Schedule::Uniform),
+            limits: ResourceLimits::default(),
+            input_len: None,
+            initial_input: Vec::new(),
+            path_coverage: false,
+            max_cost: 0,
+        }
+    }
+
+    /// Sets the seed of the mutator and of the corpus selection
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = Rng::seeded(seed);
+        self.mutator = self.mutator.seed(seed);
+        self
+    }
+
+    /// Replaces the runner, e.g. with a runner loaded with a state dump
+    pub fn runner(mut self, runner: R) -> Self {
+        self.runner = runner;
+        self
+    }
+
+    /// Feeds the comparison operands recovered by `cmplog` to the mutator
+    pub fn cmplog(mut self, cmplog: Box<dyn CmpFeedback<R::Felt>>) -> Self {
+        self.cmplog = Some(cmplog);
+        self
+    }
+
+    /// Sets the dictionnary used by the mutator
+    pub fn dict(mut self, dict: Dict<R::Field>) -> Self {
+        self.mutator = self.mutator.dict(dict);
+        self
+    }
+
+    /// Registers a custom mutation strategy
+    pub fn strategy(mut self, strategy: Box<dyn MutationStrategy<R::Field>>) -> Self {
+        self.mutator = self.mutator.strategy(strategy);
+        self
+    }
+
+    /// Sets the power schedule of the corpus entries
+    pub fn schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = schedule;
+        self.scheduler = Scheduler::new(schedule);
+        self
+    }
+
+    /// Sets the resource exhaustion detection settings
+    pub fn limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Fixes the length of the inputs, the initial input is made of zeros
+    pub fn input_len(mut self, input_len: usize) -> Self {
+        self.input_len = Some(input_len);
+        self.initial_input = vec![R::Field::from_u64(0); input_len];
+        self
+    }
+
+    /// Sets the input mutated while the corpus is empty
+    pub fn initial_input(mut self, input: Vec<R::Felt>) -> Self {
+        self.initial_input = input;
+        self
+    }
+
+    /// Keeps every new execution path instead of every new coverage point
+    pub fn path_coverage(mut self, path_coverage: bool) -> Self {
+        self.path_coverage = path_coverage;
+        self
+    }
+
+    /// Mutator of the executor, e.g. to collect the results of the strategies
+    pub fn mutator(&mut self) -> &mut Mutator<R::Field> {
+        &mut self.mutator
+    }
+
+    /// Raises the highest cost so far, e.g. to the cost reached by the other workers
+    pub fn set_max_cost(&mut self, max_cost: u64) {
+        self.max_cost = self.max_cost.max(max_cost);
+    }
+
+    /// Mutates an input of `corpus` and executes it
+    pub fn step(&mut self, corpus: &mut Corpus<R::Felt>) -> Step<R::Felt> {
+        let input = self.pick(corpus);
+        // Corpus entry the input is mutated from
+        let parent = match corpus.contains(&input) {
+            true => Some(input.clone()),
+            false => None,
+        };
+
+        // Corrupt it with 4 mutation passes, splicing from the known inputs
+        self.mutator.input.clear();
+        self.mutator.input.extend_from_slice(&input);
+        self.mutator.mutate(4, &*corpus);
+        // Custom strategies may change the length, the VM runner takes one felt per argument
+        if let Some(input_len) = self.input_len {
+            self.mutator.input.resize(input_len, R::Field::from_u64(0));
+        }
+
+        let input = self.mutator.input.clone();
+        let mutation = self.mutator.last_strategies().join(",");
+        self.run(input, parent, mutation, corpus)
+    }
+
+    /// Executes `input` without mutating it, e.g. to replay a corpus
+    pub fn execute(&mut self, input: Vec<R::Felt>, corpus: &mut Corpus<R::Felt>) -> Step<R::Felt> {
+        self.run(input, None, String::new(), corpus)
+    }
+
+    /// Picks the input to mutate, the initial input if the corpus is empty
+    fn pick(&mut self, corpus: &Corpus<R::Felt>) -> Vec<R::Felt> {
+        if self.schedule != Schedule::Uniform {
+            // Add the corpus entries found by the other workers
+            if self.scheduler.len() < corpus.len() {
+                for input in corpus.inputs() {
+                    self.scheduler.add(input.clone(), corpus.meta(input));
+                }
+            }
+            match self.scheduler.pick(&self.rng) {
+                Some(input) => input.clone(),
+                None => self.initial_input.clone(),
+            }
+        } else {
+            match corpus.pick(&self.rng) {
+                Some(input) => input.clone(),
+                None => self.initial_input.clone(),
+            }
+        }
+    }
+
+    /// Executes `input` and adds it to `corpus` if it reached new coverage
+    fn run(
+        &mut self,
+        input: Vec<R::Felt>,
+        parent: Option<Vec<R::Felt>>,
+        mutation: String,
+        corpus: &mut Corpus<R::Felt>,
+    ) -> Step<R::Felt> {
+        let mut step = Step {
+            input,
+            result: Err(String::new()),
+            parent,
+            mutation,
+            coverage: Vec::new(),
+            cost: ExecutionCost::default(),
+            new_coverage: false,
+            max_cost: false,
+            exhausted: false,
+        };
+        // The runner is consumed by the execution, keep it if the execution fails
+        let result = match self.runner.clone().run(&step.input) {
+            Ok((runner, result)) => {
+                self.runner = runner;
+                result
+            }
+            Err(e) => {
+                step.result = Err(e);
+                return step;
+            }
+        };
+
+        step.cost = ExecutionCost::from_result(&result);
+        if step.cost.steps > self.max_cost {
+            self.max_cost = step.cost.steps;
+            step.max_cost = self.limits.maximize;
+        }
+        // Resource exhaustion: the input executed more steps than the threshold
+        step.exhausted = self.limits.exceeded(&step.cost);
+
+        // Edges and cost of the execution, used by the power schedules
+        let meta = if self.schedule != Schedule::Uniform {
+            let edges = trace_edges(result.coverage.iter().map(|pc| *pc as usize));
+            self.scheduler.observe(&edges);
+            SeedMeta {
+                edges,
+                steps: step.cost.steps,
+            }
+        } else {
+            SeedMeta::default()
+        };
+
+        step.coverage = match self.path_coverage {
+            true => {
+                let mut hasher = DefaultHasher::new();
+                result.coverage.hash(&mut hasher);
+                vec![hasher.finish()]
+            }
+            false => result.coverage.clone(),
+        };
+        // Keep the input if it reached new coverage, or the highest cost when maximizing
+        let new_coverage = corpus.add(&step.input, &step.coverage);
+        if new_coverage || step.max_cost {
+            corpus.add_input(&step.input);
+            step.new_coverage = true;
+            // Credit the strategies that produced this input
+            self.mutator.report(true);
+
+            // Schedule the new entry
+            if self.schedule != Schedule::Uniform {
+                self.scheduler.add(step.input.clone(), meta.clone());
+                corpus.set_meta(&step.input, meta);
+            }
+
+            // The new path may have reached new comparisons, feed their operands to the mutator
+            if let Some(cmplog) = &mut self.cmplog {
+                self.mutator
+                    .add_cmp_values(cmplog.operands(&result.coverage, &step.input));
+            }
+        }
+        step.result = Ok(result);
+        step
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::felt::RawField;
+
+    /// Runner whose coverage is the first felt of the input, and whose cost is its
+    /// second felt
+    #[derive(Clone)]
+    struct TestRunner;
+
+    impl Runner for TestRunner {
+        type Felt = [u8; 32];
+        type Field = RawField;
+
+        fn run(self, data: &[[u8; 32]]) -> Result<(Self, ExecutionResult<[u8; 32]>), String> {
+            let result = ExecutionResult {
+                failure_flag: data[0][31] == 7,
+                coverage: vec![data[0][31] as u64],
+                steps: data[1][31] as u64,
+                ..Default::default()
+            };
+            Ok((self, result))
+        }
+    }
+
+    #[test]
+    fn test_executor_corpus() {
+        let felt = RawField::from_u64;
+        let mut corpus = Corpus::default();
+        let mut executor = Executor::new(TestRunner, Mutator::new(), 1)
+            .input_len(2)
+            .limits(ResourceLimits {
+                max_steps: 100,
+                maximize: true,
+            });
+
+        let step = executor.execute(vec![felt(1), felt(50)], &mut corpus);
+        assert!(step.new_coverage && step.max_cost && !step.exhausted);
+        // Same coverage, lower cost
+        let step = executor.execute(vec![felt(1), felt(10)], &mut corpus);
+        assert!(!step.new_coverage && !step.max_cost);
+        // Same coverage, higher cost
+        let step = executor.execute(vec![felt(1), felt(150)], &mut corpus);
+        assert!(step.new_coverage && step.max_cost && step.exhausted);
+        assert_eq!(corpus.len(), 2);
+
+        // The mutated inputs keep their length and come from the corpus
+        for _ in 0..100 {
+            let step = executor.step(&mut corpus);
+            assert_eq!(step.input.len(), 2);
+            assert!(step.parent.is_some());
+            assert!(step.result.is_ok());
+        }
+        assert!(corpus.coverage() > 1);
+    }
+}
@@ -0,0 +1,244 @@
+//! Felts of the engines, as elements of the Starknet prime field
+//!
+//! Each engine comes with its own felt type, the engine crates implement `Field` on a
+//! marker type to convert their felts from and to their big endian encoding. The field
+//! arithmetic used by the mutator and the dictionary is implemented here on top of it.
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// Prime of the field, P = 2^251 + 17 * 2^192 + 1, as little endian 64-bit limbs
+const PRIME: [u64; 4] = [1, 0, 0, 0x0800_0000_0000_0011];
+
+/// Error of `Field::from_str_radix`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseFeltError {
+    /// The number is empty or contains a character which is not a digit of the radix
+    InvalidDigit,
+    /// The number is not smaller than the field prime
+    Overflow,
+}
+
+/// Felt type of an engine, seen as an element of the field of prime P
+pub trait Field: 'static {
+    /// Felt type of the engine
+    type Felt: Clone + Debug + Eq + Hash;
+
+    /// Big endian encoding of the felt, smaller than P
+    fn to_be_bytes(felt: &Self::Felt) -> [u8; 32];
+
+    /// Felt of a big endian encoded value, reduced modulo P
+    fn from_be_bytes(bytes: &[u8; 32]) -> Self::Felt;
+
+    /// Felt of an unsigned value
+    fn from_u64(value: u64) -> Self::Felt {
+        let mut bytes = [0; 32];
+        bytes[24..].copy_from_slice(&value.to_be_bytes());
+        Self::from_be_bytes(&bytes)
+    }
+
+    /// Felt of a signed value, the negative values are represented modulo P
+    fn from_i64(value: i64) -> Self::Felt {
+        let felt = Self::from_u64(value.unsigned_abs());
+        match value < 0 {
+            true => Self::neg(&felt),
+            false => felt,
+        }
+    }
+
+    /// a + b modulo P
+    fn add(a: &Self::Felt, b: &Self::Felt) -> Self::Felt {
+        let (sum, _) = add_limbs(&limbs(&Self::to_be_bytes(a)), &limbs(&Self::to_be_bytes(b)));
+        Self::from_be_bytes(&from_limbs(&reduce(sum)))
+    }
+
+    /// a - b modulo P
+    fn sub(a: &Self::Felt, b: &Self::Felt) -> Self::Felt {
+        let (a, b) = (limbs(&Self::to_be_bytes(a)), limbs(&Self::to_be_bytes(b)));
+        let (mut diff, borrow) = sub_limbs(&a, &b);
+        if borrow {
+            // a - b + 2^256 + P wraps around to a - b + P
+            diff = add_limbs(&diff, &PRIME).0;
+        }
+        Self::from_be_bytes(&from_limbs(&diff))
+    }
+
+    /// -a modulo P
+    fn neg(a: &Self::Felt) -> Self::Felt {
+        Self::sub(&Self::from_u64(0), a)
+    }
+
+    /// 2^bits modulo P
+    fn pow2(bits: u32) -> Self::Felt {
+        let mut felt = Self::from_u64(1);
+        for _ in 0..bits {
+            felt = Self::add(&felt, &felt);
+        }
+        felt
+    }
+
+    /// Parses a number written in base `radix`, without sign or prefix
+    fn from_str_radix(digits: &str, radix: u32) -> Result<Self::Felt, ParseFeltError> {
+        if digits.is_empty() {
+            return Err(ParseFeltError::InvalidDigit);
+        }
+        let mut value = [0u64; 4];
+        for c in digits.chars() {
+            let digit = c.to_digit(radix).ok_or(ParseFeltError::InvalidDigit)?;
+            // value * radix + digit, limb by limb
+            let mut carry = digit as u128;
+            for limb in value.iter_mut() {
+                let product = *limb as u128 * radix as u128 + carry;
+                *limb = product as u64;
+                carry = product >> 64;
+            }
+            if carry != 0 || !less_than(&value, &PRIME) {
+                return Err(ParseFeltError::Overflow);
+            }
+        }
+        Ok(Self::from_be_bytes(&from_limbs(&value)))
+    }
+
+    /// Hexadecimal representation of the felt, without prefix nor leading zeros
+    fn to_hex(felt: &Self::Felt) -> String {
+        let hex: String = Self::to_be_bytes(felt)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        match hex.trim_start_matches('0') {
+            "" => "0".to_string(),
+            hex => hex.to_string(),
+        }
+    }
+}
+
+/// A field whose felts are their big endian encoding, used when the engine has no
+/// felt type of its own
+pub struct RawField;
+
+impl Field for RawField {
+    type Felt = [u8; 32];
+
+    fn to_be_bytes(felt: &[u8; 32]) -> [u8; 32] {
+        *felt
+    }
+
+    fn from_be_bytes(bytes: &[u8; 32]) -> [u8; 32] {
+        from_limbs(&reduce(limbs(bytes)))
+    }
+}
+
+fn limbs(bytes: &[u8; 32]) -> [u64; 4] {
+    let mut limbs = [0u64; 4];
+    for (idx, limb) in limbs.iter_mut().enumerate() {
+        let start = 24 - idx * 8;
+        *limb = u64::from_be_bytes(bytes[start..start + 8].try_into().unwrap());
+    }
+    limbs
+}
+
+fn from_limbs(limbs: &[u64; 4]) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    for (idx, limb) in limbs.iter().enumerate() {
+        let start = 24 - idx * 8;
+        bytes[start..start + 8].copy_from_slice(&limb.to_be_bytes());
+    }
+    bytes
+}
+
+fn add_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], bool) {
+    let mut sum = [0u64; 4];
+    let mut carry = false;
+    for idx in 0..4 {
+        let (limb, c1) = a[idx].overflowing_add(b[idx]);
+        let (limb, c2) = limb.overflowing_add(carry as u64);
+        sum[idx] = limb;
+        carry = c1 || c2;
+    }
+    (sum, carry)
+}
+
+fn sub_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], bool) {
+    let mut diff = [0u64; 4];
+    let mut borrow = false;
+    for idx in 0..4 {
+        let (limb, b1) = a[idx].overflowing_sub(b[idx]);
+        let (limb, b2) = limb.overflowing_sub(borrow as u64);
+        diff[idx] = limb;
+        borrow = b1 || b2;
+    }
+    (diff, borrow)
+}
+
+fn less_than(a: &[u64; 4], b: &[u64; 4]) -> bool {
+    a.iter().rev().cmp(b.iter().rev()).is_lt()
+}
+
+/// Reduces a 256-bit value modulo P
+fn reduce(mut value: [u64; 4]) -> [u64; 4] {
+    // 2^256 / P is about 32, the loop is short
+    while !less_than(&value, &PRIME) {
+        value = sub_limbs(&value, &PRIME).0;
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_arithmetic() {
+        let p_minus_one = RawField::neg(&RawField::from_u64(1));
+        assert_eq!(
+            RawField::to_hex(&p_minus_one),
+            "800000000000011000000000000000000000000000000000000000000000000"
+        );
+        assert_eq!(
+            RawField::add(&p_minus_one, &RawField::from_u64(3)),
+            RawField::from_u64(2)
+        );
+        assert_eq!(
+            RawField::sub(&RawField::from_u64(1), &RawField::from_u64(2)),
+            p_minus_one
+        );
+        assert_eq!(RawField::from_i64(-1), p_minus_one);
+        assert_eq!(
+            RawField::to_hex(&RawField::pow2(128)),
+            format!("1{}", "0".repeat(32))
+        );
+        // 2^256 reduced modulo P
+        assert_eq!(
+            RawField::from_be_bytes(&[0xff; 32]),
+            RawField::sub(&RawField::pow2(256), &RawField::from_u64(1))
+        );
+        assert_eq!(RawField::to_hex(&RawField::from_u64(0)), "0");
+
+        assert_eq!(
+            RawField::from_str_radix("1337", 16),
+            Ok(RawField::from_u64(0x1337))
+        );
+        assert_eq!(
+            RawField::from_str_radix("42", 10),
+            Ok(RawField::from_u64(42))
+        );
+        assert_eq!(
+            RawField::from_str_radix("", 10),
+            Err(ParseFeltError::InvalidDigit)
+        );
+        assert_eq!(
+            RawField::from_str_radix("4a", 10),
+            Err(ParseFeltError::InvalidDigit)
+        );
+        assert_eq!(
+            RawField::from_str_radix(&RawField::to_hex(&p_minus_one), 16),
+            Ok(p_minus_one)
+        );
+        assert_eq!(
+            RawField::from_str_radix(
+                "800000000000011000000000000000000000000000000000000000000000001",
+                16
+            ),
+            Err(ParseFeltError::Overflow)
+        );
+    }
+}
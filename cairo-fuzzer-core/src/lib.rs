@@ -0,0 +1,22 @@
+//! Engine independent parts of the Cairo fuzzers
+//!
+//! The cairo-vm fuzzer (`cairo-fuzzer`) and the cairo-native fuzzer
+//! (`cairo-native-fuzzer`) implement the `Runner` trait for their engine and share the
+//! mutator, the fuzz loop, the corpus, the crash triage, the power schedules, the
+//! dictionaries, the source coverage reports and the stats logs. The felt type is a
+//! parameter since each engine comes with its own, see `felt::Field`.
+pub mod corpus;
+pub mod coverage;
+pub mod crashes;
+pub mod dashboard;
+pub mod dict;
+pub mod executor;
+pub mod felt;
+pub mod metrics;
+pub mod mutator;
+pub mod plot;
+pub mod resources;
+pub mod rng;
+pub mod runner;
+pub mod schedule;
+pub mod stats_log;
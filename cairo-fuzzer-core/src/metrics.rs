@@ -0,0 +1,272 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// A worker is reported down when it did not run an execution for this number of seconds
+pub const WORKER_TIMEOUT: f64 = 10.0;
+/// Time given to a client to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Executions and heartbeat of a fuzzing worker
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkerMetrics {
+    pub id: usize,
+    pub executions: u64,
+    /// Seconds since the last execution of the worker
+    pub idle: f64,
+    /// Set when the worker finished its session or was stopped
+    pub stopped: bool,
+}
+
+impl WorkerMetrics {
+    /// Returns true if the worker is running and executed an input recently
+    pub fn is_up(&self) -> bool {
+        !self.stopped && self.idle < WORKER_TIMEOUT
+    }
+}
+
+/// Metrics of a running fuzzing session, exposed in the Prometheus text format
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metrics {
+    /// Fuzzed function, set as the `function` label of every metric
+    pub function: String,
+    /// Seconds since the start of the session
+    pub uptime: f64,
+    pub executions: u64,
+    /// Executions per second over the last update interval
+    pub execs_per_sec: f64,
+    pub coverage: usize,
+    /// Number of inputs of the corpus
+    pub corpus: usize,
+    pub crashes: u64,
+    pub unique_crashes: usize,
+    pub workers: Vec<WorkerMetrics>,
+}
+
+impl Metrics {
+    /// Formats the metrics in the Prometheus text exposition format
+    pub fn to_prometheus(&self) -> String {
+        let function = format!("function=\"{}\"", escape_label(&self.function));
+        let mut text = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, samples: &[(String, f64)]| {
+            let _ = writeln!(text, "# HELP cairo_fuzzer_{} {}", name, help);
+            let _ = writeln!(text, "# TYPE cairo_fuzzer_{} {}", name, kind);
+            for (labels, value) in samples {
+                let _ = writeln!(text, "cairo_fuzzer_{}{{{}}} {}", name, labels, value);
+            }
+        };
+        let session = |value: f64| vec![(function.clone(), value)];
+        let per_worker = |value: &dyn Fn(&WorkerMetrics) -> f64| {
+            self.workers
+                .iter()
+                .map(|worker| {
+                    let labels = format!("{},worker=\"{}\"", function, worker.id);
+                    (labels, value(worker))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        metric(
+            "uptime_seconds",
+            "gauge",
+            "Seconds since the start of the fuzzing session.",
+            &session(self.uptime),
+        );
+        metric(
+            "executions_total",
+            "counter",
+            "Executions of the fuzzed function.",
+            &session(self.executions as f64),
+        );
+        metric(
+            "executions_per_second",
+            "gauge",
+            "Executions per second over the last second.",
+            &session(self.execs_per_sec),
+        );
+        metric(
+            "coverage",
+            "gauge",
+            "Coverage points reached by the corpus.",
+            &session(self.coverage as f64),
+        );
+        metric(
+            "corpus_size",
+            "gauge",
+            "Inputs of the corpus.",
+            &session(self.corpus as f64),
+        );
+        metric(
+            "crashes_total",
+            "counter",
+            "Crashing executions.",
+            &session(self.crashes as f64),
+        );
+        metric(
+            "unique_crashes",
+            "gauge",
+            "Distinct crashing inputs.",
+            &session(self.unique_crashes as f64),
+        );
+        metric(
+            "worker_executions_total",
+            "counter",
+            "Executions of each worker.",
+            &per_worker(&|worker| worker.executions as f64),
+        );
+        metric(
+            "worker_idle_seconds",
+            "gauge",
+            "Seconds since the last execution of each worker.",
+            &per_worker(&|worker| worker.idle),
+        );
+        metric(
+            "worker_up",
+            "gauge",
+            "1 if the worker is running and executed an input recently, 0 otherwise.",
+            &per_worker(&|worker| worker.is_up() as u8 as f64),
+        );
+        text
+    }
+}
+
+/// Heartbeats of the workers, derived from the progress of their executions counters
+#[derive(Debug, Clone, Default)]
+pub struct Heartbeats {
+    /// Executions of each worker and uptime of its last progress
+    last: BTreeMap<usize, (u64, f64)>,
+}
+
+impl Heartbeats {
+    /// Records the executions of the workers at `uptime` and returns their metrics
+    pub fn update(
+        &mut self,
+        uptime: f64,
+        executions: &BTreeMap<usize, u64>,
+        stopped: bool,
+    ) -> Vec<WorkerMetrics> {
+        executions
+            .iter()
+            .map(|(id, executions)| {
+                let last = self.last.entry(*id).or_insert((*executions, uptime));
+                if last.0 != *executions {
+                    *last = (*executions, uptime);
+                }
+                WorkerMetrics {
+                    id: *id,
+                    executions: *executions,
+                    idle: uptime - last.1,
+                    stopped,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Serves the metrics on `GET /metrics` from a background thread
+/// Returns the address of the server, to get the port when binding to port 0
+pub fn serve(addr: &str, metrics: Arc<Mutex<Metrics>>) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind(addr)?;
+    let local_addr = listener.local_addr()?;
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            // A failed connection does not stop the server
+            let _ = handle(stream, &metrics);
+        }
+    });
+    Ok(local_addr)
+}
+
+/// Answers a request of the metrics endpoint
+fn handle(stream: TcpStream, metrics: &Mutex<Metrics>) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Skip the headers
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => (
+            "200 OK",
+            metrics
+                .lock()
+                .map(|metrics| metrics.to_prometheus())
+                .unwrap_or_default(),
+        ),
+        (Some("GET"), _) => ("404 Not Found", "Not Found\n".to_string()),
+        _ => ("405 Method Not Allowed", "Method Not Allowed\n".to_string()),
+    };
+    let mut stream = &stream;
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// Escapes a Prometheus label value
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_metrics() {
+        let mut heartbeats = Heartbeats::default();
+        let executions: BTreeMap<usize, u64> = [(0, 10), (1, 5)].into_iter().collect();
+        heartbeats.update(1.0, &executions, false);
+        let executions: BTreeMap<usize, u64> = [(0, 20), (1, 5)].into_iter().collect();
+        let workers = heartbeats.update(12.0, &executions, false);
+        assert_eq!(workers[0].idle, 0.0);
+        assert!(workers[0].is_up());
+        assert_eq!(workers[1].idle, 11.0);
+        assert!(!workers[1].is_up());
+
+        let metrics = Arc::new(Mutex::new(Metrics {
+            function: "Fuzz_\"a\"".to_string(),
+            executions: 25,
+            unique_crashes: 2,
+            workers,
+            ..Default::default()
+        }));
+        let text = metrics.lock().unwrap().to_prometheus();
+        assert!(text.contains("# TYPE cairo_fuzzer_executions_total counter\n"));
+        assert!(text.contains("cairo_fuzzer_executions_total{function=\"Fuzz_\\\"a\\\"\"} 25\n"));
+        assert!(text.contains("cairo_fuzzer_unique_crashes{function=\"Fuzz_\\\"a\\\"\"} 2\n"));
+        assert!(
+            text.contains("cairo_fuzzer_worker_up{function=\"Fuzz_\\\"a\\\"\",worker=\"1\"} 0\n")
+        );
+
+        let addr = serve("127.0.0.1:0", metrics.clone()).unwrap();
+        let get = |path: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let response = get("/metrics");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with(&text));
+        assert!(get("/").starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
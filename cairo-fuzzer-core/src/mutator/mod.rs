@@ -0,0 +1,17 @@
+//! Mutator of the fuzzed inputs, generic over the felts of the engine
+mod magic_values;
+pub mod mutator_felt252;
+pub mod strategy;
+
+pub use mutator_felt252::{EmptyDatabase, InputDatabase, Mutator};
+pub use strategy::{MutationStrategy, StrategyFactory};
+
+/// Results of a mutation strategy
+#[derive(Default, Debug, Clone, Copy)]
+pub struct StrategyStats {
+    /// Number of times the strategy was used to mutate an input
+    pub uses: u64,
+
+    /// Number of mutated inputs that reached new coverage
+    pub finds: u64,
+}
@@ -0,0 +1,1329 @@
+//! Basic fuzzer mutation strategies, largely ported from honggfuzz
+/*
+ *
+ * Authors:
+ * Robert Swiecki <swiecki@google.com>
+ * Brandon Falk <bfalk@gamozolabs.com>
+ *
+ * Copyright 2010-2018 by Google Inc. All Rights Reserved.
+ * Copyright 2020 by Brandon Falk <bfalk@gamozolabs.com>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License"); you may
+ * not use this file except in compliance with the License. You may obtain
+ * a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or
+ * implied. See the License for the specific language governing
+ * permissions and limitations under the License.
+ *
+ */
+extern crate alloc;
+
+use super::magic_values;
+use super::strategy::{Builtin, BuiltinSplice, MutationStrategy};
+use super::StrategyStats;
+use crate::dict::Dict;
+use crate::felt::{Field, RawField};
+use alloc::vec::Vec;
+use magic_values::MAGIC_VALUES;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+/// An empty database that never returns an input, useful for fuzzers without
+/// corpuses or input databases.
+pub struct EmptyDatabase;
+
+impl<T> InputDatabase<T> for EmptyDatabase {
+    fn num_inputs(&self) -> usize {
+        0
+    }
+    fn input(&self, _idx: usize) -> Option<Vec<T>> {
+        None
+    }
+}
+
+/// Routines to generically access a corpus/input database for a fuzzer. It's
+/// up to the database to implement this trait, allowing generic access to
+/// the number of inputs in the database, and accessors for a specific input.
+///
+/// The inputs should have zero-indexed IDs such that any input in the range of
+/// [0, self.num_inputs()) should be a valid input.
+///
+/// If the `idx` does not lead to the same input each run, the determinism of
+/// the mutator is unstable and can produce different results across different
+/// runs.
+pub trait InputDatabase<T> {
+    /// Get the number of inputs in the database
+    fn num_inputs(&self) -> usize;
+
+    /// Get an input with a specific zero-index identifier
+    /// If the `idx` is invalid or otherwise not available, this returns `None`
+    fn input(&self, idx: usize) -> Option<Vec<T>>;
+}
+
+/// A basic random number generator based on xorshift64 with 64-bits of state
+struct Rng {
+    /// The RNG's seed and state
+    seed: u64,
+
+    /// If set, `rand_exp` behaves the same as `rand`
+    exp_disabled: bool,
+}
+
+impl Rng {
+    /// Generate a random number
+    #[inline]
+    fn next(&mut self) -> u64 {
+        let val = self.seed;
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 43;
+        val
+    }
+
+    /// Generates a random number with uniform distribution in the range of
+    /// [min, max]
+    #[inline]
+    fn rand(&mut self, min: usize, max: usize) -> usize {
+        // Make sure the range is sane
+        assert!(max >= min, "Bad range specified for rand()");
+
+        // If there is no range, just return `min`
+        if min == max {
+            return min;
+        }
+
+        // If the range is unbounded, just return a random number
+        if min == 0 && max == usize::MAX {
+            return self.next() as usize;
+        }
+
+        // Pick a random number in the range
+        min + (self.next() as usize % (max - min + 1))
+    }
+
+    /// Generates a random number with exponential distribution in the range of
+    /// [min, max] with a worst case deviation from uniform of 0.5x. Meaning
+    /// this will always return uniform at least half the time.
+    #[inline]
+    fn rand_exp(&mut self, min: usize, max: usize) -> usize {
+        // If exponential random is disabled, fall back to uniform
+        if self.exp_disabled {
+            return self.rand(min, max);
+        }
+
+        if self.rand(0, 1) == 0 {
+            // Half the time, provide uniform
+            self.rand(min, max)
+        } else {
+            // Pick an exponentially difficult random number
+            let x = self.rand(min, max);
+            self.rand(min, x)
+        }
+    }
+}
+
+/// A mutator, a playground for corrupting the public `input` vector when
+/// `mutate` is invoked
+///
+/// The felts are those of the engine implementing `F`, the strategies go through the
+/// field arithmetic of `Field` so the same mutations run on cairo-vm and cairo-native.
+pub struct Mutator<F: Field> {
+    /// Input vector to mutate, this is just an entire input files bytes
+    ///
+    /// It is strongly recommended that you do `input.clear()` and
+    /// `input.extend_from_slice()` to update this buffer, to prevent the
+    /// backing from being deallocated and reallocated.
+    pub input: Vec<F::Felt>,
+    pub types: Vec<String>,
+    /// If non-zero length, this contains a list of valid indicies into
+    /// `input`, indicating which bytes of the input should mutated. This often
+    /// comes from instrumentation like access tracking or taint tracking to
+    /// indicate which parts of the input are used. This will prevent us from
+    /// corrupting parts of the input which have zero effect on the program.
+    ///
+    /// It's possible you can have this take any meaning you want, all it does
+    /// is limit the corruption/splicing locations to the indicies in this
+    /// vector. Feel free to change this to have different meanings, like
+    /// indicate indicies which are used in comparison instructions!
+    ///
+    /// Since we use `rand_exp` to pick from this, this list should remain
+    /// sorted for best behavior. If you cannot sort this, you should probably
+    /// change the behaviors of `rand_offset` to be uniform
+    ///
+    /// It is strongly recommended that you do `accessed.clear()` and
+    /// `accessed.extend_from_slice()` to update this buffer, to prevent the
+    /// backing from being deallocated and reallocated.
+    pub accessed: Vec<usize>,
+
+    /// Maximum size to allow inputs to expand to
+    max_input_size: usize,
+
+    /// The random number generator used for mutations
+    rng: Rng,
+
+    /// The mutations should prefer creating ASCII-printable characters
+    printable: bool,
+
+    /// Operands of the comparisons observed during the executions (CmpLog)
+    cmp_values: Vec<F::Felt>,
+
+    /// Index of the next comparison operand to replace once `cmp_values` is full
+    cmp_next: usize,
+
+    /// User supplied dictionary
+    dict: Dict<F>,
+
+    /// Registered mutation strategies with their scheduling statistics
+    strategies: Vec<ScheduledStrategy<F>>,
+
+    /// Indexes of the strategies used by the last call to `mutate`
+    last_used: Vec<usize>,
+}
+
+/// Number of strategy uses after which the scheduling statistics are halved, so
+/// the weights follow the strategies that recently produced new coverage
+const DECAY_PERIOD: u64 = 100_000;
+/// Maximum number of comparison operands kept, the oldest are replaced by the new ones
+const MAX_CMP_VALUES: usize = 1024;
+
+/// A mutation strategy and the statistics used to schedule it
+struct ScheduledStrategy<F: Field> {
+    strategy: Box<dyn MutationStrategy<F>>,
+    /// Set for the builtin strategies, unset for the custom ones
+    builtin: bool,
+    /// Decayed number of uses and new coverage finds, used to compute the weight
+    uses: u64,
+    finds: u64,
+    /// Statistics not yet collected by `drain_strategy_stats`
+    pending: StrategyStats,
+}
+
+impl<F: Field> ScheduledStrategy<F> {
+    fn new(strategy: Box<dyn MutationStrategy<F>>, builtin: bool) -> Self {
+        ScheduledStrategy {
+            strategy,
+            builtin,
+            uses: 0,
+            finds: 0,
+            pending: StrategyStats::default(),
+        }
+    }
+
+    /// Estimated probability of finding new coverage, with a prior of 1 find every 10 uses
+    fn weight(&self) -> f64 {
+        (self.finds + 1) as f64 / (self.uses + 10) as f64
+    }
+}
+
+/// A builtin strategy, implemented by a `Mutator` method
+pub(crate) type BuiltinFn<F> = fn(&mut Mutator<F>);
+/// A builtin strategy splicing the inputs of the database
+pub(crate) type SpliceFn<F> = fn(&mut Mutator<F>, &dyn InputDatabase<<F as Field>::Felt>);
+
+/// Values at the boundaries of the prime field and of the range checks:
+/// P-1, P-2, 2^128 +/- 1, 2^251 +/- 1, 2^64 +/- 1 and 2^32 +/- 1
+/// The felts are big endian encoded, they are shared by all the engines
+fn felt_boundaries() -> &'static [[u8; 32]] {
+    static BOUNDARIES: OnceLock<Vec<[u8; 32]>> = OnceLock::new();
+    BOUNDARIES.get_or_init(|| {
+        let mut boundaries = vec![RawField::from_i64(-1), RawField::from_i64(-2)];
+        for bits in [32u32, 64, 128, 251] {
+            let bound = RawField::pow2(bits);
+            boundaries.push(RawField::sub(&bound, &RawField::from_u64(1)));
+            boundaries.push(RawField::add(&bound, &RawField::from_u64(1)));
+            boundaries.push(bound);
+        }
+        boundaries
+    })
+}
+
+/// Boundary values of an ABI type, the felts, addresses and unknown types use the
+/// field boundaries
+fn type_boundaries(arg_type: &str) -> &'static [[u8; 32]] {
+    static BOUNDARIES: OnceLock<HashMap<&'static str, Vec<[u8; 32]>>> = OnceLock::new();
+    BOUNDARIES
+        .get_or_init(|| {
+            HashMap::from([
+                ("core::integer::u8", uint_boundaries(8)),
+                ("core::integer::u16", uint_boundaries(16)),
+                ("core::integer::u32", uint_boundaries(32)),
+                ("core::integer::u64", uint_boundaries(64)),
+                ("core::integer::u128", uint_boundaries(128)),
+                // A u256 argument takes one felt of the input, as its u128 limbs
+                ("core::integer::u256", uint_boundaries(128)),
+                ("core::integer::i8", int_boundaries(8)),
+                ("core::integer::i16", int_boundaries(16)),
+                ("core::integer::i32", int_boundaries(32)),
+                ("core::integer::i64", int_boundaries(64)),
+                ("core::integer::i128", int_boundaries(128)),
+                (
+                    "core::bool",
+                    vec![RawField::from_u64(0), RawField::from_u64(1)],
+                ),
+            ])
+        })
+        .get(arg_type)
+        .map_or_else(felt_boundaries, Vec::as_slice)
+}
+
+/// Boundary values of an unsigned integer of `bits` bits: 0, 1, the middle of the
+/// range and its maximum
+fn uint_boundaries(bits: u32) -> Vec<[u8; 32]> {
+    let bound = RawField::pow2(bits);
+    let half = RawField::pow2(bits - 1);
+    vec![
+        RawField::from_u64(0),
+        RawField::from_u64(1),
+        RawField::sub(&half, &RawField::from_u64(1)),
+        half,
+        RawField::sub(&bound, &RawField::from_u64(2)),
+        RawField::sub(&bound, &RawField::from_u64(1)),
+    ]
+}
+
+/// Boundary values of a signed integer of `bits` bits: 0, 1, -1 and the values around
+/// its minimum and maximum, the negative values are represented modulo P
+fn int_boundaries(bits: u32) -> Vec<[u8; 32]> {
+    let max = RawField::sub(&RawField::pow2(bits - 1), &RawField::from_u64(1));
+    let min = RawField::neg(&RawField::pow2(bits - 1));
+    vec![
+        RawField::from_u64(0),
+        RawField::from_u64(1),
+        RawField::from_i64(-1),
+        RawField::sub(&max, &RawField::from_u64(1)),
+        max,
+        RawField::add(&min, &RawField::from_u64(1)),
+        min,
+    ]
+}
+
+/// Builtin strategies mutating the felts of the input
+/// They keep the input length unchanged except `inter_splice`, which inserts a copy of a
+/// chunk of the input up to the maximum input size. It does nothing on an input already
+/// at that size, e.g. the one felt per argument inputs of the cairo-vm fuzzer.
+fn builtins<F: Field>() -> Vec<(&'static str, BuiltinFn<F>)> {
+    vec![
+        ("inc_byte", Mutator::inc_byte),
+        ("dec_byte", Mutator::dec_byte),
+        ("neg_byte", Mutator::neg_byte),
+        ("add_sub", Mutator::add_sub),
+        ("set", Mutator::set),
+        ("swap", Mutator::swap),
+        ("copy", Mutator::copy),
+        ("inter_splice", Mutator::inter_splice),
+        ("overwrite_rand", Mutator::overwrite_rand),
+        ("byte_repeat_overwrite", Mutator::byte_repeat_overwrite),
+        ("magic_overwrite", Mutator::magic_overwrite),
+        ("random_overwrite", Mutator::random_overwrite),
+        ("cmp_overwrite", Mutator::cmp_overwrite),
+        ("dict_overwrite", Mutator::dict_overwrite),
+        ("felt_add_sub", Mutator::felt_add_sub),
+        ("felt_boundary", Mutator::felt_boundary),
+        ("type_boundary", Mutator::type_boundary),
+    ]
+}
+
+/// Default mutation strategies: the builtins and the splices of corpus inputs
+/// The splices keep the input length unchanged.
+fn default_strategies<F: Field>() -> Vec<ScheduledStrategy<F>> {
+    let splices: &[(&'static str, SpliceFn<F>)] = &[
+        ("splice_overwrite", Mutator::splice_overwrite),
+        ("splice_insert", Mutator::splice_insert),
+    ];
+
+    let mut strategies: Vec<ScheduledStrategy<F>> = builtins()
+        .into_iter()
+        .map(|(name, func)| ScheduledStrategy::new(Box::new(Builtin { name, func }), true))
+        .collect();
+    strategies.extend(
+        splices.iter().map(|&(name, func)| {
+            ScheduledStrategy::new(Box::new(BuiltinSplice { name, func }), true)
+        }),
+    );
+    strategies
+}
+
+/// A byte corruption skeleton which has user-supplied corruption logic which
+/// will be used to mutate a byte which is passed to it
+///
+/// $corrupt takes a &mut self, `u8` as arguments, and returns a `u8` as the
+/// corrupted value.
+macro_rules! byte_corruptor {
+    ($func:ident, $corrupt:expr) => {
+        /// Corrupt a byte in the input
+        fn $func(&mut self) {
+            // Only corrupt a byte if there are bytes present
+            if !self.input.is_empty() {
+                // Pick a random byte offset
+                let offset = self.rand_offset();
+
+                // Perform the corruption
+                self.input[offset] = ($corrupt)(self, self.input[offset].clone());
+            }
+        }
+    };
+}
+
+impl<F: Field> Default for Mutator<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> Mutator<F> {
+    /// Create a new mutator
+    pub fn new() -> Self {
+        Mutator {
+            input: Vec::new(),
+            types: Vec::new(),
+            accessed: Vec::new(),
+            max_input_size: 1024,
+            printable: false,
+            cmp_values: Vec::new(),
+            cmp_next: 0,
+            dict: Dict::default(),
+            strategies: default_strategies(),
+            last_used: Vec::new(),
+            rng: Rng {
+                seed: 0x12640367f4b7ea35,
+                exp_disabled: false,
+            },
+        }
+    }
+
+    pub fn fix_inputs_types(&mut self) {
+        let mut idx = 0;
+        self.types.clone().into_iter().for_each(|input_type| {
+            let bits_to_save = match input_type.as_str() {
+                "core::integer::u8" => 8,
+                "core::integer::u16" => 16,
+                "core::integer::u32" => 32,
+                "core::integer::u64" => 64,
+                "core::integer::u128" => 128,
+                "core::integer::u256" => 256, // still need to fix for this
+                "core::felt252" => 252,
+                _ => 252, // Should be a Todo! but since the runner takes a felt252 vector, it's a good idea to send random data
+            };
+            if bits_to_save != 256 && bits_to_save != 252 {
+                let mut new_value = F::to_be_bytes(&self.input[idx]);
+                let len = new_value.len() - (bits_to_save / 8);
+                new_value[..len].fill(0);
+                self.input[idx] = F::from_be_bytes(&new_value);
+            }
+            idx += 1;
+        });
+    }
+    /// Set whether or not this mutator should produce only ASCII-printable
+    /// characters.
+    ///
+    /// If non-printable characters are used in part of the corpus or existing
+    /// input, they may be inherited and still exist in the output of the
+    /// fuzzer.
+    pub fn printable(mut self, printable: bool) -> Self {
+        self.printable = printable;
+        self
+    }
+
+    /// Allows enabling and disabling of exponential random in the fuzzer. If
+    /// disabled, all random selections will be uniform.
+    pub fn rand_exp(mut self, exponential_random: bool) -> Self {
+        self.rng.exp_disabled = !exponential_random;
+        self
+    }
+
+    /// Sets the seed for the internal RNG
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng.seed = seed ^ 0x12640367f4b7ea35;
+        self
+    }
+
+    pub fn types(mut self, types: Vec<String>) -> Self {
+        self.types.clone_from(&types);
+        self
+    }
+    /// Sets the dictionary used by the `dict_overwrite` strategy
+    pub fn dict(mut self, dict: Dict<F>) -> Self {
+        self.dict = dict;
+        self
+    }
+
+    /// Registers a custom mutation strategy, scheduled along the default ones
+    pub fn strategy(mut self, strategy: Box<dyn MutationStrategy<F>>) -> Self {
+        self.strategies
+            .push(ScheduledStrategy::new(strategy, false));
+        self
+    }
+
+    /// Enables the builtin strategies, they are enabled by default
+    /// Without them, only the custom strategies mutate the input, e.g. to keep typed
+    /// inputs well formed. The custom strategies can still use `mutate_felt`.
+    pub fn builtins(mut self, enabled: bool) -> Self {
+        self.strategies
+            .retain(|scheduled| enabled || !scheduled.builtin);
+        if enabled && !self.strategies.iter().any(|scheduled| scheduled.builtin) {
+            let custom = core::mem::replace(&mut self.strategies, default_strategies());
+            self.strategies.extend(custom);
+        }
+        self
+    }
+
+    /// Generates a random number with uniform distribution in the range of
+    /// [min, max], for use by custom strategies
+    pub fn rand(&mut self, min: usize, max: usize) -> usize {
+        self.rng.rand(min, max)
+    }
+
+    /// Sets the maximum input size
+    pub fn max_input_size(mut self, size: usize) -> Self {
+        self.max_input_size = size;
+        self
+    }
+
+    /// Adds comparison operands observed during an execution
+    /// They are used by the `cmp_overwrite` strategy
+    pub fn add_cmp_values(&mut self, values: Vec<F::Felt>) {
+        for value in values {
+            if self.cmp_values.len() < MAX_CMP_VALUES {
+                self.cmp_values.push(value);
+            } else {
+                self.cmp_values[self.cmp_next] = value;
+                self.cmp_next = (self.cmp_next + 1) % MAX_CMP_VALUES;
+            }
+        }
+    }
+
+    /// Performs standard mutation of an the input
+    pub fn mutate<T: InputDatabase<F::Felt>>(&mut self, mutations: usize, inputs: &T) {
+        // Nothing to mutate with if the builtin strategies are disabled without custom ones
+        if self.strategies.is_empty() {
+            self.last_used.clear();
+            return;
+        }
+
+        // Save the old state of the exponential random and randomly disable
+        // the exponential random
+        let old_exp_state = self.rng.exp_disabled;
+        if self.rng.rand(0, 1) == 0 {
+            self.rng.exp_disabled = true;
+        }
+
+        // Take the strategies out of the mutator so they can borrow it
+        let mut strategies = core::mem::take(&mut self.strategies);
+        self.last_used.clear();
+        for _ in 0..mutations {
+            // Pick a mutation strategy according to its recent results
+            let sel = self.pick_strategy(&strategies);
+            let scheduled = &mut strategies[sel];
+            scheduled.uses += 1;
+            scheduled.pending.uses += 1;
+            if !self.last_used.contains(&sel) {
+                self.last_used.push(sel);
+            }
+
+            // Run the mutation strategy
+            scheduled.strategy.mutate(self, inputs);
+        }
+
+        // Halve the statistics so the old results weigh less than the recent ones
+        if strategies.iter().map(|s| s.uses).sum::<u64>() > DECAY_PERIOD {
+            for scheduled in strategies.iter_mut() {
+                scheduled.uses /= 2;
+                scheduled.finds /= 2;
+            }
+        }
+        self.strategies = strategies;
+
+        // Restore exponential random state to the old state
+        self.rng.exp_disabled = old_exp_state;
+        self.fix_inputs_types();
+    }
+
+    /// Mutates a single felt with a random builtin strategy
+    /// Used by the custom strategies mutating the structure of typed inputs
+    pub fn mutate_felt(&mut self, felt: &F::Felt) -> F::Felt {
+        let builtins = builtins::<F>();
+        let (_, func) = builtins[self.rng.rand(0, builtins.len() - 1)];
+        // Mutate a one felt input, without the types and offsets of the current input
+        let input = core::mem::replace(&mut self.input, vec![felt.clone()]);
+        let types = core::mem::take(&mut self.types);
+        let accessed = core::mem::take(&mut self.accessed);
+        func(self);
+        let mutated = core::mem::replace(&mut self.input, input);
+        self.types = types;
+        self.accessed = accessed;
+        mutated.into_iter().next().unwrap_or_else(|| felt.clone())
+    }
+
+    /// Picks a strategy with a probability proportional to its weight
+    fn pick_strategy(&mut self, strategies: &[ScheduledStrategy<F>]) -> usize {
+        let total: f64 = strategies.iter().map(|s| s.weight()).sum();
+        let mut target = self.rng.next() as f64 / u64::MAX as f64 * total;
+        for (idx, scheduled) in strategies.iter().enumerate() {
+            target -= scheduled.weight();
+            if target <= 0.0 {
+                return idx;
+            }
+        }
+        strategies.len() - 1
+    }
+
+    /// Reports the result of the execution of the last mutated input
+    /// The strategies used to mutate it are credited if it reached new coverage
+    pub fn report(&mut self, new_coverage: bool) {
+        if !new_coverage {
+            return;
+        }
+        for &idx in &self.last_used {
+            self.strategies[idx].finds += 1;
+            self.strategies[idx].pending.finds += 1;
+        }
+    }
+
+    /// Names of the strategies used to mutate the last input
+    pub fn last_strategies(&self) -> Vec<&str> {
+        self.last_used
+            .iter()
+            .map(|&idx| self.strategies[idx].strategy.name())
+            .collect()
+    }
+
+    /// Returns the statistics of each strategy since the previous call
+    pub fn drain_strategy_stats(&mut self) -> Vec<(String, StrategyStats)> {
+        self.strategies
+            .iter_mut()
+            .map(|scheduled| {
+                (
+                    scheduled.strategy.name().to_string(),
+                    core::mem::take(&mut scheduled.pending),
+                )
+            })
+            .collect()
+    }
+
+    /// Pick a random offset in the input to corrupt. Any mutation
+    /// strategy which needs to pick a random byte should us this, such that
+    /// a bias can be applied to the offsets and automatically affect all
+    /// aspects of the fuzzer
+    ///
+    /// If `insert` is set to `true`, then the offset being returned is
+    /// expected to be for insertion. This means that we'll have a chance of
+    /// returning an offset before or after sections of the input. For example,
+    /// we may return an index which is `self.input.len()`, which would allow
+    /// for the user to insert data at the end of the input.
+    ///
+    /// If the input is zero length, then this will return a zero. Thus, it
+    /// is up to the caller to know whether or not the input size being zero
+    /// matters. For example, flipping a byte at offset 0 on a 0 size input
+    /// would cause a panic, but inserting at offset 0 may be desired.
+    fn rand_offset_int(&mut self, plus_one: bool) -> usize {
+        if !self.accessed.is_empty() {
+            // If we have an accessed list, use an index from the list of known
+            // accessed bytes from the input. This prevents us from corrupting
+            // a byte which cannot possibly influence the binary, as it is
+            // never read during the fuzz case.
+            self.accessed[self.rng.rand_exp(0, self.accessed.len() - 1)]
+        } else if !self.input.is_empty() {
+            // We have no accessed list, just return a random index
+            self.rng
+                .rand_exp(0, self.input.len() - (!plus_one) as usize)
+        } else {
+            // Input is entirely empty, just return index 0 such that
+            // things that insert into the input know that they should
+            // just insert at 0.
+            0
+        }
+    }
+
+    /// Generate a random offset, see `rand_offset_int` for more info
+    fn rand_offset(&mut self) -> usize {
+        self.rand_offset_int(false)
+    }
+
+    /// Pick a random chunk of another input of the database
+    fn donor_chunk(&mut self, inputs: &dyn InputDatabase<F::Felt>) -> Option<Vec<F::Felt>> {
+        // Get the number of inputs in the database
+        let dblen = inputs.num_inputs();
+        if dblen == 0 {
+            return None;
+        }
+
+        // Select a random input, nothing to splice for an empty input
+        let donor = inputs.input(self.rng.rand(0, dblen - 1))?;
+        if donor.is_empty() {
+            return None;
+        }
+
+        // Pick a random offset and length from the input which
+        // we want to use for splicing
+        let donor_offset = self.rng.rand_exp(0, donor.len() - 1);
+        let donor_length = self.rng.rand_exp(1, donor.len() - donor_offset);
+        Some(donor[donor_offset..donor_offset + donor_length].to_vec())
+    }
+
+    /// Overwrite a part of the input with a chunk of another input
+    fn splice_overwrite(&mut self, inputs: &dyn InputDatabase<F::Felt>) {
+        // Cannot overwrite an empty input
+        if self.input.is_empty() {
+            return;
+        }
+        let chunk = match self.donor_chunk(inputs) {
+            Some(chunk) => chunk,
+            None => return,
+        };
+
+        // Find an offset to overwrite in our input
+        let offset = self.rand_offset();
+        let length = core::cmp::min(chunk.len(), self.input.len() - offset);
+
+        // Overwrite it!
+        self.input[offset..offset + length].clone_from_slice(&chunk[..length]);
+    }
+
+    /// Insert a chunk of another input, shifting the end of the input out so
+    /// its length is preserved
+    fn splice_insert(&mut self, inputs: &dyn InputDatabase<F::Felt>) {
+        if self.input.is_empty() {
+            return;
+        }
+        let chunk = match self.donor_chunk(inputs) {
+            Some(chunk) => chunk,
+            None => return,
+        };
+
+        // Find an offset to insert at in our input
+        let len = self.input.len();
+        let offset = self.rand_offset();
+
+        // Insert!
+        self.input.splice(offset..offset, chunk);
+        self.input.truncate(len);
+    }
+
+    /// Add or subtract a random amount with a random endianness from a random
+    /// size `u8` through `u64`
+    fn add_sub(&mut self) {
+        // Nothing to do on an empty input
+        if self.input.is_empty() {
+            return;
+        }
+
+        // Pick an offset to corrupt at
+        let offset = self.rand_offset();
+
+        // Get the remaining number of bytes in the input
+        let remain = self.input.len() - offset;
+
+        // Pick a random size of the add or subtract as a 1, 2, 4, or 8 byte
+        // signed integer
+        let intsize = match remain {
+            1..=1 => 1,
+            2..=3 => 1 << self.rng.rand(0, 1),
+            4..=7 => 1 << self.rng.rand(0, 2),
+            8..=usize::MAX => 1 << self.rng.rand(0, 3),
+            _ => unreachable!(),
+        };
+
+        // Determine the maximum number to add or subtract
+        let range = match intsize {
+            1 => 16,
+            2 => 4096,
+            4 => 1024 * 1024,
+            8 => 256 * 1024 * 1024,
+            _ => unreachable!(),
+        };
+
+        // Convert the range to a random number from [-range, range]
+        let delta = self.rng.rand(0, range * 2) as i32 - range as i32;
+
+        /// Macro to mutate bytes in the input as a `$ty`
+        macro_rules! mutate {
+            ($ty:ty) => {{
+                // Interpret the `offset` as a `$ty`
+                let tmp = self.input[offset].clone();
+
+                // Apply the delta, interpreting the bytes as a random
+                // endianness
+                let tmp = if self.rng.rand(0, 1) == 0 {
+                    F::add(&F::from_i64(delta as i64), &tmp)
+                } else {
+                    F::from_i64(delta as i64)
+                };
+
+                // Write the new value out to the input
+                self.input[offset] = F::add(&self.input[offset], &tmp);
+            }};
+        }
+
+        // Apply the delta to the offset
+        match intsize {
+            1 => mutate!(u8),
+            2 => mutate!(u16),
+            4 => mutate!(u32),
+            8 => mutate!(u64),
+            16 => mutate!(u128),
+            _ => unreachable!(),
+        };
+    }
+
+    /// Randomly replace a sequence of bytes with the same random character
+    /// repeated a random amount of times
+    fn set(&mut self) {
+        // Nothing to do on an empty input
+        if self.input.is_empty() {
+            return;
+        }
+
+        // Pick offset to memset at
+        let offset = self.rand_offset();
+
+        // Pick random length to remainder of input
+        let len = self.rng.rand_exp(1, self.input.len() - offset);
+
+        // Pick the value to memset
+        let chr = if self.printable {
+            (self.rng.rand(0, 94) + 32) as u8
+        } else {
+            self.rng.rand(0, 255) as u8
+        };
+
+        // Replace the selected bytes at the offset with `chr`
+        self.input[offset..offset + len]
+            .iter_mut()
+            .for_each(|x| *x = F::from_u64(chr as u64));
+    }
+
+    /// Swap two ranges in an input buffer
+    fn swap_ranges(vec: &mut [F::Felt], mut offset1: usize, mut offset2: usize, mut len: usize) {
+        if offset1 < offset2 && offset1 + len >= offset2 {
+            // The ranges have the following layout here:
+            // [o1--------]
+            //      [o2--------]
+            let tail = offset2 - offset1;
+            // Copy the tail from offset1 into offset2
+            // [o1-][tail1]
+            //      [o2-][tail2]
+            // This needs to happen in the reverse order so that the later
+            // values at offset1 are not mangled in the process of copying.
+            // Same as memmove.
+            for ii in (tail..len).rev() {
+                vec[offset2 + ii] = vec[offset1 + ii].clone();
+            }
+
+            // After this, the layout is the following:
+            // [o1-][xxxxx]
+            //      [o2-][tail1]
+            len = tail;
+        } else if offset2 < offset1 && offset2 + len >= offset1 {
+            // The ranges have the following layout here:
+            //      [o1--------]
+            // [o2--------]
+            let head = len - (offset1 - offset2);
+            // Copy the head from offset1 into offset2
+            //      [head1][o1-]
+            // [head2][o2-]
+            for ii in 0..head {
+                vec[offset2 + ii] = vec[offset1 + ii].clone();
+            }
+
+            // After this, the layout is the following:
+            //      [xxxxx][o1-]
+            // [head1][o2-]
+            offset1 += head;
+            offset2 += head;
+            len -= head;
+        }
+
+        // At this point, the ranges are non-overlapping
+        // and the swap can be done in a naive way.
+        for ii in 0..len {
+            vec.swap(offset1 + ii, offset2 + ii);
+        }
+    }
+
+    /// Swap two difference sequence of bytes in the input to different places
+    fn swap(&mut self) {
+        // Nothing to do on an empty input
+        if self.input.is_empty() {
+            return;
+        }
+
+        // Pick two random ranges in the input and calculate the remaining
+        // bytes for them
+        let src = self.rand_offset();
+        let srcrem = self.input.len() - src;
+        let dst = self.rand_offset();
+        let dstrem = self.input.len() - dst;
+
+        // Pick a random length up to the max for both offsets
+        let len = self.rng.rand_exp(1, core::cmp::min(srcrem, dstrem));
+
+        // Swap the ranges of bytes
+        Self::swap_ranges(&mut self.input, src, dst, len);
+    }
+
+    /// Overwrite the felts in the input with `buf` at `offset`. If `buf`
+    /// goes out of bounds of the input the `buf` will be truncated and the
+    /// copy will stop.
+    fn overwrite(&mut self, offset: usize, buf: &[F::Felt]) {
+        let len = core::cmp::min(buf.len(), self.input.len() - offset);
+        self.input[offset..offset + len].clone_from_slice(&buf[..len]);
+    }
+
+    /// Take the bytes from `source` for `len` bytes in the input, and insert
+    /// a copy of them at `dest`
+    fn insert_inplace(&mut self, source: usize, len: usize, dest: usize) {
+        // Nothing to do
+        if len == 0 || source == dest {
+            return;
+        }
+
+        // Cap the insertion to the max input size
+        let len = core::cmp::min(len, self.max_input_size - self.input.len());
+
+        // Create an interator to splice into the input
+        let rep = core::iter::repeat_n(F::from_u64(0), len);
+
+        // Expand at `dest` with `rep`, making room for the copy
+        self.input.splice(dest..dest, rep);
+
+        // Determine where the splice occurred
+        let split_point = dest.saturating_sub(source).min(len);
+
+        for ii in 0..split_point {
+            self.input[dest + ii] = self.input[source + ii].clone();
+        }
+
+        for ii in split_point..len {
+            self.input[dest + ii] = self.input[source + ii + len].clone();
+        }
+    }
+
+    /// Take the bytes from `source` for `len` bytes in the input, and copy
+    /// them to `dest`
+    fn overwrite_inplace(&mut self, source: usize, len: usize, dest: usize) {
+        // Nothing to do
+        if len == 0 || source == dest {
+            return;
+        }
+
+        if source < dest {
+            // Copy forwards
+            for ii in 0..len {
+                self.input[dest + ii] = self.input[source + ii].clone();
+            }
+        } else {
+            // Copy backwards
+            for ii in (0..len).rev() {
+                self.input[dest + ii] = self.input[source + ii].clone();
+            }
+        }
+    }
+
+    /// Copy bytes from one location in the input and overwrite them at another
+    /// location in the input
+    fn copy(&mut self) {
+        // Nothing to do on an empty input
+        if self.input.is_empty() {
+            return;
+        }
+
+        // Pick a source and destination for a copy
+        let src = self.rand_offset();
+        let srcrem = self.input.len() - src;
+        let dst = self.rand_offset();
+        let dstrem = self.input.len() - dst;
+
+        // Pick a random length up to the max for both offsets
+        let len = self.rng.rand_exp(1, core::cmp::min(srcrem, dstrem));
+
+        // Perform a copy inplace in the input
+        self.overwrite_inplace(src, len, dst);
+    }
+
+    /// Take one location of the input and splice it into another
+    fn inter_splice(&mut self) {
+        // Nothing to do on an empty input
+        if self.input.is_empty() {
+            return;
+        }
+
+        // Pick a source and destination for an insertion
+        let src = self.rand_offset();
+        let srcrem = self.input.len() - src;
+        let dst = self.rand_offset_int(true);
+
+        // Pick a random length
+        let len = self.rng.rand_exp(1, srcrem);
+
+        // Perform an insertion inplace in the input
+        self.insert_inplace(src, len, dst);
+    }
+
+    /// Create 1 or 2 random bytes and overwrite them at a location in the
+    /// input
+    fn overwrite_rand(&mut self) {
+        // Nothing to do on an empty input
+        if self.input.is_empty() {
+            return;
+        }
+
+        // Pick some random values
+        let bytes = if self.printable {
+            [
+                F::from_u64((self.rng.rand(0, 94) + 32) as u64),
+                F::from_u64((self.rng.rand(0, 94) + 32) as u64),
+            ]
+        } else {
+            [
+                F::from_u64(self.rng.rand(0, 255) as u64),
+                F::from_u64(self.rng.rand(0, 255) as u64),
+            ]
+        };
+
+        // Pick a random offset and length
+        let offset = self.rand_offset();
+        let len = core::cmp::min(self.input.len() - offset, 2);
+        let len = self.rng.rand(1, len);
+
+        // Overwrite the bytes
+        self.overwrite(offset, &bytes[..len]);
+    }
+
+    /// Find a byte and repeat it multiple times by overwriting the data after
+    /// it
+    fn byte_repeat_overwrite(&mut self) {
+        // Nothing to do on an empty input
+        if self.input.is_empty() {
+            return;
+        }
+
+        // Pick a random offset
+        let offset = self.rand_offset();
+
+        // Pick an amount to repeat
+        let amount = self.rng.rand_exp(1, self.input.len() - offset);
+
+        // Get the old value and repeat it
+        let val = self.input[offset].clone();
+        self.input[offset + 1..offset + amount]
+            .iter_mut()
+            .for_each(|x| *x = val.clone());
+    }
+
+    /// Write over the input with a random magic value
+    fn magic_overwrite(&mut self) {
+        // Nothing to do on an empty input
+        if self.input.is_empty() {
+            return;
+        }
+
+        // Pick a random offset
+        let offset = self.rand_offset();
+
+        // Pick a random magic value
+        let magic_value = &MAGIC_VALUES[self.rng.rand(0, MAGIC_VALUES.len() - 1)];
+        let magic_felts: Vec<F::Felt> = magic_value
+            .iter()
+            .map(|byte| F::from_u64(*byte as u64))
+            .collect();
+
+        // Overwrite it
+        self.overwrite(offset, &magic_felts);
+    }
+
+    /// Overwrite a random offset of the input with random bytes
+    fn random_overwrite(&mut self) {
+        // Nothing to do on an empty input
+        if self.input.is_empty() {
+            return;
+        }
+
+        // Pick a random offset
+        let offset = self.rand_offset();
+
+        // Pick an amount to overwrite
+        let amount = self.rng.rand_exp(1, self.input.len() - offset);
+
+        // Overwrite with random data
+        let rng = &mut self.rng;
+        if self.printable {
+            self.input[offset..offset + amount]
+                .iter_mut()
+                .for_each(|x| {
+                    *x = F::from_u64((rng.rand(0, 94) + 32) as u64);
+                });
+        } else {
+            self.input[offset..offset + amount]
+                .iter_mut()
+                .for_each(|x| *x = F::from_u64(rng.rand(0, 255) as u64));
+        }
+    }
+
+    /// Write over the input with an operand of a comparison observed during execution
+    fn cmp_overwrite(&mut self) {
+        // Nothing to do on an empty input or without comparison operands
+        if self.input.is_empty() || self.cmp_values.is_empty() {
+            return;
+        }
+
+        // Pick a random offset
+        let offset = self.rand_offset();
+
+        // Pick a random comparison operand and overwrite the felt with it
+        let value = &self.cmp_values[self.rng.rand(0, self.cmp_values.len() - 1)];
+        self.input[offset] = value.clone();
+    }
+
+    /// Add or subtract a small delta modulo P, crossing 0 and P-1
+    fn felt_add_sub(&mut self) {
+        // Nothing to do on an empty input
+        if self.input.is_empty() {
+            return;
+        }
+
+        // Pick an offset and a delta in [1, 2^16] biased toward small values
+        let offset = self.rand_offset();
+        let delta = F::from_u64(self.rng.rand_exp(1, 1 << 16) as u64);
+        if self.rng.rand(0, 1) == 0 {
+            self.input[offset] = F::add(&self.input[offset], &delta);
+        } else {
+            self.input[offset] = F::sub(&self.input[offset], &delta);
+        }
+    }
+
+    /// Jump to a value at a field or range check boundary, possibly off by a few
+    fn felt_boundary(&mut self) {
+        // Nothing to do on an empty input
+        if self.input.is_empty() {
+            return;
+        }
+
+        let offset = self.rand_offset();
+        let boundaries = felt_boundaries();
+        let value = F::from_be_bytes(&boundaries[self.rng.rand(0, boundaries.len() - 1)]);
+        let jitter = F::from_i64(self.rng.rand(0, 4) as i64 - 2);
+        self.input[offset] = F::add(&value, &jitter);
+    }
+
+    /// Set an argument to a boundary value of its ABI type
+    fn type_boundary(&mut self) {
+        // Nothing to do on an empty input
+        if self.input.is_empty() {
+            return;
+        }
+
+        let offset = self.rand_offset();
+        let boundaries = type_boundaries(self.types.get(offset).map_or("", String::as_str));
+        self.input[offset] = F::from_be_bytes(&boundaries[self.rng.rand(0, boundaries.len() - 1)]);
+    }
+
+    /// Write over an argument with a dictionary value applicable to it
+    fn dict_overwrite(&mut self) {
+        // Nothing to do on an empty input or without dictionary
+        if self.input.is_empty() || self.dict.entries.is_empty() {
+            return;
+        }
+
+        // Pick a random offset
+        let offset = self.rand_offset();
+
+        // Pick a random value among the ones scoped to this argument
+        let values = self
+            .dict
+            .values_for(offset, self.types.get(offset).map(String::as_str));
+        if values.is_empty() {
+            return;
+        }
+        let value = values[self.rng.rand(0, values.len() - 1)].clone();
+        self.input[offset] = value;
+    }
+
+    // Corrupt a random bit in the input
+    /*     byte_corruptor!(bit, |obj: &mut Self, x: F::Felt| -> F::Felt {
+        x ^ F::from_u64(1u8 << obj.rng.rand(0, 7))
+    }); */
+
+    // Increment a byte in the input
+    byte_corruptor!(inc_byte, |_: &mut Self, x: F::Felt| -> F::Felt {
+        F::add(&x, &F::from_u64(1))
+    });
+
+    // Decrement a byte in the input
+    byte_corruptor!(dec_byte, |_: &mut Self, x: F::Felt| -> F::Felt {
+        F::sub(&x, &F::from_u64(1))
+    });
+
+    // Negate a felt of the input modulo P
+    byte_corruptor!(neg_byte, |_: &mut Self, x: F::Felt| -> F::Felt {
+        F::neg(&x)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::corpus::Corpus;
+
+    #[test]
+    fn test_pick_strategy() {
+        let mut mutator = Mutator::<RawField>::new().seed(1);
+        let mut strategies = default_strategies();
+        // The first strategy found new coverage on every use
+        strategies[0].uses = 1000;
+        strategies[0].finds = 1000;
+        let mut picks = vec![0; strategies.len()];
+        for _ in 0..10000 {
+            picks[mutator.pick_strategy(&strategies)] += 1;
+        }
+        // Its weight is 10 times the weight of the others
+        assert!(picks[1..].iter().all(|&count| picks[0] > 5 * count));
+        assert!(picks[1..].iter().all(|&count| count > 0));
+    }
+
+    #[test]
+    fn test_strategy_decay() {
+        let mut mutator = Mutator::<RawField>::new().seed(1);
+        mutator.input = vec![RawField::from_u64(0); 4];
+        mutator.strategies[0].uses = DECAY_PERIOD;
+        mutator.strategies[0].finds = 10;
+        mutator.mutate(1, &EmptyDatabase);
+        // The statistics are halved once the uses exceed the decay period
+        assert!(mutator.strategies[0].uses <= DECAY_PERIOD / 2 + 1);
+        assert_eq!(mutator.strategies[0].finds, 5);
+        // The statistics reported to the stats log are not decayed
+        let total: u64 = mutator
+            .drain_strategy_stats()
+            .iter()
+            .map(|(_, stats)| stats.uses)
+            .sum();
+        assert_eq!(total, 1);
+    }
+
+    #[test]
+    fn test_boundaries() {
+        let one = || RawField::from_u64(1);
+        let p_minus_one = RawField::from_i64(-1);
+        let mut mutator = Mutator::<RawField>::new().seed(1);
+        mutator.input = vec![RawField::from_u64(0)];
+        let mut values = Vec::new();
+        for _ in 0..1000 {
+            mutator.felt_boundary();
+            values.push(mutator.input[0]);
+        }
+        assert!(values.contains(&p_minus_one));
+        assert!(values.contains(&RawField::sub(&RawField::pow2(251), &one())));
+        assert!(values.contains(&RawField::add(&RawField::pow2(251), &one())));
+
+        let limits = [
+            (
+                "core::integer::u8",
+                RawField::from_u64(0),
+                RawField::from_u64(255),
+            ),
+            (
+                "core::integer::u128",
+                RawField::from_u64(0),
+                RawField::sub(&RawField::pow2(128), &one()),
+            ),
+            (
+                "core::integer::i8",
+                RawField::from_i64(-128),
+                RawField::from_u64(127),
+            ),
+            (
+                "core::integer::i128",
+                RawField::neg(&RawField::pow2(127)),
+                RawField::sub(&RawField::pow2(127), &one()),
+            ),
+            ("core::felt252", RawField::from_i64(-2), p_minus_one),
+        ];
+        for (arg_type, min, max) in limits {
+            mutator = mutator.types(vec![arg_type.to_string()]);
+            let mut values = Vec::new();
+            for _ in 0..200 {
+                mutator.type_boundary();
+                values.push(mutator.input[0]);
+            }
+            assert!(values.contains(&min), "{} min", arg_type);
+            assert!(values.contains(&max), "{} max", arg_type);
+        }
+    }
+
+    #[test]
+    fn test_splice() {
+        let seven = RawField::from_u64(7);
+        let zero = RawField::from_u64(0);
+        let mut inputs = Corpus::default();
+        assert!(inputs.add_input(&[seven; 4]));
+        assert!(!inputs.add_input(&[seven; 4]));
+        assert_eq!(inputs.input(0), Some(vec![seven; 4]));
+        assert_eq!(inputs.input(1), None);
+
+        let mut mutator = Mutator::<RawField>::new().seed(1);
+        let splices: [SpliceFn<RawField>; 2] = [Mutator::splice_overwrite, Mutator::splice_insert];
+        for splice in splices {
+            for _ in 0..100 {
+                mutator.input = vec![zero; 4];
+                splice(&mut mutator, &inputs);
+                // The length is kept and the chunk comes from the donor
+                assert_eq!(mutator.input.len(), 4);
+                assert!(mutator.input.contains(&seven));
+                assert!(mutator
+                    .input
+                    .iter()
+                    .all(|felt| *felt == zero || *felt == seven));
+            }
+            // Nothing to splice without other inputs
+            mutator.input = vec![zero; 4];
+            splice(&mut mutator, &EmptyDatabase);
+            assert_eq!(mutator.input, vec![zero; 4]);
+        }
+    }
+
+    #[test]
+    fn test_builtins_disabled() {
+        let mut mutator = Mutator::<RawField>::new().seed(1).builtins(false);
+        mutator.input = vec![RawField::from_u64(3); 2];
+        // Without builtin nor custom strategy the input is left untouched
+        mutator.mutate(4, &EmptyDatabase);
+        assert_eq!(mutator.input, vec![RawField::from_u64(3); 2]);
+        assert!(mutator.last_strategies().is_empty());
+
+        // The builtin strategies still mutate the felts given by the custom strategies
+        mutator.types = vec!["core::integer::u8".to_string()];
+        let changed = (0..100)
+            .filter(|_| mutator.mutate_felt(&RawField::from_u64(3)) != RawField::from_u64(3))
+            .count();
+        assert!(changed > 0);
+        // The input and the types are restored
+        assert_eq!(mutator.input, vec![RawField::from_u64(3); 2]);
+        assert_eq!(mutator.types, vec!["core::integer::u8".to_string()]);
+    }
+
+    #[test]
+    fn test_overwrite() {
+        let sentinel = RawField::from_u64(1000);
+        let overwrites: [(&str, BuiltinFn<RawField>); 2] = [
+            ("overwrite_rand", Mutator::overwrite_rand),
+            ("magic_overwrite", Mutator::magic_overwrite),
+        ];
+        for (name, overwrite) in overwrites {
+            let mut mutator = Mutator::<RawField>::new().seed(1);
+            mutator.input = vec![sentinel; 4];
+            for _ in 0..10 {
+                overwrite(&mut mutator);
+            }
+            // The felts are written into the input, which keeps its length
+            assert_eq!(mutator.input.len(), 4, "{}", name);
+            assert!(
+                mutator.input.iter().any(|felt| *felt != sentinel),
+                "{}",
+                name
+            );
+        }
+    }
+}
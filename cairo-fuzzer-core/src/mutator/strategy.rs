@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use super::mutator_felt252::{BuiltinFn, InputDatabase, Mutator, SpliceFn};
+use crate::felt::Field;
+
+/// A mutation strategy that can be registered in the `Mutator`
+///
+/// Strategies corrupt `mutator.input` in place and must keep its length unchanged,
+/// the runner only accepts inputs with one felt per argument. Randomness should come
+/// from `Mutator::rand` so the fuzzing session stays reproducible from its seed.
+pub trait MutationStrategy<F: Field> {
+    /// Name displayed in the strategy statistics
+    fn name(&self) -> &str;
+
+    /// Mutates `mutator.input`, `inputs` gives access to the other corpus entries
+    fn mutate(&mut self, mutator: &mut Mutator<F>, inputs: &dyn InputDatabase<F::Felt>);
+}
+
+/// Creates a new instance of a custom strategy for each fuzzing thread
+pub type StrategyFactory<F> = Arc<dyn Fn() -> Box<dyn MutationStrategy<F>> + Send + Sync>;
+
+/// A strategy implemented by a `Mutator` method
+pub(crate) struct Builtin<F: Field> {
+    pub name: &'static str,
+    pub func: BuiltinFn<F>,
+}
+
+impl<F: Field> MutationStrategy<F> for Builtin<F> {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn mutate(&mut self, mutator: &mut Mutator<F>, _inputs: &dyn InputDatabase<F::Felt>) {
+        (self.func)(mutator)
+    }
+}
+
+/// A builtin strategy which needs the input database
+pub(crate) struct BuiltinSplice<F: Field> {
+    pub name: &'static str,
+    pub func: SpliceFn<F>,
+}
+
+impl<F: Field> MutationStrategy<F> for BuiltinSplice<F> {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn mutate(&mut self, mutator: &mut Mutator<F>, inputs: &dyn InputDatabase<F::Felt>) {
+        (self.func)(mutator, inputs)
+    }
+}
@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+
+use crate::stats_log::StatsRecord;
+
+/// Size of the chart, in pixels
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 450.0;
+/// Space around the plot area for the title, the axes labels and the ticks
+const MARGIN_LEFT: f64 = 80.0;
+const MARGIN_RIGHT: f64 = 20.0;
+const MARGIN_TOP: f64 = 40.0;
+const MARGIN_BOTTOM: f64 = 50.0;
+/// Number of intervals between the ticks of an axis
+const TICKS: usize = 5;
+/// Colors of the series, reused when there are more series than colors
+const COLORS: [&str; 8] = [
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#7f7f7f",
+];
+
+/// Statistic charted over time
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum Metric {
+    #[default]
+    Coverage,
+    Execs,
+    ExecsPerSec,
+    /// Number of inputs of the corpus
+    Corpus,
+    Crashes,
+    UniqueCrashes,
+}
+
+impl Metric {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Metric::Coverage => "coverage",
+            Metric::Execs => "executions",
+            Metric::ExecsPerSec => "executions per second",
+            Metric::Corpus => "corpus size",
+            Metric::Crashes => "crashes",
+            Metric::UniqueCrashes => "unique crashes",
+        }
+    }
+
+    pub fn value(&self, record: &StatsRecord) -> f64 {
+        match self {
+            Metric::Coverage => record.coverage as f64,
+            Metric::Execs => record.execs as f64,
+            Metric::ExecsPerSec => record.execs_per_sec,
+            Metric::Corpus => record.corpus as f64,
+            Metric::Crashes => (record.crashes + record.tx_crashes) as f64,
+            Metric::UniqueCrashes => (record.unique_crashes + record.unique_tx_crashes) as f64,
+        }
+    }
+}
+
+/// Points of a stats log charted as a line, labeled in the legend
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Series {
+    pub label: String,
+    /// Points as (x, y)
+    pub points: Vec<(f64, f64)>,
+}
+
+impl Series {
+    /// Series of the metric over the time of the records
+    pub fn from_records(label: &str, records: &[StatsRecord], metric: Metric) -> Self {
+        Series {
+            label: label.to_string(),
+            points: records
+                .iter()
+                .map(|record| (record.time, metric.value(record)))
+                .collect(),
+        }
+    }
+}
+
+/// Renders the series as an SVG line chart, the axes start at 0
+pub fn render_svg(title: &str, x_label: &str, y_label: &str, series: &[Series]) -> String {
+    let points = series.iter().flat_map(|series| &series.points);
+    let x_max = nice_max(points.clone().map(|(x, _)| *x).fold(0.0, f64::max));
+    let y_max = nice_max(points.map(|(_, y)| *y).fold(0.0, f64::max));
+    let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_height = HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let x_pos = |x: f64| MARGIN_LEFT + x / x_max * plot_width;
+    let y_pos = |y: f64| MARGIN_TOP + plot_height - y / y_max * plot_height;
+
+    let mut svg = vec![
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" font-family=\"sans-serif\" font-size=\"12\">",
+            WIDTH, HEIGHT, WIDTH, HEIGHT
+        ),
+        format!("<rect width=\"{}\" height=\"{}\" fill=\"white\"/>", WIDTH, HEIGHT),
+        format!(
+            "<text x=\"{}\" y=\"24\" text-anchor=\"middle\" font-size=\"16\">{}</text>",
+            WIDTH / 2.0,
+            escape(title)
+        ),
+    ];
+
+    // Grid and ticks
+    for tick in 0..=TICKS {
+        let ratio = tick as f64 / TICKS as f64;
+        let (x, y) = (x_pos(ratio * x_max), y_pos(ratio * y_max));
+        svg.push(format!(
+            "<line x1=\"{:.1}\" y1=\"{}\" x2=\"{:.1}\" y2=\"{}\" stroke=\"#e0e0e0\"/>",
+            x,
+            MARGIN_TOP,
+            x,
+            MARGIN_TOP + plot_height
+        ));
+        svg.push(format!(
+            "<line x1=\"{}\" y1=\"{:.1}\" x2=\"{}\" y2=\"{:.1}\" stroke=\"#e0e0e0\"/>",
+            MARGIN_LEFT,
+            y,
+            MARGIN_LEFT + plot_width,
+            y
+        ));
+        svg.push(format!(
+            "<text x=\"{:.1}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+            x,
+            MARGIN_TOP + plot_height + 16.0,
+            format_tick(ratio * x_max)
+        ));
+        svg.push(format!(
+            "<text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\" dominant-baseline=\"middle\">{}</text>",
+            MARGIN_LEFT - 6.0,
+            y,
+            format_tick(ratio * y_max)
+        ));
+    }
+    svg.push(format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"black\"/>",
+        MARGIN_LEFT, MARGIN_TOP, plot_width, plot_height
+    ));
+    svg.push(format!(
+        "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+        MARGIN_LEFT + plot_width / 2.0,
+        HEIGHT - 12.0,
+        escape(x_label)
+    ));
+    svg.push(format!(
+        "<text x=\"16\" y=\"{}\" text-anchor=\"middle\" transform=\"rotate(-90 16 {})\">{}</text>",
+        MARGIN_TOP + plot_height / 2.0,
+        MARGIN_TOP + plot_height / 2.0,
+        escape(y_label)
+    ));
+
+    // Lines and legend
+    for (i, series) in series.iter().enumerate() {
+        let color = COLORS[i % COLORS.len()];
+        let points: Vec<String> = series
+            .points
+            .iter()
+            .map(|(x, y)| format!("{:.1},{:.1}", x_pos(*x), y_pos(*y)))
+            .collect();
+        svg.push(format!(
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>",
+            points.join(" "),
+            color
+        ));
+        let legend_y = MARGIN_TOP + 16.0 + i as f64 * 16.0;
+        svg.push(format!(
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"2\"/>",
+            MARGIN_LEFT + 10.0,
+            legend_y,
+            MARGIN_LEFT + 30.0,
+            legend_y,
+            color
+        ));
+        svg.push(format!(
+            "<text x=\"{}\" y=\"{}\" dominant-baseline=\"middle\">{}</text>",
+            MARGIN_LEFT + 36.0,
+            legend_y,
+            escape(&series.label)
+        ));
+    }
+
+    svg.push("</svg>".to_string());
+    let mut svg = svg.join("\n");
+    svg.push('\n');
+    svg
+}
+
+/// Rounds the maximum of an axis up to 1, 2 or 5 times a power of ten
+fn nice_max(max: f64) -> f64 {
+    if max <= 0.0 {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(max.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|step| step * magnitude)
+        .find(|nice| *nice >= max)
+        .unwrap_or(10.0 * magnitude)
+}
+
+/// Formats a tick value, with a k or M suffix for the large values
+fn format_tick(value: f64) -> String {
+    let (value, suffix) = match value {
+        v if v >= 1e6 => (v / 1e6, "M"),
+        v if v >= 1e3 => (v / 1e3, "k"),
+        v => (v, ""),
+    };
+    let value = format!("{:.2}", value);
+    let value = value.trim_end_matches('0').trim_end_matches('.');
+    format!("{}{}", value, suffix)
+}
+
+/// Escapes the XML special characters of a text
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_svg() {
+        let records = vec![
+            StatsRecord {
+                time: 1.0,
+                coverage: 10,
+                ..Default::default()
+            },
+            StatsRecord {
+                time: 4.0,
+                coverage: 40,
+                ..Default::default()
+            },
+        ];
+        let series = Series::from_records("v1 <old>", &records, Metric::Coverage);
+        assert_eq!(series.points, vec![(1.0, 10.0), (4.0, 40.0)]);
+
+        let svg = render_svg("coverage", "time (s)", "coverage", &[series]);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>\n"));
+        // The axes are rounded up to 5 and 50
+        assert!(svg.contains("<polyline points=\"220.0,328.0 640.0,112.0\""));
+        assert!(svg.contains(">v1 &lt;old&gt;</text>"));
+
+        assert_eq!(nice_max(0.0), 1.0);
+        assert_eq!(nice_max(40.0), 50.0);
+        assert_eq!(nice_max(1200.0), 2000.0);
+        assert_eq!(format_tick(2500.0), "2.5k");
+        assert_eq!(format_tick(10.0), "10");
+    }
+}
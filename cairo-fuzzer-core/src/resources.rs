@@ -0,0 +1,45 @@
+use std::collections::BTreeMap;
+
+use crate::runner::ExecutionResult;
+
+/// Resources consumed by an execution
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExecutionCost {
+    /// Number of Cairo steps, or consumed gas on cairo-native
+    pub steps: u64,
+    /// Number of instances of each builtin
+    pub builtins: BTreeMap<String, u64>,
+}
+
+impl ExecutionCost {
+    pub fn from_result<F>(result: &ExecutionResult<F>) -> Self {
+        ExecutionCost {
+            steps: result.steps,
+            builtins: result.builtins.clone(),
+        }
+    }
+}
+
+/// Resource exhaustion detection settings
+///
+/// An execution running more than `max_steps` Cairo steps is reported as a resource
+/// exhaustion finding (unbounded loops, attacker controlled iterations...).
+/// With `maximize`, inputs executing more steps than any previous input are added to
+/// the corpus even without new coverage, steering the fuzzer toward expensive paths.
+/// On cairo-native, the consumed gas stands for the steps. It is measured on the program
+/// instrumented for coverage, so it includes the gas of the libfuncs recording the edges
+/// of every block reached, and `max_steps` should leave room for this overhead.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResourceLimits {
+    /// Steps threshold, 0 disables the detection
+    pub max_steps: u64,
+    /// Treat higher step counts as new coverage
+    pub maximize: bool,
+}
+
+impl ResourceLimits {
+    /// Returns true if the execution cost exceeds the threshold
+    pub fn exceeded(&self, cost: &ExecutionCost) -> bool {
+        self.max_steps > 0 && cost.steps > self.max_steps
+    }
+}
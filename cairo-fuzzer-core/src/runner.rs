@@ -0,0 +1,68 @@
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use serde::{Deserialize, Serialize};
+
+use crate::felt::Field;
+
+/// Execution engine of a fuzzing campaign
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum Engine {
+    /// Cairo VM, through starknet_in_rust
+    #[default]
+    Vm,
+    /// cairo-native JIT or AOT compiled program
+    Native,
+}
+
+/// Result of an execution, independent of the engine
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionResult<F> {
+    /// Set if the function panicked
+    pub failure_flag: bool,
+    /// Return data, or panic data if the function panicked
+    pub retdata: Vec<F>,
+    /// Panic reason decoded by the engine, if any
+    pub error_msg: Option<String>,
+    /// Coverage points reached by the execution, in execution order
+    /// The pcs of the trace on the VM, a path signature on cairo-native
+    pub coverage: Vec<u64>,
+    /// Cost of the execution: Cairo steps on the VM, consumed gas on cairo-native
+    pub steps: u64,
+    /// Number of instances of each builtin used by the execution
+    pub builtins: BTreeMap<String, u64>,
+    /// Storage writes of the execution as (key, value)
+    pub storage_writes: Vec<(F, F)>,
+}
+
+impl<F> Default for ExecutionResult<F> {
+    fn default() -> Self {
+        ExecutionResult {
+            failure_flag: false,
+            retdata: Vec::new(),
+            error_msg: None,
+            coverage: Vec::new(),
+            steps: 0,
+            builtins: BTreeMap::new(),
+            storage_writes: Vec::new(),
+        }
+    }
+}
+
+/// An execution engine running the fuzzed function
+///
+/// `run` takes the runner by value and gives it back, so that runners keeping a state
+/// between executions can move it without cloning. An `Err` is returned when the engine
+/// could not execute the function at all, a panic of the function is reported with the
+/// `failure_flag` of the result.
+pub trait Runner: Sized {
+    /// Felt type of the engine
+    type Felt: Clone + Debug + Eq + Hash;
+    /// Field arithmetic on the felts of the engine, used by the mutator
+    type Field: Field<Felt = Self::Felt>;
+
+    fn run(self, data: &[Self::Felt]) -> Result<(Self, ExecutionResult<Self::Felt>), String>;
+}
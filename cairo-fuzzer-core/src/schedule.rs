@@ -0,0 +1,271 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use serde::{Deserialize, Serialize};
+
+use crate::rng::Rng;
+
+/// Number of picks after which the energies are recomputed, the `fast` and `rare`
+/// energies depend on the pick and hit counters
+const REFRESH_PICKS: u64 = 256;
+
+/// Power schedule used to pick the corpus entry to mutate
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum Schedule {
+    /// Every corpus entry has the same energy
+    #[default]
+    Uniform,
+    /// Favored, cheap and recent entries get more energy
+    Explore,
+    /// Like `explore`, but the energy decreases each time an entry is fuzzed
+    Fast,
+    /// Entries covering the edges the fewest executions reached get more energy
+    Rare,
+}
+
+/// Coverage and cost of a corpus entry
+#[derive(Debug, Clone, Default)]
+pub struct SeedMeta {
+    /// Edges covered by the entry
+    pub edges: Vec<u64>,
+    /// Number of Cairo steps executed by the entry
+    pub steps: u64,
+}
+
+/// A corpus entry and its scheduling state
+struct Seed<I> {
+    input: I,
+    meta: SeedMeta,
+    /// Number of times the entry was picked
+    fuzzed: u64,
+    /// Order in which the entry was added to the corpus
+    added: u64,
+}
+
+/// Picks the corpus entries to mutate according to a power schedule
+pub struct Scheduler<I> {
+    schedule: Schedule,
+    seeds: Vec<Seed<I>>,
+    /// Inputs already in the scheduler
+    known: HashSet<I>,
+    /// Number of executions that reached each edge
+    edge_hits: HashMap<u64, u64>,
+    /// Smallest set of cheapest entries covering all the known edges
+    favored: HashSet<usize>,
+    /// Set when a new entry may change the favored set and the energies
+    dirty: bool,
+    /// Cumulative energies of the entries, sampled with a binary search
+    cumulative: Vec<f64>,
+    /// Number of picks since the energies were computed
+    picks: u64,
+}
+
+/// Returns the edges (pairs of consecutive pcs) of an execution trace
+pub fn trace_edges(pcs: impl Iterator<Item = usize>) -> Vec<u64> {
+    let mut edges = HashSet::new();
+    let mut prev = 0;
+    for pc in pcs {
+        edges.insert(((prev as u64) << 32) | pc as u64);
+        prev = pc;
+    }
+    edges.into_iter().collect()
+}
+
+impl<I: Clone + Eq + Hash> Scheduler<I> {
+    pub fn new(schedule: Schedule) -> Self {
+        Scheduler {
+            schedule,
+            seeds: Vec::new(),
+            known: HashSet::new(),
+            edge_hits: HashMap::new(),
+            favored: HashSet::new(),
+            dirty: false,
+            cumulative: Vec::new(),
+            picks: 0,
+        }
+    }
+
+    /// Number of entries in the scheduler
+    pub fn len(&self) -> usize {
+        self.seeds.len()
+    }
+
+    /// Returns true if no entry was added
+    pub fn is_empty(&self) -> bool {
+        self.seeds.is_empty()
+    }
+
+    /// Records the edges reached by an execution
+    pub fn observe(&mut self, edges: &[u64]) {
+        for edge in edges {
+            *self.edge_hits.entry(*edge).or_insert(0) += 1;
+        }
+    }
+
+    /// Adds a corpus entry, returns false if it is already known
+    pub fn add(&mut self, input: I, meta: SeedMeta) -> bool {
+        if !self.known.insert(input.clone()) {
+            return false;
+        }
+        self.seeds.push(Seed {
+            input,
+            meta,
+            fuzzed: 0,
+            added: self.seeds.len() as u64,
+        });
+        self.dirty = true;
+        true
+    }
+
+    /// Picks an entry with a probability proportional to its energy
+    pub fn pick(&mut self, rng: &Rng) -> Option<&I> {
+        if self.seeds.is_empty() {
+            return None;
+        }
+        if self.dirty || self.picks >= REFRESH_PICKS {
+            self.update_energies();
+        }
+        self.picks += 1;
+
+        let total = self.cumulative.last().copied().unwrap_or_default();
+        let target = rng.rand() as f64 / u64::MAX as f64 * total;
+        let selected = self
+            .cumulative
+            .partition_point(|&energy| energy < target)
+            .min(self.seeds.len() - 1);
+
+        let seed = &mut self.seeds[selected];
+        seed.fuzzed += 1;
+        Some(&seed.input)
+    }
+
+    /// Computes the cumulative energies of the entries
+    fn update_energies(&mut self) {
+        if self.dirty {
+            self.update_favored();
+        }
+        let average_steps =
+            self.seeds.iter().map(|s| s.meta.steps).sum::<u64>() as f64 / self.seeds.len() as f64;
+        let mut total = 0.0;
+        self.cumulative = (0..self.seeds.len())
+            .map(|idx| {
+                total += self.energy(idx, average_steps);
+                total
+            })
+            .collect();
+        self.picks = 0;
+    }
+
+    /// Energy of an entry according to the schedule
+    fn energy(&self, idx: usize, average_steps: f64) -> f64 {
+        let seed = &self.seeds[idx];
+        if self.schedule == Schedule::Uniform {
+            return 1.0;
+        }
+
+        // Favored entries cover the known edges with the cheapest inputs
+        let favored = if self.favored.contains(&idx) {
+            4.0
+        } else {
+            1.0
+        };
+
+        // Recent entries are closer to the coverage frontier
+        let recency = 1.0 + seed.added as f64 / self.seeds.len() as f64;
+
+        // Cheap entries allow more executions per second
+        let cost = if seed.meta.steps == 0 || average_steps == 0.0 {
+            1.0
+        } else {
+            (average_steps / seed.meta.steps as f64).clamp(0.25, 4.0)
+        };
+
+        match self.schedule {
+            Schedule::Uniform => 1.0,
+            Schedule::Explore => favored * recency * cost,
+            Schedule::Fast => favored * recency * cost / (1 + seed.fuzzed) as f64,
+            Schedule::Rare => {
+                // Inverse of the hit count of the rarest edge covered by the entry
+                let rarest = seed
+                    .meta
+                    .edges
+                    .iter()
+                    .map(|edge| self.edge_hits.get(edge).copied().unwrap_or(1).max(1))
+                    .min()
+                    .unwrap_or(1);
+                favored * cost / rarest as f64
+            }
+        }
+    }
+
+    /// Keeps, for each edge, the entry with the fewest steps covering it
+    fn update_favored(&mut self) {
+        let mut top_rated: HashMap<u64, usize> = HashMap::new();
+        for (idx, seed) in self.seeds.iter().enumerate() {
+            for edge in &seed.meta.edges {
+                let best = top_rated.entry(*edge).or_insert(idx);
+                if self.seeds[*best].meta.steps > seed.meta.steps {
+                    *best = idx;
+                }
+            }
+        }
+        self.favored = top_rated.into_values().collect();
+        self.dirty = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{trace_edges, Schedule, Scheduler, SeedMeta, REFRESH_PICKS};
+    use crate::rng::Rng;
+
+    #[test]
+    fn test_scheduler() {
+        let mut scheduler: Scheduler<Vec<u64>> = Scheduler::new(Schedule::Rare);
+        let rng = Rng::seeded(1);
+        assert!(scheduler.pick(&rng).is_none());
+
+        let common = trace_edges([0, 1, 2].into_iter());
+        let rare = trace_edges([0, 1, 3].into_iter());
+        for _ in 0..100 {
+            scheduler.observe(&common);
+        }
+        scheduler.observe(&rare);
+        let meta = |edges: &Vec<u64>| SeedMeta {
+            edges: edges.clone(),
+            steps: 10,
+        };
+        assert!(scheduler.add(vec![1], meta(&common)));
+        assert!(scheduler.add(vec![2], meta(&rare)));
+        assert!(!scheduler.add(vec![2], meta(&rare)));
+        assert_eq!(scheduler.len(), 2);
+
+        // The entry covering the rare edge gets most of the energy
+        let rare_picks = (0..1000)
+            .filter(|_| scheduler.pick(&rng) == Some(&vec![2]))
+            .count();
+        assert!(rare_picks > 900);
+    }
+
+    #[test]
+    fn test_fast_schedule() {
+        let mut scheduler: Scheduler<Vec<u64>> = Scheduler::new(Schedule::Fast);
+        let rng = Rng::seeded(1);
+        let meta = SeedMeta {
+            edges: Vec::new(),
+            steps: 10,
+        };
+        scheduler.add(vec![1], meta.clone());
+        for _ in 0..10 * REFRESH_PICKS {
+            scheduler.pick(&rng);
+        }
+        // The energy of the fuzzed entry decreased when the energies were refreshed
+        scheduler.add(vec![2], meta);
+        let new_picks = (0..REFRESH_PICKS)
+            .filter(|_| scheduler.pick(&rng) == Some(&vec![2]))
+            .count();
+        assert!(new_picks as u64 > REFRESH_PICKS * 9 / 10);
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Columns of the CSV stats log, in order
+pub const CSV_COLUMNS: [&str; 11] = [
+    "time",
+    "execs",
+    "execs_per_sec",
+    "coverage",
+    "corpus",
+    "crashes",
+    "unique_crashes",
+    "tx_crashes",
+    "unique_tx_crashes",
+    "crash_buckets",
+    "strategies",
+];
+
+/// Format of the stats log
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum StatsFormat {
+    /// Comma separated values with a header line
+    #[default]
+    Csv,
+    /// One JSON object per line
+    Jsonl,
+}
+
+impl StatsFormat {
+    /// Extension of the stats log files
+    pub fn extension(&self) -> &'static str {
+        match self {
+            StatsFormat::Csv => "csv",
+            StatsFormat::Jsonl => "jsonl",
+        }
+    }
+
+    /// Format of a stats log file, from its extension
+    pub fn from_path(path: &str) -> Option<Self> {
+        match path.rsplit_once('.')?.1 {
+            "csv" => Some(StatsFormat::Csv),
+            "jsonl" => Some(StatsFormat::Jsonl),
+            _ => None,
+        }
+    }
+}
+
+/// Uses and finds of a mutation strategy
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct StrategyRecord {
+    pub uses: u64,
+    /// Number of mutations adding an input to the corpus
+    pub finds: u64,
+}
+
+/// Statistics of a fuzzing session at a point in time, one line of the stats log
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct StatsRecord {
+    /// Seconds since the start of the session
+    pub time: f64,
+    pub execs: u64,
+    /// Executions per second since the previous record
+    pub execs_per_sec: f64,
+    pub coverage: usize,
+    /// Number of inputs of the corpus
+    pub corpus: usize,
+    pub crashes: u64,
+    pub unique_crashes: usize,
+    /// Crashes of the transactions sequences
+    pub tx_crashes: u64,
+    pub unique_tx_crashes: usize,
+    /// Crashes by error message
+    #[serde(default)]
+    pub crash_buckets: BTreeMap<String, u64>,
+    /// Uses and finds of each mutation strategy
+    #[serde(default)]
+    pub strategies: BTreeMap<String, StrategyRecord>,
+}
+
+impl StatsRecord {
+    /// Header line of the CSV stats log
+    pub fn csv_header() -> String {
+        CSV_COLUMNS.join(",")
+    }
+
+    /// Formats the record as a CSV line
+    ///
+    /// The crash buckets are written as `message=hits` and the strategies as
+    /// `name=finds/uses`, separated by `;`.
+    pub fn to_csv(&self) -> String {
+        let crash_buckets: Vec<String> = self
+            .crash_buckets
+            .iter()
+            .map(|(message, hits)| format!("{}={}", escape(message), hits))
+            .collect();
+        let strategies: Vec<String> = self
+            .strategies
+            .iter()
+            .map(|(name, s)| format!("{}={}/{}", escape(name), s.finds, s.uses))
+            .collect();
+        [
+            format!("{:.3}", self.time),
+            self.execs.to_string(),
+            format!("{:.2}", self.execs_per_sec),
+            self.coverage.to_string(),
+            self.corpus.to_string(),
+            self.crashes.to_string(),
+            self.unique_crashes.to_string(),
+            self.tx_crashes.to_string(),
+            self.unique_tx_crashes.to_string(),
+            quote(&crash_buckets.join(";")),
+            quote(&strategies.join(";")),
+        ]
+        .join(",")
+    }
+
+    /// Parses a CSV line, the columns are matched by name with the header
+    pub fn from_csv(header: &[String], line: &str) -> Result<Self, String> {
+        let fields = split_csv(line)?;
+        if fields.len() != header.len() {
+            return Err(format!(
+                "expected {} columns, found {}",
+                header.len(),
+                fields.len()
+            ));
+        }
+        let mut record = StatsRecord::default();
+        for (column, field) in header.iter().zip(&fields) {
+            let number = |field: &str| {
+                field
+                    .parse::<f64>()
+                    .map_err(|_| format!("invalid {} {}", column, field))
+            };
+            match column.as_str() {
+                "time" => record.time = number(field)?,
+                "execs" => record.execs = number(field)? as u64,
+                "execs_per_sec" => record.execs_per_sec = number(field)?,
+                "coverage" => record.coverage = number(field)? as usize,
+                "corpus" => record.corpus = number(field)? as usize,
+                "crashes" => record.crashes = number(field)? as u64,
+                "unique_crashes" => record.unique_crashes = number(field)? as usize,
+                "tx_crashes" => record.tx_crashes = number(field)? as u64,
+                "unique_tx_crashes" => record.unique_tx_crashes = number(field)? as usize,
+                "crash_buckets" => {
+                    for (message, hits) in split_map(field)? {
+                        record.crash_buckets.insert(message, number(&hits)? as u64);
+                    }
+                }
+                "strategies" => {
+                    for (name, stats) in split_map(field)? {
+                        let (finds, uses) = stats
+                            .split_once('/')
+                            .ok_or_else(|| format!("invalid strategy {}={}", name, stats))?;
+                        let strategy = StrategyRecord {
+                            uses: number(uses)? as u64,
+                            finds: number(finds)? as u64,
+                        };
+                        record.strategies.insert(name, strategy);
+                    }
+                }
+                // Columns added by later versions are ignored
+                _ => {}
+            }
+        }
+        Ok(record)
+    }
+}
+
+/// Parses a CSV stats log
+pub fn parse_csv(contents: &str) -> Result<Vec<StatsRecord>, String> {
+    let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+    let header = match lines.next() {
+        Some(header) => split_csv(header)?,
+        None => return Ok(Vec::new()),
+    };
+    if !header.iter().any(|column| column == "time") {
+        return Err("the CSV header has no time column".to_string());
+    }
+    lines
+        .enumerate()
+        .map(|(i, line)| {
+            StatsRecord::from_csv(&header, line).map_err(|e| format!("line {}: {}", i + 2, e))
+        })
+        .collect()
+}
+
+/// Quotes a CSV field if it contains a separator or a quote
+fn quote(field: &str) -> String {
+    match field.contains([',', '"', '\n']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+/// Splits a CSV line into its fields, unquoting them
+fn split_csv(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => quoted = false,
+            ('"', false) if field.is_empty() => quoted = true,
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field".to_string());
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+/// Escapes the separators of the `key=value;...` maps in the keys, the line breaks are
+/// replaced by spaces to keep one record per line
+fn escape(key: &str) -> String {
+    key.replace('\n', " ")
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace('=', "\\=")
+}
+
+/// Splits a `key=value;...` map, unescaping the keys
+fn split_map(field: &str) -> Result<Vec<(String, String)>, String> {
+    let mut entries = Vec::new();
+    let mut key = String::new();
+    let mut value: Option<String> = None;
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        match value.as_mut() {
+            None if c == '\\' => key.push(chars.next().ok_or("invalid escape at end of field")?),
+            None if c == '=' => value = Some(String::new()),
+            None => key.push(c),
+            Some(_) if c == ';' => {
+                entries.push((std::mem::take(&mut key), value.take().unwrap_or_default()))
+            }
+            Some(value) => value.push(c),
+        }
+    }
+    match value {
+        Some(value) => entries.push((key, value)),
+        None if !key.is_empty() => return Err(format!("invalid entry {}", key)),
+        None => {}
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_csv() {
+        let mut record = StatsRecord {
+            time: 2.5,
+            execs: 1000,
+            execs_per_sec: 400.0,
+            coverage: 12,
+            corpus: 4,
+            crashes: 3,
+            unique_crashes: 2,
+            ..Default::default()
+        };
+        record
+            .crash_buckets
+            .insert("Error at pc=0:12, a;b \"c\"".to_string(), 2);
+        record.crash_buckets.insert("overflow".to_string(), 1);
+        record.strategies.insert(
+            "bit_flip".to_string(),
+            StrategyRecord { uses: 10, finds: 1 },
+        );
+
+        let contents = format!("{}\n{}\n", StatsRecord::csv_header(), record.to_csv());
+        assert_eq!(parse_csv(&contents).unwrap(), vec![record.clone()]);
+
+        // Columns are matched by name
+        let records = parse_csv("execs,time\n10,1.0\n").unwrap();
+        assert_eq!(records[0].execs, 10);
+        assert_eq!(records[0].time, 1.0);
+        assert!(parse_csv("execs\n10\n").is_err());
+        assert!(parse_csv("time,execs\n1.0\n").is_err());
+
+        assert_eq!(
+            StatsFormat::from_path("stats.jsonl"),
+            Some(StatsFormat::Jsonl)
+        );
+        assert_eq!(StatsFormat::from_path("stats.txt"), None);
+    }
+}
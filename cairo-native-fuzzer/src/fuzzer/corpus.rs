@@ -0,0 +1,5 @@
+use starknet_types_core::felt::Felt;
+
+/// Inputs that reached new edges between the Sierra blocks
+/// (see `crate::runner::coverage::instrument_program`)
+pub type Corpus = cairo_fuzzer_core::corpus::Corpus<Felt>;
@@ -0,0 +1,174 @@
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Range;
+
+use cairo_fuzzer_core::coverage::{
+    CoverageAnnotations, CoverageReport, SourceMap, COVERAGE_ANNOTATIONS,
+};
+use cairo_lang_sierra::program::Program;
+use cairo_lang_starknet_classes::contract_class::ContractClass;
+
+use crate::runner::coverage::block_starts;
+
+/// Source coverage of the executions of a function
+///
+/// The executions record the edges between the Sierra blocks they reached (see
+/// `crate::runner::coverage::instrument_program`), all the statements of a reached block
+/// are executed. The statements are mapped to the Cairo source lines using the annotations
+/// of the Sierra debug info.
+pub struct SourceCoverage {
+    source_map: SourceMap,
+    /// Statements of each block, by index of the first statement of the block
+    blocks: HashMap<usize, Range<usize>>,
+    /// Number of executions reaching each statement
+    hits: HashMap<usize, u64>,
+}
+
+impl SourceCoverage {
+    /// Maps the blocks of the Sierra program, before its instrumentation, to the sources of
+    /// the contract class
+    pub fn new(contract_class: &ContractClass, program: &Program) -> Result<Self, String> {
+        let value = serde_json::to_value(contract_class)
+            .map_err(|e| format!("Could not serialize the contract: {}", e))?;
+        let annotations = match value
+            .get("sierra_program_debug_info")
+            .and_then(|debug_info| debug_info.get("annotations"))
+            .and_then(|annotations| annotations.get(COVERAGE_ANNOTATIONS))
+        {
+            Some(annotations) => serde_json::from_value::<CoverageAnnotations>(annotations.clone())
+                .map_err(|e| format!("Could not parse the coverage annotations: {}", e))?,
+            None => {
+                return Err(
+                    "the Sierra debug info has no statements locations, compile the contract with \
+                     unstable-add-statements-code-locations-debug-info"
+                        .to_string(),
+                )
+            }
+        };
+        let source_map = SourceMap::from_annotations(&annotations)?;
+
+        let starts: Vec<usize> = block_starts(program).into_iter().collect();
+        let blocks = starts
+            .iter()
+            .enumerate()
+            .map(|(idx, start)| {
+                let end = starts
+                    .get(idx + 1)
+                    .copied()
+                    .unwrap_or(program.statements.len());
+                (*start, *start..end)
+            })
+            .collect();
+        let hits = (0..program.statements.len())
+            .map(|statement| (statement, 0))
+            .collect();
+
+        Ok(SourceCoverage {
+            source_map,
+            blocks,
+            hits,
+        })
+    }
+
+    /// Counts the statements of the blocks reached by an execution
+    pub fn add_edges(&mut self, edges: &[u64]) {
+        // The edges end at the blocks reached, an edge is recorded once per execution
+        let reached: BTreeSet<usize> = edges
+            .iter()
+            .map(|edge| (edge & u32::MAX as u64) as usize)
+            .collect();
+        for block in reached {
+            for statement in self.blocks.get(&block).cloned().unwrap_or_default() {
+                *self.hits.entry(statement).or_default() += 1;
+            }
+        }
+    }
+
+    /// Coverage of the source lines and functions by the added executions
+    pub fn report(&self) -> CoverageReport {
+        CoverageReport::new(&self.source_map, &self.hits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use cairo_fuzzer_core::runner::Runner;
+    use cairo_lang_compiler::CompilerConfig;
+    use cairo_lang_starknet::compile::compile_path;
+    use cairo_native::context::NativeContext;
+    use starknet_types_core::felt::Felt;
+
+    use super::*;
+    use crate::fuzzer::utils::find_entry_point_id;
+    use crate::runner::coverage::instrument_program;
+    use crate::runner::runner::{
+        compile_sierra_program, create_executor, NativeExecutor, NativeRunner,
+    };
+
+    #[test]
+    fn test_source_coverage() {
+        let contract = compile_path(
+            Path::new("examples/cairo/echo.cairo"),
+            None,
+            CompilerConfig {
+                replace_ids: true,
+                add_statements_functions: true,
+                add_statements_code_locations: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let sierra_program = contract.extract_sierra_program().unwrap();
+        let mut coverage = SourceCoverage::new(&contract, &sierra_program).unwrap();
+
+        let program = Arc::new(instrument_program(&sierra_program));
+        let entry_point_id = find_entry_point_id(&Some(program.clone()), "echo_felt");
+        let native_context = NativeContext::new();
+        let module = compile_sierra_program(&native_context, &program).unwrap();
+        let executor = NativeExecutor::Jit(create_executor(module).unwrap());
+        let mut run = |value: u64| {
+            let (_, result) = NativeRunner::new(&executor, &entry_point_id)
+                .run(&[Felt::from(value)])
+                .unwrap();
+            coverage.add_edges(&result.coverage);
+            coverage.report()
+        };
+        // Line 11 holds the assert, both executions reach it and only the second one fails it
+        let line_hits = |report: &CoverageReport, line: usize| {
+            let file = report
+                .files
+                .iter()
+                .find(|file| file.path.ends_with("echo.cairo"))
+                .expect("No coverage of echo.cairo");
+            file.lines[&line]
+        };
+        let report = run(1);
+        assert_eq!(line_hits(&report, 11), 1);
+        let lines_hit = report.lines_hit();
+        assert!(lines_hit < report.lines_found());
+        let report = run(2);
+        assert_eq!(line_hits(&report, 11), 2);
+        assert!(report.lines_hit() > lines_hit);
+        let echo_felt = report
+            .files
+            .iter()
+            .flat_map(|file| &file.functions)
+            .find(|function| function.name.ends_with("::echo_felt"))
+            .expect("No coverage of echo_felt");
+        assert_eq!(echo_felt.hits, 2);
+
+        // Without the statements locations the source lines are unknown
+        let contract = compile_path(
+            Path::new("examples/cairo/echo.cairo"),
+            None,
+            CompilerConfig {
+                replace_ids: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(SourceCoverage::new(&contract, &sierra_program).is_err());
+    }
+}
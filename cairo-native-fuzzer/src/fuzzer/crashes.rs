@@ -0,0 +1,155 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use cairo_fuzzer_core::crashes::{Crash, CrashTriage};
+use cairo_fuzzer_core::runner::ExecutionResult;
+use log::error;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use starknet_types_core::felt::Felt;
+
+/// Crashes found during a fuzzing campaign, deduplicated by error message
+pub struct CrashStore {
+    // Directory where the crashes are saved
+    crash_dir: PathBuf,
+    // Distinct crashes, in the order they were found
+    triage: CrashTriage<Felt>,
+}
+
+impl CrashStore {
+    /// Creates a crash store saving the crashes in `crash_dir`
+    pub fn new(crash_dir: PathBuf) -> Self {
+        Self {
+            crash_dir,
+            triage: CrashTriage::default(),
+        }
+    }
+
+    /// Records a crash and saves it to the crash directory if its error message is new
+    /// Returns true if this is a new distinct crash
+    pub fn record(&mut self, input: &[Felt], result: &ExecutionResult<Felt>) -> bool {
+        let error_msg = result.error_msg.clone().unwrap_or_default();
+        self.record_message(input, error_msg, &result.retdata)
+    }
+
+    /// Records a finding identified by `error_msg`, e.g. a resource exhaustion
+    /// Returns true if this is a new distinct crash
+    /// A crash that can't be saved is logged, the fuzzing goes on
+    pub fn record_message(
+        &mut self,
+        input: &[Felt],
+        error_msg: String,
+        return_values: &[Felt],
+    ) -> bool {
+        match self.triage.record(input, error_msg, return_values) {
+            Some(crash) => {
+                if let Err(e) = save_crash(&self.crash_dir, crash) {
+                    error!("{}", e);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Number of distinct crashes
+    pub fn len(&self) -> usize {
+        self.triage.len()
+    }
+
+    /// Returns true if no crash has been found
+    pub fn is_empty(&self) -> bool {
+        self.triage.is_empty()
+    }
+
+    /// Distinct crashes, in the order they were found
+    pub fn crashes(&self) -> &[Crash<Felt>] {
+        self.triage.crashes()
+    }
+
+    /// Error message and number of hits of each distinct crash
+    pub fn buckets(&self) -> Vec<(String, u64)> {
+        self.triage.buckets()
+    }
+
+    /// Prints the distinct crashes found during the campaign
+    pub fn print_summary(&self) {
+        if self.triage.is_empty() {
+            println!("No crash found.");
+            return;
+        }
+        println!(
+            "{} distinct crash(es) saved in {}:",
+            self.triage.len(),
+            self.crash_dir.display()
+        );
+        for crash in self.triage.crashes() {
+            println!(
+                "- {}: {:?} ({} hit(s)), input = {:?}",
+                crash_file_name(&crash.input),
+                crash.error_msg,
+                crash.hits,
+                crash.input
+            );
+        }
+    }
+}
+
+/// Name of the file of a crash, from the hash of its input
+/// The crashes of the previous runs saved in the same directory are kept
+pub fn crash_file_name(input: &[Felt]) -> String {
+    let mut hasher = Sha256::new();
+    for felt in input {
+        hasher.update(felt.to_bytes_be());
+    }
+    let hash: String = hasher.finalize()[..8]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("crash_{}.json", hash)
+}
+
+/// Writes the crash to `<crash_dir>/crash_<input hash>.json`
+fn save_crash(crash_dir: &Path, crash: &Crash<Felt>) -> Result<(), String> {
+    fs::create_dir_all(crash_dir)
+        .map_err(|e| format!("Failed to create crash directory: {}", e))?;
+    let content = json!({
+        "input": crash.input.iter().map(|felt| felt.to_hex_string()).collect::<Vec<_>>(),
+        "error_msg": crash.error_msg,
+        "return_values": crash
+            .return_values
+            .iter()
+            .map(|felt| felt.to_hex_string())
+            .collect::<Vec<_>>(),
+    });
+    let path = crash_dir.join(crash_file_name(&crash.input));
+    fs::write(&path, serde_json::to_string_pretty(&content).unwrap())
+        .map_err(|e| format!("Failed to save crash to {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crash_files() {
+        let crash_dir = std::env::temp_dir().join(format!("crash_store_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&crash_dir);
+
+        let mut store = CrashStore::new(crash_dir.clone());
+        assert!(store.record_message(&[Felt::from(1)], "overflow".to_string(), &[]));
+        assert!(!store.record_message(&[Felt::from(2)], "overflow".to_string(), &[]));
+        // A new run does not overwrite the crashes of the previous one
+        let mut store = CrashStore::new(crash_dir.clone());
+        assert!(store.record_message(&[Felt::from(3)], "overflow".to_string(), &[]));
+        assert!(crash_dir.join(crash_file_name(&[Felt::from(1)])).exists());
+        assert!(crash_dir.join(crash_file_name(&[Felt::from(3)])).exists());
+        assert_eq!(fs::read_dir(&crash_dir).unwrap().count(), 2);
+
+        // A crash that can't be saved is still recorded
+        let mut store = CrashStore::new(crash_dir.join(crash_file_name(&[Felt::from(1)])));
+        assert!(store.record_message(&[Felt::from(4)], "overflow".to_string(), &[]));
+        assert_eq!(store.len(), 1);
+        fs::remove_dir_all(&crash_dir).unwrap();
+    }
+}
@@ -0,0 +1,183 @@
+use std::fs;
+use std::path::Path;
+
+use cairo_fuzzer_core::runner::ExecutionResult;
+use serde_json::Value;
+use starknet_types_core::felt::Felt;
+
+/// Result of an execution on the cairo-vm runner, written by `cairo-fuzzer --diff-dump`
+pub struct VmRecord {
+    // Name of the executed function
+    pub function: String,
+    // Arguments of the function
+    pub calldata: Vec<Felt>,
+    // Set if the function panicked or could not be executed
+    pub failure_flag: bool,
+    // Return data, or panic data if the function panicked
+    pub retdata: Vec<Felt>,
+    // Error returned by the cairo-vm runner
+    pub error: Option<String>,
+    // Storage writes as (key, value)
+    pub storage_writes: Vec<(Felt, Felt)>,
+}
+
+/// Parses a `0x` prefixed hex felt
+fn parse_felt(value: &Value) -> Result<Felt, String> {
+    let hex = value
+        .as_str()
+        .ok_or(format!("expected a hex string, got {}", value))?;
+    Felt::from_hex(hex).map_err(|e| format!("invalid felt {}: {:?}", hex, e))
+}
+
+/// Parses an array of hex felts
+fn parse_felts(value: &Value) -> Result<Vec<Felt>, String> {
+    value
+        .as_array()
+        .ok_or(format!("expected an array, got {}", value))?
+        .iter()
+        .map(parse_felt)
+        .collect()
+}
+
+impl VmRecord {
+    /// Parses a JSON line of the dump
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let data: Value = serde_json::from_str(line).map_err(|e| e.to_string())?;
+        let storage_writes = data["storage_writes"]
+            .as_array()
+            .ok_or("missing storage_writes")?
+            .iter()
+            .map(|write| Ok((parse_felt(&write[0])?, parse_felt(&write[1])?)))
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self {
+            function: data["function"]
+                .as_str()
+                .ok_or("missing function")?
+                .to_string(),
+            calldata: parse_felts(&data["calldata"])?,
+            failure_flag: data["failure_flag"]
+                .as_bool()
+                .ok_or("missing failure_flag")?,
+            retdata: parse_felts(&data["retdata"])?,
+            error: data["error"].as_str().map(str::to_string),
+            storage_writes,
+        })
+    }
+}
+
+/// Loads the records of a dump, one JSON object per line
+pub fn load_records(path: &Path) -> Result<Vec<VmRecord>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            VmRecord::parse(line).map_err(|e| format!("{}:{}: {}", path.display(), idx + 1, e))
+        })
+        .collect()
+}
+
+/// Compares the cairo-vm and cairo-native results of the same calldata
+/// Returns a description of each difference
+///
+/// The return data and storage writes are only compared when both engines executed the
+/// function: a cairo-vm runner error has no return data.
+pub fn compare_results(
+    record: &VmRecord,
+    native_result: &Result<ExecutionResult<Felt>, String>,
+) -> Vec<String> {
+    let mut differences = Vec::new();
+    let result = match native_result {
+        Ok(result) => result,
+        Err(e) => {
+            if record.error.is_none() {
+                differences.push(format!("cairo-native error {:?}, cairo-vm executed", e));
+            }
+            return differences;
+        }
+    };
+
+    if record.error.is_some() {
+        if !result.failure_flag {
+            differences.push(format!(
+                "cairo-vm error {:?}, cairo-native succeeded",
+                record.error
+            ));
+        }
+        return differences;
+    }
+
+    if record.failure_flag != result.failure_flag {
+        differences.push(format!(
+            "failure flag: cairo-vm {}, cairo-native {} ({:?})",
+            record.failure_flag, result.failure_flag, result.error_msg
+        ));
+    }
+    if record.retdata != result.retdata {
+        differences.push(format!(
+            "return data: cairo-vm {:?}, cairo-native {:?}",
+            record.retdata, result.retdata
+        ));
+    }
+    // Both sides are sorted by key bytes, the felt ordering may differ between the engines
+    let mut vm_storage = record.storage_writes.clone();
+    vm_storage.sort_by_key(|(key, _)| key.to_bytes_be());
+    let mut native_storage = result.storage_writes.clone();
+    native_storage.sort_by_key(|(key, _)| key.to_bytes_be());
+    if !record.failure_flag && !result.failure_flag && vm_storage != native_storage {
+        differences.push(format!(
+            "storage writes: cairo-vm {:?}, cairo-native {:?}",
+            vm_storage, native_storage
+        ));
+    }
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_results() {
+        let record = VmRecord::parse(
+            r#"{"function":"echo_felt","calldata":["0x1"],"failure_flag":false,"retdata":["0x1"],"error":null,"storage_writes":[["0x2","0x3"],["0x1","0x4"]]}"#,
+        )
+        .unwrap();
+        let mut result = ExecutionResult {
+            retdata: vec![Felt::from(1)],
+            // The storage writes are compared whatever their order
+            storage_writes: vec![
+                (Felt::from(1), Felt::from(4)),
+                (Felt::from(2), Felt::from(3)),
+            ],
+            ..Default::default()
+        };
+        assert!(compare_results(&record, &Ok(result.clone())).is_empty());
+
+        result.retdata = vec![Felt::from(2)];
+        result.storage_writes.pop();
+        let differences = compare_results(&record, &Ok(result.clone()));
+        assert_eq!(differences.len(), 2);
+        assert!(differences[0].starts_with("return data"));
+        assert!(differences[1].starts_with("storage writes"));
+
+        // The storage writes of a panicking execution are not compared
+        result.failure_flag = true;
+        result.retdata = vec![Felt::from(1)];
+        let differences = compare_results(&record, &Ok(result.clone()));
+        assert_eq!(differences.len(), 1);
+        assert!(differences[0].starts_with("failure flag"));
+
+        let error = Err("Failed to deserialize param #1".to_string());
+        assert_eq!(compare_results(&record, &error).len(), 1);
+        // A cairo-vm runner error matches a native panic or error
+        let record = VmRecord {
+            error: Some("entry point not found".to_string()),
+            ..record
+        };
+        assert!(compare_results(&record, &error).is_empty());
+        assert!(compare_results(&record, &Ok(result)).is_empty());
+    }
+}
@@ -1,26 +1,42 @@
+use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use std::thread;
 use std::time::Instant;
 
+use cairo_fuzzer_core::coverage::CoverageReport;
+use cairo_fuzzer_core::dashboard::{FunctionProgress, FunctionState};
+use cairo_fuzzer_core::dict::Dict;
+use cairo_fuzzer_core::runner::Runner;
+use cairo_fuzzer_core::schedule::Schedule;
 use cairo_lang_compiler::CompilerConfig;
 use cairo_lang_sierra::ids::FunctionId;
 use cairo_lang_sierra::program::Program;
 use cairo_lang_starknet::compile::compile_path;
 use cairo_lang_starknet_classes::contract_class::ContractClass;
 use cairo_native::context::NativeContext;
-use cairo_native::executor::JitNativeExecutor;
+use cairo_native::executor::AotNativeExecutor;
 use starknet_types_core::felt::Felt;
 
+use crate::fuzzer::corpus::Corpus;
+use crate::fuzzer::coverage_report::SourceCoverage;
+use crate::fuzzer::crashes::CrashStore;
+use crate::fuzzer::differential::{compare_results, load_records};
 use crate::fuzzer::statistics::FuzzerStats;
 use crate::fuzzer::utils::{
     find_entry_point_id, get_function_argument_types, print_contract_functions, print_init_message,
 };
+use crate::fuzzer::worker::{monitor, Budget, FuzzerWorker, SharedState, WorkerConfig};
 use crate::mutator::argument_type::ArgumentType;
-use crate::mutator::basic_mutator::Mutator;
-use crate::runner::runner::{compile_sierra_program, create_executor, run_program};
+use crate::runner::coverage::instrument_program;
+use crate::runner::runner::{
+    compile_sierra_program, create_executor, load_aot_executor, run_program, NativeExecutor,
+    NativeRunner, StarkField,
+};
 
-use log::{error, info, warn};
+use log::{error, info};
 
 /// Struct representing the fuzzer
 pub struct Fuzzer {
@@ -28,14 +44,44 @@ pub struct Fuzzer {
     program_path: Option<PathBuf>,
     // Entry point of the Sierra program
     entry_point: Option<String>,
-    // Sierra program
+    // Contract class of the program, its debug info maps the Sierra statements to the sources
+    contract_class: Option<ContractClass>,
+    // Sierra program, instrumented to record the coverage
     sierra_program: Option<Arc<Program>>,
     // Entry point parameters
     params: Arc<Mutex<Vec<Felt>>>,
     // ID of the entry point
     entry_point_id: Option<FunctionId>,
-    // Mutator for the parameters
-    mutator: Option<Mutex<Mutator>>,
+    // Seed of the first worker, the other workers use the following seeds
+    seed: u64,
+    // Number of fuzzing threads
+    cores: usize,
+    // Inputs that reached new execution paths
+    corpus: Arc<Mutex<Corpus>>,
+    // Distinct crashes found while fuzzing the current function
+    crashes: Arc<Mutex<CrashStore>>,
+    // Directory where the fuzzer saves its outputs
+    workspace: PathBuf,
+    // Keep fuzzing after a crash until the iteration or time budget is exhausted
+    continue_on_crash: bool,
+    // Number of seconds the fuzzing of a function will last
+    run_time: Option<u64>,
+    // Use an AOT compiled shared library, cached in the workspace, instead of the JIT
+    aot: bool,
+    // Executions consuming more gas than this threshold are reported as crashes
+    max_gas: Option<u64>,
+    // Keep the inputs consuming the most gas in the corpus
+    maximize_gas: bool,
+    // Values used by the mutator
+    dict: Dict<StarkField>,
+    // Power schedule of the corpus entries
+    schedule: Schedule,
+    // Draw a dashboard of the fuzzing session instead of printing the statistics
+    tui: bool,
+    // Progress of the functions fuzzed in property testing
+    progress: Vec<FunctionProgress>,
+    // AOT compiled program, reused for every fuzzed function
+    aot_executor: Option<Arc<AotNativeExecutor>>,
     // Types of the entry point arguments
     argument_types: Vec<ArgumentType>,
     // Fuzzer statistics
@@ -45,7 +91,7 @@ pub struct Fuzzer {
     // true if Sierra has already been compiled to MLIR
     // Used to avoid recompiling to MLIR each time a new function is fuzzed
     is_mlir_compiled: bool,
-    // If the entry point arguments are in an array, e.g. core::array::Span::<core::felt252>
+    // If the entry point arguments are raw calldata whose types are unknown
     // We will need to determine the exact number of elements inside the array
     unknown_arguments_count: bool,
 }
@@ -58,10 +104,25 @@ impl Fuzzer {
         Self {
             program_path: Some(program_path),
             entry_point,
+            contract_class: None,
             sierra_program: None,
             params: Arc::new(Mutex::new(Vec::new())),
             entry_point_id: None,
-            mutator: None,
+            seed: 0,
+            cores: 1,
+            corpus: Arc::new(Mutex::new(Corpus::default())),
+            crashes: Arc::new(Mutex::new(CrashStore::new(PathBuf::new()))),
+            workspace: PathBuf::from("fuzzer_workspace"),
+            continue_on_crash: false,
+            run_time: None,
+            aot: false,
+            max_gas: None,
+            maximize_gas: false,
+            dict: Dict::default(),
+            schedule: Schedule::Uniform,
+            tui: false,
+            progress: Vec::new(),
+            aot_executor: None,
             argument_types: Vec::new(),
             stats: Arc::new(Mutex::new(FuzzerStats::default())),
             native_context,
@@ -93,10 +154,25 @@ impl Fuzzer {
         Self {
             program_path: None,
             entry_point,
-            sierra_program: Some(Arc::new(sierra_program)),
+            contract_class: Some(contract_class),
+            sierra_program: Some(Arc::new(instrument_program(&sierra_program))),
             params: Arc::new(Mutex::new(Vec::new())),
             entry_point_id: None,
-            mutator: None,
+            seed: 0,
+            cores: 1,
+            corpus: Arc::new(Mutex::new(Corpus::default())),
+            crashes: Arc::new(Mutex::new(CrashStore::new(PathBuf::new()))),
+            workspace: PathBuf::from("fuzzer_workspace"),
+            continue_on_crash: false,
+            run_time: None,
+            aot: false,
+            max_gas: None,
+            maximize_gas: false,
+            dict: Dict::default(),
+            schedule: Schedule::Uniform,
+            tui: false,
+            progress: Vec::new(),
+            aot_executor: None,
             argument_types: Vec::new(),
             stats: Arc::new(Mutex::new(FuzzerStats::default())),
             native_context,
@@ -105,14 +181,75 @@ impl Fuzzer {
         }
     }
 
+    /// Sets the directory where the fuzzer saves its outputs
+    pub fn workspace(mut self, workspace: PathBuf) -> Self {
+        self.workspace = workspace;
+        self
+    }
+
+    /// Keeps fuzzing after a crash instead of stopping at the first one
+    pub fn continue_on_crash(mut self, continue_on_crash: bool) -> Self {
+        self.continue_on_crash = continue_on_crash;
+        self
+    }
+
+    /// Sets the number of fuzzing threads
+    pub fn cores(mut self, cores: usize) -> Self {
+        self.cores = cores.max(1);
+        self
+    }
+
+    /// Sets the number of seconds the fuzzing of a function will last
+    pub fn run_time(mut self, run_time: Option<u64>) -> Self {
+        self.run_time = run_time;
+        self
+    }
+
+    /// Uses an AOT compiled shared library cached in the workspace instead of the JIT
+    pub fn aot(mut self, aot: bool) -> Self {
+        self.aot = aot;
+        self
+    }
+
+    /// Reports the executions consuming more than `max_gas` as resource exhaustions
+    /// The gas is measured on the instrumented program, it includes the coverage overhead
+    pub fn max_gas(mut self, max_gas: Option<u64>) -> Self {
+        self.max_gas = max_gas;
+        self
+    }
+
+    /// Keeps the inputs consuming the most gas in the corpus
+    pub fn maximize_gas(mut self, maximize_gas: bool) -> Self {
+        self.maximize_gas = maximize_gas;
+        self
+    }
+
+    /// Sets the dictionnary used by the mutator
+    pub fn dict(mut self, dict: Dict<StarkField>) -> Self {
+        self.dict = dict;
+        self
+    }
+
+    /// Sets the power schedule of the corpus entries
+    pub fn schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Draws a dashboard of the fuzzing session instead of printing the statistics
+    pub fn tui(mut self, tui: bool) -> Self {
+        self.tui = tui;
+        self
+    }
+
     /// Initialize the fuzzer with a given seed
-    /// - Initializes the mutator with the given seed
+    /// - Stores the seed used by the workers' mutators
     /// - Compiles Cairo code to Sierra if needed
     /// - Finds the entry point ID
     pub fn init(&mut self, seed: u64) -> Result<(), String> {
         print_init_message(seed);
 
-        self.mutator = Some(Mutex::new(Mutator::new(seed)));
+        self.seed = seed;
 
         // Only compile if the input program is a Sierra file
         if self.sierra_program.is_none() {
@@ -140,6 +277,9 @@ impl Fuzzer {
             None,
             CompilerConfig {
                 replace_ids: true,
+                // Locations of the statements, used by the source coverage report
+                add_statements_functions: true,
+                add_statements_code_locations: true,
                 ..Default::default()
             },
         )
@@ -148,28 +288,21 @@ impl Fuzzer {
         let sierra_program = contract
             .extract_sierra_program()
             .map_err(|e| format!("Failed to extract Sierra program: {}", e))?;
-        self.sierra_program = Some(Arc::new(sierra_program));
+        self.sierra_program = Some(Arc::new(instrument_program(&sierra_program)));
+        self.contract_class = Some(contract);
         Ok(())
     }
 
     /// Generates parameters based on the function argument types.
     pub fn generate_params(&mut self) {
-        let mut params = self.params.lock().unwrap();
-        *params = self
+        *self.params.lock().unwrap() = self
             .argument_types
             .iter()
-            .map(|arg_type| match arg_type {
-                ArgumentType::Felt => Felt::from(0),
-                ArgumentType::FeltArray => {
-                    self.unknown_arguments_count = true;
-                    Felt::from(0)
-                } // TODO: Add support for other types
-            })
+            .flat_map(|arg_type| arg_type.default_value())
             .collect();
 
-        // Release the lock before calling determine_argument_count
-        drop(params);
-
+        // The size of the raw calldata is only known by executing the entry point
+        self.unknown_arguments_count = self.argument_types.contains(&ArgumentType::FeltArray);
         if self.unknown_arguments_count {
             self.determine_argument_count();
         }
@@ -178,15 +311,15 @@ impl Fuzzer {
     /// Determines the correct number of arguments by iteratively adding parameters
     /// and checking for deserialization errors.
     fn determine_argument_count(&mut self) {
-        loop {
-            let executor = match self.setup_execution_environment() {
-                Ok(executor) => executor,
-                Err(e) => {
-                    error!("Error setting up execution environment: {}", e);
-                    return;
-                }
-            };
+        let executor = match self.setup_execution_environment() {
+            Ok(executor) => executor,
+            Err(e) => {
+                error!("Error setting up execution environment: {}", e);
+                return;
+            }
+        };
 
+        loop {
             let params_guard = self.params.lock().unwrap();
             match run_program(
                 &executor,
@@ -220,86 +353,45 @@ impl Fuzzer {
     }
 
     /// Initializes parameters based on the function argument types.
-    fn initialize_parameters(&mut self) {
+    /// Fails if one of the arguments has a type the fuzzer doesn't support.
+    fn initialize_parameters(&mut self) -> Result<(), String> {
         self.argument_types =
-            get_function_argument_types(&self.sierra_program, &self.entry_point_id);
+            get_function_argument_types(&self.sierra_program, &self.entry_point_id)?;
         self.generate_params();
+        Ok(())
     }
 
     /// Sets up the execution environment by compiling the Sierra program and creating a JIT executor.
-    fn setup_execution_environment(&self) -> Result<JitNativeExecutor, String> {
+    /// The AOT compiled program is reused instead if enabled.
+    fn setup_execution_environment(&self) -> Result<NativeExecutor, String> {
+        if let Some(aot_executor) = &self.aot_executor {
+            return Ok(NativeExecutor::Aot(aot_executor.clone()));
+        }
         let mlir_module = compile_sierra_program(
             &self.native_context,
             self.sierra_program
                 .as_ref()
                 .ok_or("Sierra program not available")?,
         )?;
-        let executor = create_executor(mlir_module);
-        Ok(executor)
-    }
-
-    /// Executes the program and checks for crashes.
-    fn execute_program(&self, executor: &JitNativeExecutor) -> Result<bool, String> {
-        let params_guard = self.params.lock().unwrap();
-        match run_program(
-            executor,
-            self.entry_point_id.as_ref().unwrap(),
-            &params_guard,
-        ) {
-            Ok(result) => {
-                // Crash detected
-                if result.failure_flag
-                    // Ignore this error
-                    && result.error_msg != Some("Failed to deserialize param #1".to_string())
-                {
-                    // Print the parameters
-                    println!("Parameters at crash: {:?}", *params_guard);
-                    // Print the beautified result in a single line
-                    println!(
-                        "Results: Remaining Gas = {}, Failure Flag = {}, Return Values = {:?}, Error Message = {:?}\n",
-                        result.remaining_gas,
-                        result.failure_flag,
-                        result.return_values,
-                        result.error_msg
-                    );
-                    return Ok(true);
-                }
-            }
-            Err(e) => error!("Error during execution: {}\n", e),
-        }
-        Ok(false)
+        let executor = create_executor(mlir_module)?;
+        Ok(NativeExecutor::Jit(executor))
     }
 
-    /// Mutates the parameters using the mutator.
-    fn mutate_parameters(&self) {
-        let mut mutator_guard = self.mutator.as_ref().unwrap().lock().unwrap();
-        for param in self.params.lock().unwrap().iter_mut() {
-            *param = mutator_guard.mutate(*param);
-        }
-    }
-
-    /// Prints the statistics every 1000 executions.
-    fn print_statistics(&self, current_iter: i32) {
-        if current_iter % 1000 == 0 && current_iter != 0 {
-            let stats_guard = self.stats.lock().unwrap();
-            let uptime = stats_guard.start_time.elapsed();
-            let uptime_secs = uptime.as_secs_f64();
-
-            // Calculate execs per second
-            let execs_per_second = if uptime_secs > 0.0 {
-                stats_guard.total_executions as f64 / uptime_secs
-            } else {
-                0.0
-            };
-
-            println!(
-                "| {:<30} | {:<25} | {:<25} | {:<20} |",
-                format!("Total Executions = {}", stats_guard.total_executions),
-                format!("Uptime = {:.1}s", uptime_secs),
-                format!("Crashes = {}", stats_guard.crashes),
-                format!("Exec Speed = {:.2} execs/s", execs_per_second)
-            );
+    /// Loads the AOT compiled program from the workspace cache, compiling it on the first run
+    fn load_aot_program(&mut self) -> Result<(), String> {
+        if !self.aot || self.aot_executor.is_some() {
+            return Ok(());
         }
+        info!("Loading AOT compiled program");
+        let executor = load_aot_executor(
+            &self.native_context,
+            self.sierra_program
+                .as_ref()
+                .ok_or("Sierra program not available")?,
+            &self.workspace.join("native_cache"),
+        )?;
+        self.aot_executor = Some(Arc::new(executor));
+        Ok(())
     }
 
     /// Returns a vector of strings with the entry points
@@ -319,7 +411,12 @@ impl Fuzzer {
 
     /// Runs the fuzzer.
     pub fn fuzz(&mut self, iter: i32) -> Result<(), String> {
-        self.initialize_parameters();
+        self.load_aot_program()?;
+        self.initialize_parameters()?;
+
+        // Start from an empty corpus for each fuzzed function
+        self.corpus = Arc::new(Mutex::new(Corpus::default()));
+        self.crashes = Arc::new(Mutex::new(CrashStore::new(self.crash_dir())));
 
         // Initialize the start time
         {
@@ -327,51 +424,192 @@ impl Fuzzer {
             stats_guard.start_time = Instant::now();
         }
 
-        let mut current_iter = 0;
-        let max_iter = if iter == -1 { i32::MAX } else { iter };
+        let budget = Budget {
+            max_iter: if iter == -1 {
+                usize::MAX
+            } else {
+                iter.max(0) as usize
+            },
+            run_time: self.run_time,
+            continue_on_crash: self.continue_on_crash,
+            max_gas: self.max_gas,
+            maximize_gas: self.maximize_gas,
+        };
 
         // Print it only one time
-        if !self.is_mlir_compiled {
+        if !self.is_mlir_compiled && self.aot_executor.is_none() {
             info!("Compiling Sierra to MLIR module");
             println!();
 
             self.is_mlir_compiled = true;
         }
 
-        let executor = self.setup_execution_environment()?;
+        let config = WorkerConfig {
+            sierra_program: self
+                .sierra_program
+                .clone()
+                .ok_or("Sierra program not available")?,
+            entry_point_id: self
+                .entry_point_id
+                .clone()
+                .ok_or("Entry point not available")?,
+            aot_executor: self.aot_executor.clone(),
+            argument_types: self.argument_types.clone(),
+            params: self.params.lock().unwrap().clone(),
+            seed: self.seed,
+            budget,
+        };
+        let shared = SharedState {
+            stats: self.stats.clone(),
+            corpus: self.corpus.clone(),
+            crashes: self.crashes.clone(),
+            executions: Arc::new(AtomicUsize::new(0)),
+            stop: Arc::new(AtomicBool::new(false)),
+            tui: self.tui,
+        };
 
-        let log_message = format!("Fuzzing function: {}", self.entry_point.clone().unwrap());
+        let log_message = format!(
+            "Fuzzing function: {} on {} thread(s)",
+            self.entry_point.clone().unwrap(),
+            self.cores
+        );
         info!("{}", log_message);
-        // Main fuzz loop
-        loop {
-            if current_iter >= max_iter {
-                warn!("Maximum iterations reached.");
-                println!();
-                break;
-            }
 
-            if self.execute_program(&executor)? {
-                // Increment the crashes counter
-                {
-                    let mut stats_guard = self.stats.lock().unwrap();
-                    stats_guard.crashes += 1;
-                }
-                break;
+        // Spawn the workers, each one with its own executor and mutator
+        let function_name = self.function_name();
+        let done = AtomicBool::new(false);
+        let results: Vec<Result<(), String>> = thread::scope(|scope| {
+            if self.tui {
+                scope.spawn(|| monitor(&shared, &function_name, &self.progress, &done));
             }
+            let handles: Vec<_> = (0..self.cores)
+                .map(|worker_id| {
+                    let config = WorkerConfig {
+                        seed: self.seed.wrapping_add(worker_id as u64),
+                        ..config.clone()
+                    };
+                    let mut worker = FuzzerWorker::new(worker_id, config, shared.clone())
+                        .dict(self.dict.clone())
+                        .schedule(self.schedule);
+                    scope.spawn(move || worker.run())
+                })
+                .collect();
+            let results = handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|_| Err("Fuzzing worker panicked".to_string()))
+                })
+                .collect();
+            done.store(true, Ordering::Relaxed);
+            results
+        });
+
+        self.crashes.lock().unwrap().print_summary();
+
+        results.into_iter().collect()
+    }
 
-            // Increment the total_executions counter
-            {
-                let mut stats_guard = self.stats.lock().unwrap();
-                stats_guard.total_executions += 1;
+    /// Replays the cairo-vm execution results dumped by `cairo-fuzzer --diff-dump` on
+    /// cairo-native and reports the inputs whose results differ
+    /// Returns the number of divergent inputs
+    pub fn differential(&mut self, records_path: &Path) -> Result<usize, String> {
+        self.load_aot_program()?;
+        let executor = self.setup_execution_environment()?;
+        let entry_point_id = self
+            .entry_point_id
+            .clone()
+            .ok_or("Entry point not available")?;
+        let function_name = self.function_name();
+
+        let records = load_records(records_path)?;
+        let mut replayed = 0;
+        let mut divergences = 0;
+        for record in records
+            .iter()
+            .filter(|record| record.function == function_name)
+        {
+            replayed += 1;
+            let result = NativeRunner::new(&executor, &entry_point_id)
+                .run(&record.calldata)
+                .map(|(_, result)| result);
+
+            let differences = compare_results(record, &result);
+            if !differences.is_empty() {
+                divergences += 1;
+                println!("Divergence on input {:?}:", record.calldata);
+                for difference in differences {
+                    println!("- {}", difference);
+                }
+                println!();
             }
+        }
 
-            self.mutate_parameters();
-            self.print_statistics(current_iter);
+        info!(
+            "Replayed {} of {} cairo-vm executions on {}: {} divergence(s)",
+            replayed,
+            records.len(),
+            function_name,
+            divergences
+        );
+        Ok(divergences)
+    }
 
-            current_iter += 1;
+    /// Executes the corpus and the crashes of the fuzzed function and returns the coverage
+    /// of the contract source lines and functions
+    pub fn source_coverage(&self) -> Result<CoverageReport, String> {
+        let contract_class = self
+            .contract_class
+            .as_ref()
+            .ok_or("Contract class not available")?;
+        let sierra_program = contract_class
+            .extract_sierra_program()
+            .map_err(|e| format!("Failed to extract Sierra program: {}", e))?;
+        let mut coverage = SourceCoverage::new(contract_class, &sierra_program)?;
+        let executor = self.setup_execution_environment()?;
+        let entry_point_id = self
+            .entry_point_id
+            .as_ref()
+            .ok_or("Entry point not available")?;
+
+        let mut inputs: HashSet<Vec<Felt>> = self
+            .corpus
+            .lock()
+            .unwrap()
+            .inputs()
+            .iter()
+            .cloned()
+            .collect();
+        inputs.extend(
+            self.crashes
+                .lock()
+                .unwrap()
+                .crashes()
+                .iter()
+                .map(|crash| crash.input.clone()),
+        );
+        for input in &inputs {
+            if let Ok((_, result)) = NativeRunner::new(&executor, entry_point_id).run(input) {
+                coverage.add_edges(&result.coverage);
+            }
         }
+        Ok(coverage.report())
+    }
 
-        Ok(())
+    /// Returns the name of the fuzzed function, without its path and wrapper prefix
+    pub fn function_name(&self) -> String {
+        self.entry_point
+            .as_deref()
+            .and_then(|entry_point| entry_point.split("::").last())
+            .unwrap_or("unknown")
+            .trim_start_matches("__wrapper__")
+            .to_string()
+    }
+
+    /// Returns the directory where the crashes of the current function are saved
+    fn crash_dir(&self) -> PathBuf {
+        self.workspace.join(self.function_name()).join("crashes")
     }
 
     /// Fuzzes all functions that finish with "fuzz_*".
@@ -392,19 +630,29 @@ impl Fuzzer {
             }
         }
 
+        self.progress = fuzz_functions
+            .iter()
+            .map(|fuzz_function| {
+                let name = fuzz_function.split("::").last().unwrap_or(fuzz_function);
+                FunctionProgress::new(name.trim_start_matches("__wrapper__"))
+            })
+            .collect();
+
         // Fuzz all the filtered entrypoints
-        for fuzz_function in fuzz_functions {
+        for (index, fuzz_function) in fuzz_functions.into_iter().enumerate() {
             // Re-initialize statistics
             self.stats = Arc::new(Mutex::new(FuzzerStats::default()));
 
             self.entry_point = Some(fuzz_function.clone());
             self.entry_point_id = Some(find_entry_point_id(&self.sierra_program, &fuzz_function));
-            self.initialize_parameters();
 
             // Run the fuzzer for the current function
+            self.progress[index].state = FunctionState::Running;
             if let Err(e) = self.fuzz(iter) {
                 error!("Error fuzzing function {}: {}", fuzz_function, e);
             }
+            self.progress[index].state = FunctionState::Done;
+            self.progress[index].executions = self.stats.lock().unwrap().total_executions as u64;
         }
 
         Ok(())
@@ -1,3 +1,8 @@
+pub mod corpus;
+pub mod coverage_report;
+pub mod crashes;
+pub mod differential;
 pub mod fuzzer;
 pub mod statistics;
 pub mod utils;
+pub mod worker;
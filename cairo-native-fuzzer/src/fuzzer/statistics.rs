@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::time::Instant;
 
 /// Cairo Fuzzer statistics
@@ -8,6 +9,14 @@ pub struct FuzzerStats {
     pub start_time: Instant,
     // Total number of crashes
     pub crashes: usize,
+    // Highest amount of gas consumed by an execution
+    pub max_gas: u64,
+    // Total number of executions exceeding the gas threshold
+    pub exhaustions: usize,
+    // Number of executions of each worker, by worker id
+    pub worker_executions: BTreeMap<usize, u64>,
+    // Input and error of the last crash
+    pub last_crash: Option<String>,
 }
 
 impl Default for FuzzerStats {
@@ -16,6 +25,10 @@ impl Default for FuzzerStats {
             // Init the fuzzer statistics
             total_executions: 0,
             crashes: 0,
+            max_gas: 0,
+            exhaustions: 0,
+            worker_executions: BTreeMap::new(),
+            last_crash: None,
             start_time: Instant::now(),
         }
     }
@@ -2,10 +2,10 @@ use regex::Regex;
 use std::sync::Arc;
 
 use cairo_lang_sierra::ids::FunctionId;
-use cairo_lang_sierra::program::Program;
+use cairo_lang_sierra::program::{Function, Program};
 use colored::*;
 
-use crate::mutator::argument_type::{map_argument_type, ArgumentType};
+use crate::mutator::argument_type::{is_implicit_argument, resolve_argument_type, ArgumentType};
 use crate::utils::get_cairo_native_version;
 use crate::utils::get_function_by_id;
 
@@ -36,41 +36,59 @@ pub fn print_init_message(seed: u64) {
 ///
 /// For example, given a function with the prototype:
 /// ```
-/// myfunction(a: felt252, b: felt252) -> felt252
+/// myfunction(a: felt252, b: u8, c: Array<u256>) -> felt252
 /// ```
 /// This function will return:
 /// ```
-/// [Felt, Felt]
+/// [Felt, Uint(8), Array(U256)]
 /// ```
+/// Contract entry points (`__wrapper__*`) take their arguments as serialized calldata,
+/// the types are read from the wrapped function. If it can't be found the calldata is
+/// fuzzed as a `FeltArray` of unknown size.
 pub fn get_function_argument_types(
     sierra_program: &Option<Arc<Program>>,
     entry_point_id: &Option<FunctionId>,
-) -> Vec<ArgumentType> {
+) -> Result<Vec<ArgumentType>, String> {
     // Get the function from the Sierra program using the entry point id
-    let func = match (sierra_program, entry_point_id) {
-        (Some(program), Some(entry_point_id)) => get_function_by_id(program, entry_point_id),
-        _ => None,
+    let (program, func) = match (sierra_program, entry_point_id) {
+        (Some(program), Some(entry_point_id)) => (
+            program,
+            get_function_by_id(program, entry_point_id).ok_or("Entry point not found")?,
+        ),
+        _ => return Ok(Vec::new()),
     };
 
-    // Iterate through entry point arguments and map their types to a type supported by the fuzzer
-    if let Some(func) = func {
-        let argument_types: Vec<ArgumentType> = func
-            .signature
-            .param_types
-            .iter()
-            .filter_map(|param_type| {
-                if let Some(debug_name) = &param_type.debug_name {
-                    // Map param_type to an `ArgumentType`
-                    // For now we only handle felt252
-                    return map_argument_type(debug_name);
-                }
-                None
-            })
-            .collect();
-        argument_types
-    } else {
-        Vec::new()
+    let func = match func.id.debug_name.as_deref() {
+        Some(name) if name.contains("__wrapper__") => match find_wrapped_function(program, name) {
+            Some(wrapped) => wrapped,
+            None => return Ok(vec![ArgumentType::FeltArray]),
+        },
+        _ => func,
+    };
+
+    // Iterate through the arguments and map their types to a type supported by the fuzzer
+    func.signature
+        .param_types
+        .iter()
+        .filter(|param_type| !is_implicit_argument(program, param_type))
+        .map(|param_type| resolve_argument_type(program, param_type))
+        .collect()
+}
+
+/// Finds the function wrapped by a contract entry point
+/// `mod::__wrapper__name` wraps `mod::name` and `mod::__wrapper__Impl__name` wraps `mod::Impl::name`
+fn find_wrapped_function<'a>(program: &'a Program, wrapper_name: &str) -> Option<&'a Function> {
+    let (prefix, name) = wrapper_name.split_once("__wrapper__")?;
+    let mut candidates = vec![format!("{}{}", prefix, name)];
+    if let Some((impl_name, function_name)) = name.split_once("__") {
+        candidates.push(format!("{}{}::{}", prefix, impl_name, function_name));
     }
+    candidates.iter().find_map(|candidate| {
+        program
+            .funcs
+            .iter()
+            .find(|function| function.id.debug_name.as_deref() == Some(candidate.as_str()))
+    })
 }
 
 /// Print the contract functions prototypes
@@ -134,6 +152,27 @@ pub fn find_entry_point_id(sierra_program: &Option<Arc<Program>>, entry_point: &
         .as_ref()
         .expect("Sierra program not available");
     cairo_native::utils::find_function_id(sierra_program, entry_point)
+        .or_else(|| find_function_by_name(sierra_program, entry_point))
         .expect(&format!("Entry point '{}' not found", entry_point))
         .clone()
 }
+
+/// Finds a contract entry point by the name of the function it wraps, e.g. `echo_felt`
+/// for `echo::echo::Echo::__wrapper__echo_felt` or `echo::echo::Echo::__wrapper__Impl__echo_felt`
+fn find_function_by_name<'a>(program: &'a Program, name: &str) -> Option<&'a FunctionId> {
+    program
+        .funcs
+        .iter()
+        .map(|function| &function.id)
+        .find(|id| {
+            let wrapped = id
+                .debug_name
+                .as_deref()
+                .and_then(|debug_name| debug_name.split("::").last())
+                .and_then(|last| last.strip_prefix("__wrapper__"));
+            match wrapped {
+                Some(wrapped) => wrapped == name || wrapped.ends_with(&format!("__{}", name)),
+                None => false,
+            }
+        })
+}
@@ -0,0 +1,486 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use cairo_fuzzer_core::dashboard::{Dashboard, DashboardState, FunctionProgress, WorkerStatus};
+use cairo_fuzzer_core::dict::Dict;
+use cairo_fuzzer_core::executor::{Executor, Step};
+use cairo_fuzzer_core::metrics::Heartbeats;
+use cairo_fuzzer_core::mutator::Mutator;
+use cairo_fuzzer_core::resources::ResourceLimits;
+use cairo_fuzzer_core::schedule::Schedule;
+use cairo_lang_sierra::ids::FunctionId;
+use cairo_lang_sierra::program::Program;
+use cairo_native::context::NativeContext;
+use cairo_native::executor::AotNativeExecutor;
+use starknet_types_core::felt::Felt;
+
+use crate::fuzzer::corpus::Corpus;
+use crate::fuzzer::crashes::CrashStore;
+use crate::fuzzer::statistics::FuzzerStats;
+use crate::mutator::argument_type::ArgumentType;
+use crate::mutator::arguments::ArgumentsStrategy;
+use crate::runner::runner::{
+    compile_sierra_program, create_executor, NativeExecutor, NativeRunner, StarkField,
+};
+
+use log::{error, warn};
+
+/// State shared between the workers fuzzing the same function
+#[derive(Clone)]
+pub struct SharedState {
+    // Aggregated statistics of all the workers
+    pub stats: Arc<Mutex<FuzzerStats>>,
+    // Inputs that reached new execution paths
+    pub corpus: Arc<Mutex<Corpus>>,
+    // Distinct crashes found by the workers
+    pub crashes: Arc<Mutex<CrashStore>>,
+    // Executions started by the workers, checked against the iteration budget
+    pub executions: Arc<AtomicUsize>,
+    // Set when the workers should stop fuzzing
+    pub stop: Arc<AtomicBool>,
+    // Set when the dashboard is drawn, the workers do not print their statistics and findings
+    pub tui: bool,
+}
+
+/// Budget of a fuzzing campaign
+#[derive(Clone, Copy)]
+pub struct Budget {
+    // Maximum number of executions, summed over all the workers
+    pub max_iter: usize,
+    // Number of seconds the campaign will last
+    pub run_time: Option<u64>,
+    // Keep fuzzing after a crash until the budget is exhausted
+    pub continue_on_crash: bool,
+    // Executions consuming more gas than this threshold are reported as crashes
+    pub max_gas: Option<u64>,
+    // Keep the inputs consuming the most gas in the corpus
+    pub maximize_gas: bool,
+}
+
+/// Program, entry point and settings of a worker
+#[derive(Clone)]
+pub struct WorkerConfig {
+    // Sierra program
+    pub sierra_program: Arc<Program>,
+    // ID of the entry point
+    pub entry_point_id: FunctionId,
+    // AOT compiled program shared by all the workers, if enabled
+    pub aot_executor: Option<Arc<AotNativeExecutor>>,
+    // Types of the entry point arguments, used to mutate the input
+    pub argument_types: Vec<ArgumentType>,
+    // Input mutated while the corpus is empty
+    pub params: Vec<Felt>,
+    // Seed of the mutator and of the corpus selection
+    pub seed: u64,
+    // Budget of the campaign
+    pub budget: Budget,
+}
+
+/// Number of executions between two syncs of a worker with the shared state
+const SYNC_INTERVAL: u64 = 256;
+
+/// Statistics and findings of a worker not synced with the shared state yet
+#[derive(Default)]
+struct PendingSync {
+    executions: u64,
+    crashes: usize,
+    exhaustions: usize,
+    // Highest amount of gas consumed by an execution
+    max_gas: u64,
+    // Input and error of the last crash
+    last_crash: Option<String>,
+    // Inputs that reached new edges, with their coverage
+    new_inputs: Vec<(Vec<Felt>, Vec<u64>)>,
+    // Inputs kept for the gas they consumed
+    gas_inputs: Vec<Vec<Felt>>,
+}
+
+/// A fuzzing thread with its own executor and mutator
+/// The inputs are mutated and executed by the executor of `cairo_fuzzer_core`, shared
+/// with the cairo-vm fuzzer
+pub struct FuzzerWorker {
+    // ID of the worker
+    worker_id: usize,
+    // Sierra program
+    sierra_program: Arc<Program>,
+    // ID of the entry point
+    entry_point_id: FunctionId,
+    // AOT compiled program shared by all the workers, if enabled
+    aot_executor: Option<Arc<AotNativeExecutor>>,
+    // Types of the entry point arguments, used to mutate the input
+    argument_types: Vec<ArgumentType>,
+    // Input mutated while the corpus is empty
+    params: Vec<Felt>,
+    // Seed of the mutator and of the corpus selection
+    seed: u64,
+    // Values used by the mutator
+    dict: Dict<StarkField>,
+    // Power schedule of the corpus entries
+    schedule: Schedule,
+    // Copy of the shared corpus with the inputs found by this worker since the last sync
+    corpus: Corpus,
+    // Number of inputs of the shared corpus already copied
+    synced_inputs: usize,
+    // Statistics and findings to sync with the shared state
+    pending: PendingSync,
+    // Highest amount of gas consumed by the executions of all the workers, at the last sync
+    max_gas: u64,
+    // Start of the campaign
+    start_time: Instant,
+    // State shared with the other workers
+    shared: SharedState,
+    // Budget of the campaign
+    budget: Budget,
+}
+
+impl FuzzerWorker {
+    /// Creates a worker fuzzing the program of `config`
+    pub fn new(worker_id: usize, config: WorkerConfig, shared: SharedState) -> Self {
+        let WorkerConfig {
+            sierra_program,
+            entry_point_id,
+            aot_executor,
+            argument_types,
+            params,
+            seed,
+            budget,
+        } = config;
+        let start_time = shared.stats.lock().unwrap().start_time;
+        Self {
+            worker_id,
+            sierra_program,
+            entry_point_id,
+            aot_executor,
+            argument_types,
+            params,
+            seed,
+            dict: Dict::default(),
+            schedule: Schedule::Uniform,
+            corpus: Corpus::default(),
+            synced_inputs: 0,
+            pending: PendingSync::default(),
+            max_gas: 0,
+            start_time,
+            shared,
+            budget,
+        }
+    }
+
+    /// Sets the dictionnary used by the mutator
+    pub fn dict(mut self, dict: Dict<StarkField>) -> Self {
+        self.dict = dict;
+        self
+    }
+
+    /// Sets the power schedule of the corpus entries
+    pub fn schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Compiles the program if needed and fuzzes it until the budget is exhausted
+    /// The other workers are stopped if this one fails
+    pub fn run(&mut self) -> Result<(), String> {
+        let result = self.fuzz();
+        self.sync();
+        if result.is_err() {
+            self.shared.stop.store(true, Ordering::Relaxed);
+        }
+        result
+    }
+
+    /// Main fuzz loop of the worker
+    fn fuzz(&mut self) -> Result<(), String> {
+        let native_context;
+        let sierra_program = self.sierra_program.clone();
+        let native_executor = match &self.aot_executor {
+            Some(aot_executor) => NativeExecutor::Aot(aot_executor.clone()),
+            None => {
+                // The MLIR context can't be shared between threads, each worker compiles its own module
+                native_context = NativeContext::new();
+                let mlir_module = compile_sierra_program(&native_context, &sierra_program)?;
+                NativeExecutor::Jit(create_executor(mlir_module)?)
+            }
+        };
+
+        // The argument types keep the mutated inputs well formed
+        let mutator = Mutator::new()
+            .builtins(false)
+            .dict(self.dict.clone())
+            .strategy(Box::new(ArgumentsStrategy::new(
+                self.argument_types.clone(),
+            )));
+        let entry_point_id = self.entry_point_id.clone();
+        let runner = NativeRunner::new(&native_executor, &entry_point_id);
+        let mut executor = Executor::new(runner, mutator, self.seed)
+            .initial_input(self.params.clone())
+            .schedule(self.schedule)
+            .limits(ResourceLimits {
+                max_steps: self.budget.max_gas.unwrap_or(0),
+                maximize: self.budget.maximize_gas,
+            });
+
+        // Main fuzz loop
+        while !self.shared.stop.load(Ordering::Relaxed) {
+            if self.budget_exhausted() {
+                break;
+            }
+
+            // The most expensive input may have been found by another worker
+            executor.set_max_cost(self.max_gas);
+            let step = executor.step(&mut self.corpus);
+            let crashed = self.triage(step);
+
+            self.pending.executions += 1;
+            if crashed {
+                self.pending.crashes += 1;
+            }
+
+            if crashed && !self.budget.continue_on_crash {
+                self.shared.stop.store(true, Ordering::Relaxed);
+                break;
+            }
+
+            if self.pending.executions >= SYNC_INTERVAL {
+                self.sync();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if the iteration or time budget is exhausted, otherwise counts the
+    /// next execution in the budget
+    /// The first worker to notice it stops the others
+    fn budget_exhausted(&self) -> bool {
+        let message =
+            if self.shared.executions.fetch_add(1, Ordering::Relaxed) >= self.budget.max_iter {
+                "Maximum iterations reached."
+            } else if self.budget.run_time.map_or(false, |run_time| {
+                self.start_time.elapsed() >= Duration::from_secs(run_time)
+            }) {
+                "Time budget exhausted."
+            } else {
+                return false;
+            };
+
+        if !self.shared.stop.swap(true, Ordering::Relaxed) && !self.shared.tui {
+            warn!("{}", message);
+            println!();
+        }
+        true
+    }
+
+    /// Records the findings of an execution, returns true if it crashed
+    /// The executor already added the inputs reaching new edges to the local corpus
+    fn triage(&mut self, step: Step<Felt>) -> bool {
+        let result = match &step.result {
+            Ok(result) => result,
+            Err(e) => {
+                error!("Error during execution: {}\n", e);
+                return false;
+            }
+        };
+
+        // The cost of a native execution is the consumed gas
+        let gas_used = step.cost.steps;
+        self.max_gas = self.max_gas.max(gas_used);
+        self.pending.max_gas = self.pending.max_gas.max(gas_used);
+
+        if step.new_coverage {
+            self.pending
+                .new_inputs
+                .push((step.input.clone(), step.coverage.clone()));
+            // Steer the fuzzer toward the most expensive executions
+            if step.max_cost {
+                self.pending.gas_inputs.push(step.input.clone());
+            }
+        }
+
+        // Resource exhaustion detected
+        if step.exhausted {
+            self.pending.exhaustions += 1;
+            if self.shared.crashes.lock().unwrap().record_message(
+                &step.input,
+                "Resource exhaustion: gas threshold exceeded".to_string(),
+                &result.retdata,
+            ) && !self.shared.tui
+            {
+                println!("Parameters at resource exhaustion: {:?}", step.input);
+                println!("Results: Gas Used = {}\n", gas_used);
+            }
+            return true;
+        }
+
+        // Crash detected
+        if result.failure_flag
+            // Ignore this error
+            && result.error_msg != Some("Failed to deserialize param #1".to_string())
+        {
+            self.pending.last_crash = Some(format!(
+                "Parameters at crash: {:?} -- Error Message = {:?}",
+                step.input, result.error_msg
+            ));
+            // Only print the crashes that were never seen before
+            if self
+                .shared
+                .crashes
+                .lock()
+                .unwrap()
+                .record(&step.input, result)
+                && !self.shared.tui
+            {
+                // Print the parameters
+                println!("Parameters at crash: {:?}", step.input);
+                // Print the beautified result in a single line
+                println!(
+                    "Results: Gas Used = {}, Failure Flag = {}, Return Values = {:?}, Error Message = {:?}\n",
+                    gas_used,
+                    result.failure_flag,
+                    result.retdata,
+                    result.error_msg
+                );
+            }
+            return true;
+        }
+        false
+    }
+
+    /// Flushes the statistics and the new inputs of the worker to the shared state and
+    /// copies the inputs found by the other workers
+    fn sync(&mut self) {
+        let pending = std::mem::take(&mut self.pending);
+        {
+            let mut corpus_guard = self.shared.corpus.lock().unwrap();
+            for input in &corpus_guard.inputs()[self.synced_inputs..] {
+                self.corpus.add_input(input);
+            }
+            for (input, coverage) in &pending.new_inputs {
+                corpus_guard.add(input, coverage);
+            }
+            for input in &pending.gas_inputs {
+                corpus_guard.add_input(input);
+            }
+            // The inputs reaching only the edges found by the other workers are not new
+            self.corpus.merge_coverage(&corpus_guard);
+            self.synced_inputs = corpus_guard.len();
+        }
+
+        let total_executions = {
+            let mut stats_guard = self.shared.stats.lock().unwrap();
+            stats_guard.total_executions += pending.executions as usize;
+            *stats_guard
+                .worker_executions
+                .entry(self.worker_id)
+                .or_default() += pending.executions;
+            stats_guard.crashes += pending.crashes;
+            stats_guard.exhaustions += pending.exhaustions;
+            stats_guard.max_gas = stats_guard.max_gas.max(pending.max_gas);
+            self.max_gas = stats_guard.max_gas;
+            if pending.last_crash.is_some() {
+                stats_guard.last_crash = pending.last_crash;
+            }
+            stats_guard.total_executions
+        };
+
+        // Prints the statistics every 1000 executions
+        let previous_executions = total_executions - pending.executions as usize;
+        if !self.shared.tui && total_executions / 1000 > previous_executions / 1000 {
+            print_statistics(&self.shared);
+        }
+    }
+}
+
+/// Draws the dashboard of the workers every second until `done` is set
+pub fn monitor(
+    shared: &SharedState,
+    title: &str,
+    functions: &[FunctionProgress],
+    done: &AtomicBool,
+) {
+    let mut dashboard = Dashboard::default();
+    let mut heartbeats = Heartbeats::default();
+    while !done.load(Ordering::Relaxed) {
+        let state = dashboard_state(shared, title, functions, &mut heartbeats);
+        dashboard.update(&state);
+        if let Err(e) = dashboard.draw(&state) {
+            error!("Could not draw the dashboard: {}", e);
+        }
+        thread::sleep(Duration::from_secs(1));
+    }
+}
+
+/// Snapshot of the workers drawn by the dashboard
+/// A worker is running while its executions counter progresses
+fn dashboard_state(
+    shared: &SharedState,
+    title: &str,
+    functions: &[FunctionProgress],
+    heartbeats: &mut Heartbeats,
+) -> DashboardState {
+    let stats_guard = shared.stats.lock().unwrap();
+    let corpus_guard = shared.corpus.lock().unwrap();
+    let uptime = stats_guard.start_time.elapsed().as_secs_f64();
+    let workers = heartbeats.update(
+        uptime,
+        &stats_guard.worker_executions,
+        shared.stop.load(Ordering::Relaxed),
+    );
+    let mut functions = functions.to_vec();
+    for function in &mut functions {
+        if function.name == title {
+            function.executions = stats_guard.total_executions as u64;
+        }
+    }
+    DashboardState {
+        title: format!("cairo-native-fuzzer -- {}", title),
+        uptime,
+        executions: stats_guard.total_executions as u64,
+        coverage: corpus_guard.coverage(),
+        corpus: corpus_guard.len(),
+        crashes: stats_guard.crashes as u64,
+        crash_buckets: shared.crashes.lock().unwrap().buckets(),
+        workers: workers
+            .iter()
+            .map(|worker| WorkerStatus {
+                id: worker.id,
+                executions: worker.executions,
+                running: worker.is_up(),
+            })
+            .collect(),
+        functions,
+        last_crash: stats_guard.last_crash.clone(),
+    }
+}
+
+/// Prints the aggregated statistics of the workers
+fn print_statistics(shared: &SharedState) {
+    let stats_guard = shared.stats.lock().unwrap();
+    let corpus_guard = shared.corpus.lock().unwrap();
+    let uptime = stats_guard.start_time.elapsed();
+    let uptime_secs = uptime.as_secs_f64();
+
+    // Calculate execs per second
+    let execs_per_second = if uptime_secs > 0.0 {
+        stats_guard.total_executions as f64 / uptime_secs
+    } else {
+        0.0
+    };
+
+    println!(
+        "| {:<30} | {:<25} | {:<25} | {:<20} | {:<20} | {:<20} | {:<20} |",
+        format!("Total Executions = {}", stats_guard.total_executions),
+        format!("Uptime = {:.1}s", uptime_secs),
+        format!(
+            "Crashes = {} ({} unique)",
+            stats_guard.crashes,
+            shared.crashes.lock().unwrap().len()
+        ),
+        format!("Exec Speed = {:.2} execs/s", execs_per_second),
+        format!("Coverage = {}", corpus_guard.coverage()),
+        format!("Corpus = {}", corpus_guard.len()),
+        format!("Max Gas = {}", stats_guard.max_gas)
+    );
+}
@@ -0,0 +1,10 @@
+//! cairo-native fuzzer
+//!
+//! The engine independent parts (corpus, crash triage, `Runner` trait...) come from
+//! `cairo-fuzzer-core`, shared with the cairo-vm fuzzer. `runner::runner::NativeRunner`
+//! implements its `Runner` trait on top of cairo-native.
+pub mod custom_rand;
+pub mod fuzzer;
+pub mod mutator;
+pub mod runner;
+pub mod utils;
@@ -1,14 +1,12 @@
-mod custom_rand;
-mod fuzzer;
-mod mutator;
-mod runner;
-mod utils;
-
 use clap::Parser;
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::fuzzer::fuzzer::Fuzzer;
+use cairo_fuzzer_core::dashboard::is_terminal;
+use cairo_fuzzer_core::dict::Dict;
+use cairo_fuzzer_core::schedule::Schedule;
+use cairo_native_fuzzer::fuzzer::fuzzer::Fuzzer;
+use cairo_native_fuzzer::runner::runner::StarkField;
 
 /// Command-line arguments for the fuzzer
 #[derive(Parser, Debug)]
@@ -41,6 +39,56 @@ struct Args {
     /// Seed for the random number generator
     #[arg(short, long)]
     seed: Option<u64>,
+
+    /// Keep fuzzing after a crash until the iteration or time budget is exhausted
+    #[arg(long)]
+    continue_on_crash: bool,
+
+    /// Number of seconds the fuzzing of a function will last
+    #[arg(long)]
+    run_time: Option<u64>,
+
+    /// Number of fuzzing threads
+    #[arg(long, default_value_t = 1)]
+    cores: usize,
+
+    /// Use an AOT compiled shared library cached in the workspace instead of the JIT
+    #[arg(long)]
+    aot: bool,
+
+    /// Directory where the fuzzer saves its outputs
+    #[arg(long, default_value = "fuzzer_workspace")]
+    workspace: PathBuf,
+
+    /// Report the executions consuming more gas than this threshold as resource exhaustions.
+    /// The gas includes the overhead of the coverage instrumentation, a few
+    /// libfuncs per Sierra block reached, so set it above the gas of an uninstrumented run
+    #[arg(long)]
+    max_gas: Option<u64>,
+
+    /// Add the inputs consuming the most gas to the corpus
+    #[arg(long)]
+    maximize_gas: bool,
+
+    /// Path to a dictionary of values used by the mutator (same format as cairo-fuzzer)
+    #[arg(long)]
+    dict: Option<PathBuf>,
+
+    /// Power schedule used to pick the corpus entries to mutate
+    #[arg(long, value_enum, default_value = "uniform")]
+    schedule: Schedule,
+
+    /// Draw a dashboard of the fuzzing session when the output is a terminal
+    #[arg(long)]
+    tui: bool,
+
+    /// Folder where the lcov and HTML source coverage reports of the corpus are written
+    #[arg(long)]
+    coverage_report: Option<PathBuf>,
+
+    /// Replay the cairo-vm results dumped by `cairo-fuzzer --diff-dump` and report divergences
+    #[arg(long)]
+    differential: Option<PathBuf>,
 }
 
 fn main() {
@@ -78,8 +126,20 @@ fn main() {
         return;
     }
 
+    // Load the dictionary
+    let dict = match &args.dict {
+        Some(path) => match Dict::<StarkField>::read_dict(&path.to_string_lossy()) {
+            Ok(dict) => dict,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return;
+            }
+        },
+        None => Dict::default(),
+    };
+
     // Initialize the fuzzer based on the provided program path
-    let mut fuzzer = if let Some(sierra_program) = args.sierra_program {
+    let fuzzer = if let Some(sierra_program) = args.sierra_program {
         Fuzzer::new_sierra(sierra_program, args.entry_point)
     } else if let Some(program_path) = args.program_path {
         Fuzzer::new(program_path, args.entry_point)
@@ -87,6 +147,17 @@ fn main() {
         eprintln!("Error: Either --program-path or --sierra-program must be specified");
         return;
     };
+    let mut fuzzer = fuzzer
+        .workspace(args.workspace)
+        .cores(args.cores)
+        .aot(args.aot)
+        .continue_on_crash(args.continue_on_crash)
+        .run_time(args.run_time)
+        .max_gas(args.max_gas)
+        .maximize_gas(args.maximize_gas)
+        .dict(dict)
+        .schedule(args.schedule)
+        .tui(args.tui && is_terminal());
 
     match fuzzer.init(seed) {
         Ok(()) => {
@@ -94,6 +165,17 @@ fn main() {
             if args.analyze {
                 fuzzer.print_functions_prototypes();
             }
+            // Compare the cairo-vm results with cairo-native
+            else if let Some(records_path) = args.differential {
+                match fuzzer.differential(&records_path) {
+                    Ok(0) => println!("No divergence found."),
+                    Ok(divergences) => {
+                        eprintln!("{} divergent input(s) found.", divergences);
+                        std::process::exit(1);
+                    }
+                    Err(e) => eprintln!("Error during differential fuzzing: {}", e),
+                }
+            }
             // Run the fuzzer
             else {
                 if args.proptesting {
@@ -106,6 +188,23 @@ fn main() {
                         Ok(()) => println!("Fuzzing completed successfully."),
                         Err(e) => eprintln!("Error during fuzzing: {}", e),
                     }
+                    // Write the source coverage of the corpus
+                    if let Some(dir) = &args.coverage_report {
+                        let dir = dir.to_string_lossy();
+                        let report = fuzzer.source_coverage().and_then(|report| {
+                            report.write(&dir, &fuzzer.function_name())?;
+                            Ok(report)
+                        });
+                        match report {
+                            Ok(report) => println!(
+                                "Source coverage: {}/{} lines, report written in {}",
+                                report.lines_hit(),
+                                report.lines_found(),
+                                dir
+                            ),
+                            Err(e) => eprintln!("Error writing the coverage report: {}", e),
+                        }
+                    }
                 }
             }
         }
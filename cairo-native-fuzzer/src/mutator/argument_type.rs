@@ -1,18 +1,451 @@
+use cairo_lang_sierra::ids::ConcreteTypeId;
+use cairo_lang_sierra::program::{GenericArg, Program};
+use starknet_types_core::felt::Felt;
+
+/// Maximum number of elements generated in an array
+pub const MAX_ARRAY_LEN: usize = 64;
+
+/// Contract addresses are smaller than 2^251 - 256
+pub fn contract_address_bound() -> Felt {
+    Felt::TWO.pow(251u128) - Felt::from(256)
+}
+
+/// Implicit arguments passed by the executor, they are not part of the calldata
+const IMPLICIT_TYPES: &[&str] = &[
+    "AddMod",
+    "Bitwise",
+    "EcOp",
+    "GasBuiltin",
+    "MulMod",
+    "Pedersen",
+    "Poseidon",
+    "RangeCheck",
+    "RangeCheck96",
+    "SegmentArena",
+    "System",
+];
+
 /// Enum representing the types of arguments that can be passed to a function
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ArgumentType {
     Felt,
-    FeltArray, // TODO: Add support for other types
+    Bool,
+    /// Unsigned integer of the given bit width
+    Uint(usize),
+    /// Signed integer of the given bit width
+    Int(usize),
+    /// Serialized as its low and high u128 limbs
+    U256,
+    ContractAddress,
+    ClassHash,
+    /// Array or span, serialized as its length followed by its elements
+    Array(Box<ArgumentType>),
+    /// Struct or tuple, serialized as its members one after the other
+    Struct(Vec<ArgumentType>),
+    /// Enum, serialized as the variant index followed by the variant payload
+    Enum(Vec<ArgumentType>),
+    /// Raw calldata of an entry point whose arguments are unknown
+    /// The number of felts is determined by executing the entry point
+    FeltArray,
+}
+
+/// A position of a serialized argument that can be mutated
+#[derive(Debug, Clone)]
+pub enum Slot {
+    /// A scalar value at `offset`
+    Scalar { offset: usize, ty: ArgumentType },
+    /// The length `len` of an array at `offset`, its elements end at `end`
+    Length {
+        offset: usize,
+        len: usize,
+        end: usize,
+        item: ArgumentType,
+    },
+    /// The variant index of an enum at `offset`, its payload ends at `end`
+    Variant {
+        offset: usize,
+        end: usize,
+        variants: Vec<ArgumentType>,
+    },
+}
+
+impl ArgumentType {
+    /// Returns the serialized value used for the first execution
+    pub fn default_value(&self) -> Vec<Felt> {
+        match self {
+            ArgumentType::U256 => vec![Felt::ZERO, Felt::ZERO],
+            ArgumentType::Struct(members) => members
+                .iter()
+                .flat_map(|member| member.default_value())
+                .collect(),
+            ArgumentType::Enum(variants) => {
+                let mut value = vec![Felt::ZERO];
+                if let Some(variant) = variants.first() {
+                    value.extend(variant.default_value());
+                }
+                value
+            }
+            _ => vec![Felt::ZERO],
+        }
+    }
+
+    /// Brings a mutated scalar value back into the range of its type
+    pub fn fit(&self, felt: Felt) -> Felt {
+        match self {
+            ArgumentType::Bool => mask_bits(felt, 1),
+            ArgumentType::Uint(bits) => mask_bits(felt, *bits),
+            ArgumentType::Int(bits) => {
+                // Values in [-2^(bits-1), 2^(bits-1)) are already valid
+                let half = Felt::TWO.pow((*bits - 1) as u128);
+                if felt < half || felt >= Felt::ZERO - half {
+                    return felt;
+                }
+                // Otherwise reinterpret the low bits as a two's complement integer
+                let low = mask_bits(felt, *bits);
+                if low >= half {
+                    low - Felt::TWO.pow(*bits as u128)
+                } else {
+                    low
+                }
+            }
+            // Contract addresses are smaller than 2^251 - 256, the values masked to 251 bits
+            // above the bound are wrapped below it
+            ArgumentType::ContractAddress => {
+                let address = mask_bits(felt, 251);
+                let bound = contract_address_bound();
+                if address >= bound {
+                    address - bound
+                } else {
+                    address
+                }
+            }
+            // Class hashes are smaller than 2^251
+            ArgumentType::ClassHash => mask_bits(felt, 251),
+            _ => felt,
+        }
+    }
+
+    /// Collects the mutable slots of the value serialized at `input[offset..]`
+    /// Returns the offset following the value, or `None` if the input doesn't match the type
+    fn collect_slots(&self, input: &[Felt], offset: usize, slots: &mut Vec<Slot>) -> Option<usize> {
+        match self {
+            ArgumentType::U256 => {
+                let offset = ArgumentType::Uint(128).collect_slots(input, offset, slots)?;
+                ArgumentType::Uint(128).collect_slots(input, offset, slots)
+            }
+            ArgumentType::Array(item) => {
+                let len = felt_to_usize(input.get(offset)?)?;
+                if len > input.len() {
+                    return None;
+                }
+                let mut end = offset + 1;
+                for _ in 0..len {
+                    end = item.collect_slots(input, end, slots)?;
+                }
+                slots.push(Slot::Length {
+                    offset,
+                    len,
+                    end,
+                    item: *item.clone(),
+                });
+                Some(end)
+            }
+            ArgumentType::Struct(members) => members.iter().try_fold(offset, |offset, member| {
+                member.collect_slots(input, offset, slots)
+            }),
+            ArgumentType::Enum(variants) => {
+                let index = felt_to_usize(input.get(offset)?)?;
+                let end = variants
+                    .get(index)?
+                    .collect_slots(input, offset + 1, slots)?;
+                slots.push(Slot::Variant {
+                    offset,
+                    end,
+                    variants: variants.clone(),
+                });
+                Some(end)
+            }
+            ArgumentType::FeltArray => {
+                for offset in offset..input.len() {
+                    slots.push(Slot::Scalar {
+                        offset,
+                        ty: ArgumentType::Felt,
+                    });
+                }
+                Some(input.len().max(offset))
+            }
+            _ => {
+                input.get(offset)?;
+                slots.push(Slot::Scalar {
+                    offset,
+                    ty: self.clone(),
+                });
+                Some(offset + 1)
+            }
+        }
+    }
+
+    /// Returns the offset following the value serialized at `input[offset..]`
+    pub fn serialized_end(&self, input: &[Felt], offset: usize) -> Option<usize> {
+        self.collect_slots(input, offset, &mut Vec::new())
+    }
+}
+
+/// Returns the mutable slots of an input serialized from the given argument types
+/// Returns `None` if the input doesn't match the types
+pub fn collect_slots(types: &[ArgumentType], input: &[Felt]) -> Option<Vec<Slot>> {
+    let mut slots = Vec::new();
+    let end = types
+        .iter()
+        .try_fold(0, |offset, ty| ty.collect_slots(input, offset, &mut slots))?;
+    if end != input.len() {
+        return None;
+    }
+    Some(slots)
+}
+
+/// Keeps the `bits` least significant bits of a felt
+fn mask_bits(felt: Felt, bits: usize) -> Felt {
+    let mut bytes = felt.to_bytes_be();
+    let full_bytes = bits / 8;
+    let extra_bits = bits % 8;
+    for (idx, byte) in bytes.iter_mut().enumerate() {
+        // Position of the byte starting from the least significant one
+        let position = 32 - idx;
+        if position == full_bytes + 1 && extra_bits != 0 {
+            *byte &= (1 << extra_bits) - 1;
+        } else if position > full_bytes {
+            *byte = 0;
+        }
+    }
+    Felt::from_bytes_be(&bytes)
+}
+
+/// Converts a felt used as a length or an index to usize
+fn felt_to_usize(felt: &Felt) -> Option<usize> {
+    let bytes = felt.to_bytes_be();
+    if bytes[..24].iter().any(|&byte| byte != 0) {
+        return None;
+    }
+    usize::try_from(u64::from_be_bytes(bytes[24..].try_into().unwrap())).ok()
 }
 
 /// Helper function to map argument types based on their debug names
 /// This function takes a debug name string and returns the corresponding `ArgumentType`
 pub fn map_argument_type(debug_name: &str) -> Option<ArgumentType> {
     match debug_name {
-        "felt252" => Some(ArgumentType::Felt),
-        // We treat felt252 arrays as a single felt for now
-        "core::array::Span::<core::felt252>" => Some(ArgumentType::FeltArray),
-        // TODO: Add support for other types
+        "felt252" | "core::felt252" => Some(ArgumentType::Felt),
+        "core::bool" => Some(ArgumentType::Bool),
+        "u8" | "core::integer::u8" => Some(ArgumentType::Uint(8)),
+        "u16" | "core::integer::u16" => Some(ArgumentType::Uint(16)),
+        "u32" | "core::integer::u32" => Some(ArgumentType::Uint(32)),
+        "u64" | "core::integer::u64" => Some(ArgumentType::Uint(64)),
+        "u128" | "core::integer::u128" => Some(ArgumentType::Uint(128)),
+        "i8" | "core::integer::i8" => Some(ArgumentType::Int(8)),
+        "i16" | "core::integer::i16" => Some(ArgumentType::Int(16)),
+        "i32" | "core::integer::i32" => Some(ArgumentType::Int(32)),
+        "i64" | "core::integer::i64" => Some(ArgumentType::Int(64)),
+        "i128" | "core::integer::i128" => Some(ArgumentType::Int(128)),
+        "core::integer::u256" => Some(ArgumentType::U256),
+        "ContractAddress" | "core::starknet::contract_address::ContractAddress" => {
+            Some(ArgumentType::ContractAddress)
+        }
+        "ClassHash" | "core::starknet::class_hash::ClassHash" => Some(ArgumentType::ClassHash),
         _ => None,
     }
 }
+
+/// Returns true if the type is an implicit argument or the contract state
+/// Those arguments are provided by the executor and are not part of the calldata
+pub fn is_implicit_argument(program: &Program, type_id: &ConcreteTypeId) -> bool {
+    let Some(declaration) = program.type_declarations.iter().find(|d| &d.id == type_id) else {
+        return false;
+    };
+    let generic_id = declaration.long_id.generic_id.0.as_str();
+    if IMPLICIT_TYPES.contains(&generic_id) {
+        return true;
+    }
+    match declaration.long_id.generic_args.first() {
+        Some(GenericArg::UserType(user_type)) if generic_id == "Struct" => user_type
+            .debug_name
+            .as_ref()
+            .map_or(false, |name| name.ends_with("ContractState")),
+        // `self: @ContractState`
+        Some(GenericArg::Type(inner)) if generic_id == "Snapshot" => {
+            is_implicit_argument(program, inner)
+        }
+        _ => false,
+    }
+}
+
+/// Resolves the `ArgumentType` of a Sierra type using the program type declarations
+/// Returns an error if the type, or one of its members, is not supported by the fuzzer
+pub fn resolve_argument_type(
+    program: &Program,
+    type_id: &ConcreteTypeId,
+) -> Result<ArgumentType, String> {
+    let type_name = type_id
+        .debug_name
+        .as_ref()
+        .map_or_else(|| type_id.id.to_string(), |name| name.to_string());
+    let declaration = program
+        .type_declarations
+        .iter()
+        .find(|d| &d.id == type_id)
+        .ok_or(format!("Type declaration not found for {}", type_name))?;
+
+    // Types used as generic arguments, e.g. the members of a struct
+    let inner_types: Vec<&ConcreteTypeId> = declaration
+        .long_id
+        .generic_args
+        .iter()
+        .filter_map(|arg| match arg {
+            GenericArg::Type(inner) => Some(inner),
+            _ => None,
+        })
+        .collect();
+    // Name of the user type for structs and enums, e.g. core::integer::u256
+    let user_type_name = declaration
+        .long_id
+        .generic_args
+        .iter()
+        .find_map(|arg| match arg {
+            GenericArg::UserType(user_type) => user_type.debug_name.clone(),
+            _ => None,
+        });
+
+    let generic_id = declaration.long_id.generic_id.0.as_str();
+    if let Some(ty) = user_type_name
+        .as_deref()
+        .and_then(map_argument_type)
+        .or_else(|| map_argument_type(generic_id))
+    {
+        return Ok(ty);
+    }
+
+    match generic_id {
+        "Array" => Ok(ArgumentType::Array(Box::new(resolve_argument_type(
+            program,
+            inner_types.first().ok_or("Array without item type")?,
+        )?))),
+        // Snapshots, boxes and non-zero values are serialized as the wrapped value
+        "Snapshot" | "Box" | "NonZero" => resolve_argument_type(
+            program,
+            inner_types
+                .first()
+                .ok_or(format!("{} without inner type", generic_id))?,
+        ),
+        "Struct" => Ok(ArgumentType::Struct(
+            inner_types
+                .iter()
+                .map(|member| resolve_argument_type(program, member))
+                .collect::<Result<_, _>>()?,
+        )),
+        "Enum" => Ok(ArgumentType::Enum(
+            inner_types
+                .iter()
+                .map(|variant| resolve_argument_type(program, variant))
+                .collect::<Result<_, _>>()?,
+        )),
+        _ => Err(format!("Unsupported argument type: {}", type_name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn felts(values: &[u64]) -> Vec<Felt> {
+        values.iter().map(|value| Felt::from(*value)).collect()
+    }
+
+    #[test]
+    fn test_mask_bits() {
+        assert_eq!(mask_bits(Felt::from(0b1011), 1), Felt::ONE);
+        assert_eq!(mask_bits(Felt::from(0x1ff), 8), Felt::from(0xff));
+        assert_eq!(mask_bits(Felt::from(0xabcd), 12), Felt::from(0xbcd));
+        assert_eq!(mask_bits(Felt::from(u64::MAX), 64), Felt::from(u64::MAX));
+        // P - 1 = 2^251 + 17 * 2^192, its 251 low bits are 17 * 2^192
+        assert_eq!(
+            mask_bits(Felt::MAX, 251),
+            Felt::from(17) * Felt::TWO.pow(192u128)
+        );
+    }
+
+    #[test]
+    fn test_fit() {
+        assert_eq!(ArgumentType::Bool.fit(Felt::from(3)), Felt::ONE);
+        assert_eq!(
+            ArgumentType::Uint(8).fit(Felt::from(0x1ff)),
+            Felt::from(0xff)
+        );
+        assert_eq!(ArgumentType::Uint(128).fit(Felt::MAX), Felt::ZERO);
+
+        // Values in range are kept, negative values are P - |value|
+        let int8 = ArgumentType::Int(8);
+        assert_eq!(int8.fit(Felt::from(127)), Felt::from(127));
+        assert_eq!(int8.fit(-Felt::from(128)), -Felt::from(128));
+        // Out of range values are read as two's complement integers
+        assert_eq!(int8.fit(Felt::from(0x80)), -Felt::from(128));
+        assert_eq!(int8.fit(Felt::from(0x1ff)), -Felt::ONE);
+        assert_eq!(int8.fit(Felt::from(0x17f)), Felt::from(127));
+
+        let bound = contract_address_bound();
+        let address = ArgumentType::ContractAddress;
+        assert_eq!(address.fit(bound - Felt::ONE), bound - Felt::ONE);
+        assert_eq!(address.fit(bound), Felt::ZERO);
+        assert_eq!(address.fit(bound + Felt::from(255)), Felt::from(255));
+        assert!(address.fit(Felt::MAX) < bound);
+        let max_class_hash = Felt::TWO.pow(251u128) - Felt::ONE;
+        assert_eq!(ArgumentType::ClassHash.fit(max_class_hash), max_class_hash);
+        assert_eq!(ArgumentType::Felt.fit(Felt::MAX), Felt::MAX);
+    }
+
+    #[test]
+    fn test_collect_slots() {
+        let types = vec![
+            ArgumentType::U256,
+            ArgumentType::Array(Box::new(ArgumentType::Uint(8))),
+            ArgumentType::Enum(vec![ArgumentType::Felt, ArgumentType::Struct(vec![])]),
+        ];
+        // u256 (1, 2), [7, 8], enum variant 0 holding 9
+        let input = felts(&[1, 2, 2, 7, 8, 0, 9]);
+        let slots = collect_slots(&types, &input).unwrap();
+        let scalars: Vec<usize> = slots
+            .iter()
+            .filter_map(|slot| match slot {
+                Slot::Scalar { offset, .. } => Some(*offset),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(scalars, vec![0, 1, 3, 4, 6]);
+        assert!(slots.iter().any(|slot| matches!(
+            slot,
+            Slot::Length {
+                offset: 2,
+                len: 2,
+                end: 5,
+                ..
+            }
+        )));
+        assert!(slots.iter().any(|slot| matches!(
+            slot,
+            Slot::Variant {
+                offset: 5,
+                end: 7,
+                ..
+            }
+        )));
+
+        // The empty variant has no payload
+        assert!(collect_slots(&types, &felts(&[1, 2, 0, 1])).is_some());
+        // Missing or extra felts, out of range variant
+        assert!(collect_slots(&types, &felts(&[1, 2, 2, 7])).is_none());
+        assert!(collect_slots(&types, &felts(&[1, 2, 0, 1, 5])).is_none());
+        assert!(collect_slots(&types, &felts(&[1, 2, 0, 2])).is_none());
+        assert_eq!(
+            ArgumentType::FeltArray.serialized_end(&felts(&[1, 2, 3]), 1),
+            Some(3)
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use cairo_fuzzer_core::mutator::{InputDatabase, MutationStrategy, Mutator};
+use starknet_types_core::felt::Felt;
+
+use crate::mutator::argument_type::{collect_slots, ArgumentType, Slot, MAX_ARRAY_LEN};
+use crate::runner::runner::StarkField;
+
+/// Mutates inputs serialized from typed arguments
+///
+/// Scalars are mutated by the builtin strategies of the shared mutator and kept in the
+/// range of their type, array lengths and enum variants are mutated along with their
+/// serialized elements so the input stays well formed. The input length changes with
+/// the arrays and the enums, the builtin strategies are disabled so they don't corrupt it.
+pub struct ArgumentsStrategy {
+    // Types of the entry point arguments
+    types: Vec<ArgumentType>,
+}
+
+impl ArgumentsStrategy {
+    pub fn new(types: Vec<ArgumentType>) -> Self {
+        Self { types }
+    }
+
+    /// Mutates a random selection of the felts of an input
+    /// Keeping the other felts intact preserves the progress made by the corpus entry
+    fn mutate_felts(mutator: &mut Mutator<StarkField>) {
+        if mutator.input.is_empty() {
+            return;
+        }
+        let mutations = mutator.rand(1, mutator.input.len());
+        for _ in 0..mutations {
+            let idx = mutator.rand(0, mutator.input.len() - 1);
+            let felt = mutator.input[idx];
+            mutator.input[idx] = mutator.mutate_felt(&felt);
+        }
+    }
+}
+
+impl MutationStrategy<StarkField> for ArgumentsStrategy {
+    fn name(&self) -> &str {
+        "arguments"
+    }
+
+    fn mutate(&mut self, mutator: &mut Mutator<StarkField>, _inputs: &dyn InputDatabase<Felt>) {
+        let Some(slots) = collect_slots(&self.types, &mutator.input) else {
+            // The input doesn't match the types, mutate it as raw felts
+            Self::mutate_felts(mutator);
+            return;
+        };
+        if slots.is_empty() {
+            return;
+        }
+
+        let mutations = mutator.rand(1, slots.len());
+        for _ in 0..mutations {
+            // Offsets change when an array or an enum is resized, collect them again
+            let slots = match collect_slots(&self.types, &mutator.input) {
+                Some(slots) if !slots.is_empty() => slots,
+                _ => return,
+            };
+            match &slots[mutator.rand(0, slots.len() - 1)] {
+                Slot::Scalar { offset, ty } => {
+                    let felt = mutator.input[*offset];
+                    mutator.input[*offset] = ty.fit(mutator.mutate_felt(&felt));
+                }
+                Slot::Length {
+                    offset,
+                    len,
+                    end,
+                    item,
+                } => {
+                    let grow = *len == 0 || (*len < MAX_ARRAY_LEN && mutator.rand(0, 1) == 0);
+                    let input = &mut mutator.input;
+                    if grow {
+                        // Append a new element
+                        input.splice(*end..*end, item.default_value());
+                        input[*offset] = Felt::from(*len + 1);
+                    } else if let Some(first_end) = item.serialized_end(input, offset + 1) {
+                        // Remove the first element
+                        input.drain(offset + 1..first_end);
+                        input[*offset] = Felt::from(*len - 1);
+                    }
+                }
+                Slot::Variant {
+                    offset,
+                    end,
+                    variants,
+                } => {
+                    // Switch to another variant with a default payload
+                    let index = mutator.rand(0, variants.len() - 1);
+                    let input = &mut mutator.input;
+                    input.splice(offset + 1..*end, variants[index].default_value());
+                    input[*offset] = Felt::from(index);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_fuzzer_core::corpus::Corpus;
+
+    use super::*;
+
+    #[test]
+    fn test_arguments_strategy() {
+        let types = vec![
+            ArgumentType::Uint(8),
+            ArgumentType::Array(Box::new(ArgumentType::Bool)),
+            ArgumentType::Enum(vec![ArgumentType::Felt, ArgumentType::U256]),
+        ];
+        let mut mutator = Mutator::new()
+            .seed(1)
+            .builtins(false)
+            .strategy(Box::new(ArgumentsStrategy::new(types.clone())));
+        mutator.input = types.iter().flat_map(|ty| ty.default_value()).collect();
+        let corpus = Corpus::default();
+        for _ in 0..1000 {
+            mutator.mutate(4, &corpus);
+            // The input stays well formed and the scalars in the range of their type
+            let slots = collect_slots(&types, &mutator.input).expect("Malformed input");
+            for slot in slots {
+                if let Slot::Scalar { offset, ty } = slot {
+                    assert_eq!(ty.fit(mutator.input[offset]), mutator.input[offset]);
+                }
+            }
+        }
+        assert_eq!(mutator.last_strategies(), vec!["arguments"]);
+    }
+}
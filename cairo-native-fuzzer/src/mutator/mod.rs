@@ -1,3 +1,2 @@
 pub mod argument_type;
-pub mod basic_mutator;
-pub mod magic_values;
+pub mod arguments;
@@ -0,0 +1,516 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::ffi::c_void;
+use std::sync::OnceLock;
+
+use cairo_lang_sierra::ids::{
+    ConcreteLibfuncId, ConcreteTypeId, GenericLibfuncId, GenericTypeId, VarId,
+};
+use cairo_lang_sierra::program::{
+    BranchInfo, BranchTarget, ConcreteLibfuncLongId, ConcreteTypeLongId, GenericArg, Invocation,
+    LibfuncDeclaration, Program, Statement, StatementIdx, TypeDeclaration,
+};
+use num_bigint::BigInt;
+use starknet_types_core::felt::Felt;
+
+/// First element of the arrays printed by the instrumentation, the short string 'coverage'
+pub const COVERAGE_MARKER: u64 = 0x636f766572616765;
+/// Previous block of the first block reached by an execution
+const ENTRY_BLOCK: u64 = u32::MAX as u64;
+/// Global holding the address of the runtime function called by the `print` libfunc
+const PRINT_SYMBOL: &str = "cairo_native__libfunc__debug__print";
+
+/// Runtime function called by the `print` libfunc
+type PrintFn = unsafe extern "C" fn(i32, *const [u8; 32], u32) -> i32;
+
+/// cairo-native runtime function, called for the prints of the fuzzed program
+static RUNTIME_PRINT: OnceLock<PrintFn> = OnceLock::new();
+
+thread_local! {
+    /// Blocks reached by the execution running on the current thread
+    static TRACE: RefCell<EdgeTrace> = RefCell::new(EdgeTrace::default());
+}
+
+/// Edges between the blocks reached by an execution
+struct EdgeTrace {
+    previous: u64,
+    edges: HashSet<u64>,
+}
+
+impl Default for EdgeTrace {
+    fn default() -> Self {
+        EdgeTrace {
+            previous: ENTRY_BLOCK,
+            edges: HashSet::new(),
+        }
+    }
+}
+
+/// Returns the ID of the edge between two blocks, identified by the index of their first
+/// Sierra statement
+pub fn edge_id(from: u64, to: u64) -> u64 {
+    (from << 32) | (to & ENTRY_BLOCK)
+}
+
+/// Clears the edges recorded by the current thread before an execution
+pub fn start_recording() {
+    TRACE.with(|trace| *trace.borrow_mut() = EdgeTrace::default());
+}
+
+/// Records that the current thread reached a block
+pub fn record_block(block: u64) {
+    TRACE.with(|trace| {
+        let mut trace = trace.borrow_mut();
+        let edge = edge_id(trace.previous, block);
+        trace.edges.insert(edge);
+        trace.previous = block;
+    });
+}
+
+/// Returns the edges reached by the current thread since `start_recording`, sorted
+pub fn take_edges() -> Vec<u64> {
+    let trace = TRACE.with(|trace| std::mem::take(&mut *trace.borrow_mut()));
+    let mut edges: Vec<u64> = trace.edges.into_iter().collect();
+    edges.sort_unstable();
+    edges
+}
+
+/// Replaces the runtime function of the `print` libfunc in a compiled program by one
+/// recording the blocks reached, the other prints are forwarded to the runtime
+/// `find_symbol_ptr` looks up a symbol in the executor of the program
+pub fn install_hook(find_symbol_ptr: impl Fn(&str) -> Option<*mut c_void>) -> Result<(), String> {
+    let global = find_symbol_ptr(PRINT_SYMBOL)
+        .ok_or("The program is not instrumented for coverage")?
+        as *mut Option<PrintFn>;
+    // SAFETY: cairo-native sets the global to the address of its runtime function when it
+    // creates the executor, the compiled program calls the function through it
+    unsafe {
+        match *global {
+            Some(print) if print as usize == coverage_print as PrintFn as usize => {}
+            Some(print) => {
+                RUNTIME_PRINT.get_or_init(|| print);
+                *global = Some(coverage_print);
+            }
+            None => return Err("The print runtime function is not set".to_string()),
+        }
+    }
+    Ok(())
+}
+
+/// Records the blocks printed by the instrumentation
+unsafe extern "C" fn coverage_print(target_fd: i32, data: *const [u8; 32], len: u32) -> i32 {
+    if len == 2 {
+        let marker = *data;
+        let block = *data.add(1);
+        if marker == Felt::from(COVERAGE_MARKER).to_bytes_le() {
+            let mut id = [0u8; 8];
+            id.copy_from_slice(&block[..8]);
+            record_block(u64::from_le_bytes(id));
+            return 0;
+        }
+    }
+    match RUNTIME_PRINT.get() {
+        Some(print) => print(target_fd, data, len),
+        None => 0,
+    }
+}
+
+/// Number of Sierra statements printing the ID of a block
+pub const MARKER_LEN: usize = 6;
+
+/// Declarations used by the statements marking the blocks
+struct Markers {
+    array_new: ConcreteLibfuncId,
+    array_append: ConcreteLibfuncId,
+    print: ConcreteLibfuncId,
+    // Variables defined and consumed by the marker statements
+    array: VarId,
+    value: VarId,
+}
+
+impl Markers {
+    /// Declares the types and libfuncs of the marker statements in the program
+    fn declare(program: &mut Program) -> Self {
+        let felt252 = declare_type(program, "felt252", vec![]);
+        // Type of the printed arrays
+        declare_type(program, "Array", vec![GenericArg::Type(felt252.clone())]);
+        let element = vec![GenericArg::Type(felt252)];
+        let next_var = max_var_id(program) + 1;
+        Markers {
+            array_new: declare_libfunc(program, "array_new", element.clone()),
+            array_append: declare_libfunc(program, "array_append", element),
+            print: declare_libfunc(program, "print", vec![]),
+            array: VarId::new(next_var),
+            value: VarId::new(next_var + 1),
+        }
+    }
+
+    /// Statements printing `[COVERAGE_MARKER, block]`
+    fn statements(&self, program: &mut Program, block: usize) -> Vec<Statement> {
+        let marker = declare_libfunc(
+            program,
+            "felt252_const",
+            vec![GenericArg::Value(BigInt::from(COVERAGE_MARKER))],
+        );
+        let block = declare_libfunc(
+            program,
+            "felt252_const",
+            vec![GenericArg::Value(BigInt::from(block))],
+        );
+        let array = || vec![self.array.clone()];
+        let append = || vec![self.array.clone(), self.value.clone()];
+        vec![
+            invocation(&self.array_new, vec![], array()),
+            invocation(&marker, vec![], vec![self.value.clone()]),
+            invocation(&self.array_append, append(), array()),
+            invocation(&block, vec![], vec![self.value.clone()]),
+            invocation(&self.array_append, append(), array()),
+            invocation(&self.print, array(), vec![]),
+        ]
+    }
+}
+
+/// Instruments a Sierra program to record the edges between its blocks
+///
+/// Each block starts with statements printing a marker and the index of the first
+/// statement of the block in the original program, the hook installed by `install_hook`
+/// records them. The marker is placed after the `branch_align` starting the branches.
+pub fn instrument_program(program: &Program) -> Program {
+    let mut instrumented = program.clone();
+    let markers = Markers::declare(&mut instrumented);
+
+    // Blocks start at the function entry points and the branches targets
+    let branch_align = GenericLibfuncId::from_string("branch_align");
+    let is_branch_align = |statement: &Statement| match statement {
+        Statement::Invocation(invocation) => {
+            program.libfunc_declarations.iter().any(|declaration| {
+                declaration.id == invocation.libfunc_id
+                    && declaration.long_id.generic_id == branch_align
+            })
+        }
+        Statement::Return(_) => false,
+    };
+    let mut markers_at: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for block in block_starts(program) {
+        let at = match program.statements.get(block) {
+            Some(statement) if is_branch_align(statement) => block + 1,
+            _ => block,
+        };
+        markers_at.entry(at).or_default().push(block);
+    }
+
+    // Position of the original statements, a jump to a block also runs its marker
+    let mut starts = Vec::with_capacity(program.statements.len());
+    let mut statements = Vec::new();
+    for (idx, statement) in program.statements.iter().enumerate() {
+        starts.push(statements.len());
+        for block in markers_at.get(&idx).into_iter().flatten() {
+            statements.extend(markers.statements(&mut instrumented, *block));
+        }
+        statements.push(statement.clone());
+    }
+    let remap = |target: &StatementIdx| StatementIdx(starts[target.0]);
+    for statement in &mut statements {
+        if let Statement::Invocation(invocation) = statement {
+            for branch in &mut invocation.branches {
+                if let BranchTarget::Statement(target) = &branch.target {
+                    branch.target = BranchTarget::Statement(remap(target));
+                }
+            }
+        }
+    }
+    for function in &mut instrumented.funcs {
+        function.entry_point = remap(&function.entry_point);
+    }
+    instrumented.statements = statements;
+    instrumented
+}
+
+/// Returns the index of the first statement of each block of the program
+pub fn block_starts(program: &Program) -> BTreeSet<usize> {
+    let mut starts: BTreeSet<usize> = program
+        .funcs
+        .iter()
+        .map(|function| function.entry_point.0)
+        .collect();
+    for (idx, statement) in program.statements.iter().enumerate() {
+        let Statement::Invocation(invocation) = statement else {
+            continue;
+        };
+        let branching = invocation.branches.len() > 1;
+        for branch in &invocation.branches {
+            match branch.target {
+                BranchTarget::Statement(target) => {
+                    starts.insert(target.0);
+                }
+                BranchTarget::Fallthrough if branching => {
+                    starts.insert(idx + 1);
+                }
+                BranchTarget::Fallthrough => {}
+            }
+        }
+    }
+    starts.retain(|start| *start < program.statements.len());
+    starts
+}
+
+/// Returns the ID of a type, declaring it if the program does not use it yet
+fn declare_type(
+    program: &mut Program,
+    name: &str,
+    generic_args: Vec<GenericArg>,
+) -> ConcreteTypeId {
+    let long_id = ConcreteTypeLongId {
+        generic_id: GenericTypeId::from_string(name),
+        generic_args,
+    };
+    if let Some(declaration) = program
+        .type_declarations
+        .iter()
+        .find(|declaration| declaration.long_id == long_id)
+    {
+        return declaration.id.clone();
+    }
+    let id = ConcreteTypeId {
+        id: program
+            .type_declarations
+            .iter()
+            .map(|declaration| declaration.id.id + 1)
+            .max()
+            .unwrap_or_default(),
+        debug_name: Some(long_id.to_string().into()),
+    };
+    program.type_declarations.push(TypeDeclaration {
+        id: id.clone(),
+        long_id,
+        declared_type_info: None,
+    });
+    id
+}
+
+/// Returns the ID of a libfunc, declaring it if the program does not use it yet
+fn declare_libfunc(
+    program: &mut Program,
+    name: &str,
+    generic_args: Vec<GenericArg>,
+) -> ConcreteLibfuncId {
+    let long_id = ConcreteLibfuncLongId {
+        generic_id: GenericLibfuncId::from_string(name),
+        generic_args,
+    };
+    if let Some(declaration) = program
+        .libfunc_declarations
+        .iter()
+        .find(|declaration| declaration.long_id == long_id)
+    {
+        return declaration.id.clone();
+    }
+    let id = ConcreteLibfuncId {
+        id: program
+            .libfunc_declarations
+            .iter()
+            .map(|declaration| declaration.id.id + 1)
+            .max()
+            .unwrap_or_default(),
+        debug_name: Some(long_id.to_string().into()),
+    };
+    program.libfunc_declarations.push(LibfuncDeclaration {
+        id: id.clone(),
+        long_id,
+    });
+    id
+}
+
+/// Returns the largest variable ID of the program
+fn max_var_id(program: &Program) -> u64 {
+    let statements = program
+        .statements
+        .iter()
+        .flat_map(|statement| match statement {
+            Statement::Invocation(invocation) => invocation
+                .args
+                .iter()
+                .chain(
+                    invocation
+                        .branches
+                        .iter()
+                        .flat_map(|branch| &branch.results),
+                )
+                .map(|var| var.id)
+                .collect::<Vec<_>>(),
+            Statement::Return(vars) => vars.iter().map(|var| var.id).collect(),
+        });
+    let params = program
+        .funcs
+        .iter()
+        .flat_map(|function| function.params.iter().map(|param| param.id.id));
+    statements.chain(params).max().unwrap_or_default()
+}
+
+/// Invocation of a libfunc with a single branch falling through
+fn invocation(libfunc_id: &ConcreteLibfuncId, args: Vec<VarId>, results: Vec<VarId>) -> Statement {
+    Statement::Invocation(Invocation {
+        libfunc_id: libfunc_id.clone(),
+        args,
+        branches: vec![BranchInfo {
+            target: BranchTarget::Fallthrough,
+            results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use cairo_fuzzer_core::runner::Runner;
+    use cairo_lang_compiler::CompilerConfig;
+    use cairo_lang_sierra::extensions::core::{CoreLibfunc, CoreType};
+    use cairo_lang_sierra::program_registry::ProgramRegistry;
+    use cairo_lang_sierra::ProgramParser;
+    use cairo_lang_starknet::compile::compile_path;
+    use cairo_native::context::NativeContext;
+
+    use super::*;
+    use crate::fuzzer::utils::find_entry_point_id;
+    use crate::runner::runner::{
+        compile_sierra_program, create_executor, NativeExecutor, NativeRunner,
+    };
+
+    const IS_ZERO: &str = "
+        type felt252 = felt252;
+        type NonZeroFelt252 = NonZero<felt252>;
+
+        libfunc felt252_is_zero = felt252_is_zero;
+        libfunc branch_align = branch_align;
+        libfunc drop<NonZeroFelt252> = drop<NonZeroFelt252>;
+        libfunc felt252_const<1> = felt252_const<1>;
+        libfunc felt252_const<0> = felt252_const<0>;
+
+        felt252_is_zero([0]) { fallthrough() 4([1]) };
+        branch_align() -> ();
+        felt252_const<1>() -> ([0]);
+        return([0]);
+        branch_align() -> ();
+        drop<NonZeroFelt252>([1]) -> ();
+        felt252_const<0>() -> ([0]);
+        return([0]);
+
+        test::is_zero@0([0]: felt252) -> (felt252);
+    ";
+
+    #[test]
+    fn test_instrument_program() {
+        let program = ProgramParser::new().parse(IS_ZERO).unwrap();
+        assert_eq!(block_starts(&program), BTreeSet::from([0, 1, 4]));
+
+        let instrumented = instrument_program(&program);
+        assert_eq!(
+            instrumented.statements.len(),
+            program.statements.len() + 3 * MARKER_LEN
+        );
+        // The entry block is marked before its first statement, the branches after their
+        // branch_align, the jump to a branch lands on its branch_align
+        let branch_target = 2 + MARKER_LEN + 2 + MARKER_LEN;
+        assert_eq!(instrumented.funcs[0].entry_point, StatementIdx(0));
+        match &instrumented.statements[MARKER_LEN] {
+            Statement::Invocation(invocation) => assert_eq!(
+                invocation.branches[1].target,
+                BranchTarget::Statement(StatementIdx(branch_target))
+            ),
+            statement => panic!("unexpected statement {}", statement),
+        }
+        assert_eq!(
+            instrumented.statements[branch_target],
+            program.statements[4]
+        );
+        assert_eq!(
+            instrumented.statements[branch_target + MARKER_LEN + 1],
+            program.statements[5]
+        );
+
+        // The marker prints the index of the first statement of the block
+        let libfunc = |statement: &Statement| match statement {
+            Statement::Invocation(invocation) => instrumented
+                .libfunc_declarations
+                .iter()
+                .find(|declaration| declaration.id == invocation.libfunc_id)
+                .unwrap()
+                .long_id
+                .to_string(),
+            Statement::Return(_) => "return".to_string(),
+        };
+        let marker: Vec<String> = instrumented.statements
+            [branch_target + 1..branch_target + 1 + MARKER_LEN]
+            .iter()
+            .map(libfunc)
+            .collect();
+        assert_eq!(marker[0], "array_new<felt252>");
+        assert_eq!(marker[1], format!("felt252_const<{}>", COVERAGE_MARKER));
+        assert_eq!(marker[3], "felt252_const<4>");
+        assert_eq!(marker[5], "print");
+        // The existing declarations are reused
+        assert_eq!(
+            instrumented
+                .libfunc_declarations
+                .iter()
+                .filter(|declaration| declaration.long_id.to_string() == "felt252_const<0>")
+                .count(),
+            1
+        );
+
+        ProgramRegistry::<CoreType, CoreLibfunc>::new(&instrumented).unwrap();
+    }
+
+    #[test]
+    fn test_record_edges() {
+        start_recording();
+        for block in [0, 4, 9, 4, 9] {
+            record_block(block);
+        }
+        assert_eq!(
+            take_edges(),
+            vec![
+                edge_id(0, 4),
+                edge_id(4, 9),
+                edge_id(9, 4),
+                edge_id(ENTRY_BLOCK, 0)
+            ]
+        );
+        start_recording();
+        assert!(take_edges().is_empty());
+    }
+
+    #[test]
+    fn test_native_coverage() {
+        let contract = compile_path(
+            Path::new("examples/cairo/echo.cairo"),
+            None,
+            CompilerConfig {
+                replace_ids: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let program = Arc::new(instrument_program(
+            &contract.extract_sierra_program().unwrap(),
+        ));
+        let entry_point_id = find_entry_point_id(&Some(program.clone()), "echo_felt");
+        let native_context = NativeContext::new();
+        let module = compile_sierra_program(&native_context, &program).unwrap();
+        let executor = NativeExecutor::Jit(create_executor(module).unwrap());
+
+        let coverage = |value: u64| {
+            let (_, result) = NativeRunner::new(&executor, &entry_point_id)
+                .run(&[Felt::from(value)])
+                .unwrap();
+            result.coverage
+        };
+        // Echoing a different value takes the same edges
+        assert!(!coverage(1).is_empty());
+        assert_eq!(coverage(1), coverage(3));
+        // The failing assert takes other ones
+        assert_ne!(coverage(1), coverage(2));
+    }
+}
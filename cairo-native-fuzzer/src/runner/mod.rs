@@ -1,2 +1,3 @@
+pub mod coverage;
 pub mod runner;
 pub mod syscall_handler;
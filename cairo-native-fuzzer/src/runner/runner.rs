@@ -1,17 +1,73 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use cairo_fuzzer_core::felt::Field;
+use cairo_fuzzer_core::runner::{ExecutionResult, Runner};
+use cairo_lang_sierra::extensions::core::{CoreLibfunc, CoreType};
 use cairo_lang_sierra::ids::FunctionId;
 use cairo_lang_sierra::program::Program;
+use cairo_lang_sierra::program_registry::ProgramRegistry;
 use cairo_native::context::NativeContext;
 use cairo_native::execution_result::ContractExecutionResult;
-use cairo_native::executor::JitNativeExecutor;
+use cairo_native::executor::{AotNativeExecutor, JitNativeExecutor};
+use cairo_native::metadata::gas::GasMetadata;
 use cairo_native::module::NativeModule;
+use cairo_native::{module_to_object, object_to_shared_lib, OptLevel};
+use libloading::Library;
+use sha2::{Digest, Sha256};
 use starknet_types_core::felt::Felt;
 
+use crate::runner::coverage;
 use crate::runner::syscall_handler::SyscallHandler;
+use crate::utils::get_cairo_native_version;
+
+/// Gas available to each execution
+pub const INITIAL_GAS: u64 = u64::MAX;
+
+/// Field arithmetic of the cairo-native felts, used by the shared mutator and dictionaries
+pub struct StarkField;
+
+impl Field for StarkField {
+    type Felt = Felt;
+
+    fn to_be_bytes(felt: &Felt) -> [u8; 32] {
+        felt.to_bytes_be()
+    }
+
+    fn from_be_bytes(bytes: &[u8; 32]) -> Felt {
+        Felt::from_bytes_be(bytes)
+    }
+
+    fn add(a: &Felt, b: &Felt) -> Felt {
+        *a + *b
+    }
+
+    fn sub(a: &Felt, b: &Felt) -> Felt {
+        *a - *b
+    }
+
+    fn neg(a: &Felt) -> Felt {
+        -*a
+    }
+}
+
+/// Executor of a compiled Sierra program
+pub enum NativeExecutor<'a> {
+    /// Program compiled in memory by the current thread
+    Jit(JitNativeExecutor<'a>),
+    /// Program compiled to a shared library, can be shared between threads
+    Aot(Arc<AotNativeExecutor>),
+}
 
-// Create a JIT Native Executor
-pub fn create_executor<'a>(native_program: NativeModule<'a>) -> JitNativeExecutor<'a> {
-    JitNativeExecutor::from_native_module(native_program, Default::default())
-        .expect("Failed to create JIT native executor from the provided native module")
+/// Create a JIT Native Executor recording the coverage of the instrumented program
+pub fn create_executor<'a>(
+    native_program: NativeModule<'a>,
+) -> Result<JitNativeExecutor<'a>, String> {
+    let executor = JitNativeExecutor::from_native_module(native_program, Default::default())
+        .map_err(|e| format!("Failed to create JIT native executor: {}", e))?;
+    coverage::install_hook(|symbol| executor.find_symbol_ptr(symbol))?;
+    Ok(executor)
 }
 
 /// Compile a Sierra program into a MLIR module
@@ -24,13 +80,160 @@ pub fn compile_sierra_program<'a>(
         .map_err(|e| e.to_string())
 }
 
+/// Returns the key of a Sierra program in the AOT cache, the SHA-256 of the program
+/// and of the cairo-native version used to compile it
+/// The key is stable across runs and builds of the fuzzer
+pub fn aot_cache_key(sierra_program: &Program) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(sierra_program.to_string());
+    hasher.update([0]);
+    hasher.update(get_cairo_native_version());
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Loads the AOT compiled shared library of a Sierra program from `cache_dir`
+/// The program is compiled and saved in the cache if it's not there yet
+pub fn load_aot_executor(
+    native_context: &NativeContext,
+    sierra_program: &Program,
+    cache_dir: &Path,
+) -> Result<AotNativeExecutor, String> {
+    let library_path = cache_dir.join(format!("{}.so", aot_cache_key(sierra_program)));
+
+    if !library_path.exists() {
+        let native_module = compile_sierra_program(native_context, sierra_program)?;
+        let object = module_to_object(native_module.module(), OptLevel::Default)
+            .map_err(|e| format!("Failed to compile MLIR module to object: {}", e))?;
+
+        // Link into a temporary file first so concurrent runs never load a partial library
+        fs::create_dir_all(cache_dir)
+            .map_err(|e| format!("Failed to create AOT cache directory: {}", e))?;
+        let tmp_path = library_path.with_extension(format!("so.{}.tmp", std::process::id()));
+        object_to_shared_lib(&object, &tmp_path)
+            .map_err(|e| format!("Failed to link shared library: {}", e))?;
+        fs::rename(&tmp_path, &library_path)
+            .map_err(|e| format!("Failed to save shared library in the AOT cache: {}", e))?;
+    }
+
+    // SAFETY: the library was built by `object_to_shared_lib` from this exact program
+    let library = unsafe { Library::new(&library_path) }
+        .map_err(|e| format!("Failed to load {}: {}", library_path.display(), e))?;
+    let registry =
+        ProgramRegistry::<CoreType, CoreLibfunc>::new(sierra_program).map_err(|e| e.to_string())?;
+    let gas_metadata =
+        GasMetadata::new(sierra_program, Some(Default::default())).map_err(|e| e.to_string())?;
+
+    let executor = AotNativeExecutor::new(library, registry, gas_metadata);
+    coverage::install_hook(|symbol| executor.find_symbol_ptr(symbol))?;
+    Ok(executor)
+}
+
 /// Execute a program with arbitraty entrypoint & parameters
 pub fn run_program(
-    executor: &JitNativeExecutor,
+    executor: &NativeExecutor,
     entry_point_id: &FunctionId,
     params: &Vec<Felt>,
 ) -> Result<ContractExecutionResult, String> {
-    executor
-        .invoke_contract_dynamic(entry_point_id, params, Some(u64::MAX), SyscallHandler)
-        .map_err(|e| e.to_string())
+    run_program_with_handler(
+        executor,
+        entry_point_id,
+        params,
+        &mut SyscallHandler::default(),
+    )
+}
+
+/// Execute a program with the given syscall handler, which keeps the storage of the execution
+pub fn run_program_with_handler(
+    executor: &NativeExecutor,
+    entry_point_id: &FunctionId,
+    params: &[Felt],
+    syscall_handler: &mut SyscallHandler,
+) -> Result<ContractExecutionResult, String> {
+    match executor {
+        NativeExecutor::Jit(executor) => executor.invoke_contract_dynamic(
+            entry_point_id,
+            params,
+            Some(INITIAL_GAS),
+            syscall_handler,
+        ),
+        NativeExecutor::Aot(executor) => executor.invoke_contract_dynamic(
+            entry_point_id,
+            params,
+            Some(INITIAL_GAS),
+            syscall_handler,
+        ),
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// Runs an entry point of the compiled program on a fresh storage for each execution
+/// The program must be instrumented by `coverage::instrument_program`, the coverage of
+/// the executions is the edges between its blocks
+#[derive(Clone)]
+pub struct NativeRunner<'a, 'b> {
+    executor: &'a NativeExecutor<'b>,
+    entry_point_id: &'a FunctionId,
+}
+
+impl<'a, 'b> NativeRunner<'a, 'b> {
+    pub fn new(executor: &'a NativeExecutor<'b>, entry_point_id: &'a FunctionId) -> Self {
+        Self {
+            executor,
+            entry_point_id,
+        }
+    }
+}
+
+impl Runner for NativeRunner<'_, '_> {
+    type Felt = Felt;
+    type Field = StarkField;
+
+    fn run(self, data: &[Felt]) -> Result<(Self, ExecutionResult<Felt>), String> {
+        let mut syscall_handler = SyscallHandler::default();
+        coverage::start_recording();
+        let result = run_program_with_handler(
+            self.executor,
+            self.entry_point_id,
+            data,
+            &mut syscall_handler,
+        );
+        let edges = coverage::take_edges();
+        let result = result?;
+        let execution_result = ExecutionResult {
+            failure_flag: result.failure_flag,
+            coverage: edges,
+            steps: INITIAL_GAS.saturating_sub(result.remaining_gas),
+            builtins: Default::default(),
+            storage_writes: syscall_handler.storage.into_iter().collect(),
+            error_msg: result.error_msg,
+            retdata: result.return_values,
+        };
+        Ok((self, execution_result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_lang_sierra::ProgramParser;
+
+    use super::*;
+
+    #[test]
+    fn test_aot_cache_key() {
+        let program = ProgramParser::new()
+            .parse("type felt252 = felt252;")
+            .unwrap();
+        let other = ProgramParser::new()
+            .parse("type felt252 = felt252;\ntype u8 = u8;")
+            .unwrap();
+        let key = aot_cache_key(&program);
+        assert_eq!(key.len(), 64);
+        assert!(key.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(key, aot_cache_key(&program.clone()));
+        assert_ne!(key, aot_cache_key(&other));
+    }
 }
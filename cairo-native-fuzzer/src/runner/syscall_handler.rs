@@ -1,5 +1,7 @@
 // Source : https://github.com/lambdaclass/cairo_native/blob/2bad480b4f59cd047626b9b5697eb90fa723ef07/examples/erc20.rs
 
+use std::collections::HashMap;
+
 use cairo_native::starknet::BlockInfo;
 use cairo_native::starknet::ExecutionInfo;
 use cairo_native::starknet::ExecutionInfoV2;
@@ -13,8 +15,13 @@ use cairo_native::starknet::TxV2Info;
 use cairo_native::starknet::U256;
 use starknet_types_core::felt::Felt;
 
-#[derive(Debug)]
-pub struct SyscallHandler;
+/// Syscall handler of the fuzzed contract
+/// The storage starts empty for each execution, like a freshly deployed contract
+#[derive(Debug, Default)]
+pub struct SyscallHandler {
+    // Values written by the contract, by storage address
+    pub storage: HashMap<Felt, Felt>,
+}
 
 impl StarknetSyscallHandler for SyscallHandler {
     fn get_block_hash(&mut self, block_number: u64, _gas: &mut u64) -> SyscallResult<Felt> {
@@ -132,22 +139,21 @@ impl StarknetSyscallHandler for SyscallHandler {
 
     fn storage_read(
         &mut self,
-        address_domain: u32,
+        _address_domain: u32,
         address: Felt,
         _gas: &mut u64,
     ) -> SyscallResult<Felt> {
-        println!("Called `storage_read({address_domain}, {address})` from MLIR.");
-        Ok(address * Felt::from(3))
+        Ok(self.storage.get(&address).copied().unwrap_or(Felt::ZERO))
     }
 
     fn storage_write(
         &mut self,
-        address_domain: u32,
+        _address_domain: u32,
         address: Felt,
         value: Felt,
         _gas: &mut u64,
     ) -> SyscallResult<()> {
-        println!("Called `storage_write({address_domain}, {address}, {value})` from MLIR.");
+        self.storage.insert(address, value);
         Ok(())
     }
 
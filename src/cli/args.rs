@@ -1,4 +1,8 @@
-use clap::{self, Parser};
+use crate::fuzzer::schedule::Schedule;
+use crate::runner::runner::Engine;
+use cairo_fuzzer_core::plot::Metric;
+use cairo_fuzzer_core::stats_log::StatsFormat;
+use clap::{self, Parser, Subcommand};
 
 #[derive(Debug, Parser)]
 pub struct Opt {
@@ -84,12 +88,45 @@ pub struct Opt {
 
     #[arg(
         long,
-        help = "Enable fuzzer logs in file",
+        help = "Extract the contract constants into an automatic dictionnary",
+        name = "AUTO_DICT",
+        default_value = "false"
+    )]
+    pub auto_dict: bool,
+
+    #[arg(
+        long,
+        help = "Path where the automatic dictionnary is dumped",
+        name = "DUMP_DICT",
+        default_value = ""
+    )]
+    pub dump_dict: String,
+
+    #[arg(
+        long,
+        help = "Write the fuzzer stats every second in a stats log of the workspace",
         name = "LOGS",
         default_value = "false"
     )]
     pub logs: bool,
 
+    #[arg(
+        long,
+        value_enum,
+        help = "Format of the stats log",
+        name = "STATS_FORMAT",
+        default_value = "csv"
+    )]
+    pub stats_format: StatsFormat,
+
+    #[arg(
+        long,
+        help = "Address of the HTTP endpoint serving the fuzzer metrics in the Prometheus format, e.g. 127.0.0.1:9100",
+        name = "METRICS",
+        default_value = ""
+    )]
+    pub metrics: String,
+
     #[arg(
         long,
         help = "Set a custom seed (only applicable for 1 core run)",
@@ -138,4 +175,173 @@ pub struct Opt {
     pub analyze: bool,
     #[arg(long, help = "Iteration Number", name = "ITER", default_value = "-1")]
     pub iter: i64,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Power schedule used to pick the corpus entries to mutate",
+        name = "SCHEDULE",
+        default_value = "uniform"
+    )]
+    pub schedule: Schedule,
+
+    #[arg(
+        long,
+        help = "Report the inputs executing more Cairo steps than this threshold (0 to disable)",
+        name = "MAX_STEPS",
+        default_value = "0"
+    )]
+    pub max_steps: u64,
+
+    #[arg(
+        long,
+        help = "Add the inputs executing the most Cairo steps to the corpus",
+        name = "MAXIMIZE_STEPS",
+        default_value = "false"
+    )]
+    pub maximize_steps: bool,
+
+    #[arg(
+        long,
+        help = "Execute the corpus and dump the results for differential fuzzing with cairo-native",
+        name = "DIFF_DUMP",
+        default_value = ""
+    )]
+    pub diff_dump: String,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Execution engine of the fuzzed contract",
+        name = "ENGINE",
+        default_value = "vm"
+    )]
+    pub engine: Engine,
+
+    #[arg(
+        long,
+        help = "Pad or truncate the loaded inputs that do not match the function arguments",
+        name = "ADAPT_INPUTS",
+        default_value = "false"
+    )]
+    pub adapt_inputs: bool,
+
+    #[arg(
+        long,
+        help = "Path to the JSON state dump loaded into the runner state before fuzzing",
+        name = "STATE_DUMP",
+        default_value = ""
+    )]
+    pub state_dump: String,
+
+    #[arg(
+        long,
+        help = "Load the state of a dump address at another address, as FROM=TO (the fuzzed contract is at 0x457)",
+        name = "MAP_ADDRESS"
+    )]
+    pub map_address: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Folder where the lcov and HTML source coverage reports of the corpus are written",
+        name = "COVERAGE_REPORT",
+        default_value = ""
+    )]
+    pub coverage_report: String,
+
+    #[arg(
+        long,
+        help = "Draw a dashboard of the fuzzing session instead of the stats lines (only in a terminal)",
+        name = "TUI",
+        default_value = "false"
+    )]
+    pub tui: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Convert a JSON inputs file to a binary corpus file
+    ImportCorpus {
+        #[arg(help = "JSON inputs file to convert", name = "INPUTFILE")]
+        inputfile: String,
+        #[arg(help = "Binary corpus file to create", name = "CORPUS")]
+        corpus: String,
+    },
+    /// Convert a binary corpus file to a JSON inputs file
+    ExportCorpus {
+        #[arg(help = "Binary corpus file to convert", name = "CORPUS")]
+        corpus: String,
+        #[arg(help = "JSON inputs file to create", name = "INPUTFILE")]
+        inputfile: String,
+    },
+    /// Check inputs files or folders against the function arguments (requires --contract and --function)
+    Validate {
+        #[arg(
+            help = "Inputs files or folders to check (JSON or binary corpus)",
+            name = "FILES",
+            required = true
+        )]
+        files: Vec<String>,
+    },
+    /// Import the calldata of recorded invoke transactions calling the function (requires --contract and --function)
+    ImportTxs {
+        #[arg(
+            help = "JSON or JSON lines file of transactions with contract_address, selector, calldata and caller",
+            name = "TXS"
+        )]
+        txs: String,
+        #[arg(
+            long,
+            help = "Only import the transactions sent to this contract address",
+            name = "ADDRESS"
+        )]
+        address: Option<String>,
+        #[arg(
+            long,
+            help = "File of the imported inputs, a binary corpus if it ends with .corpus, a JSON inputs file otherwise",
+            name = "OUTPUT"
+        )]
+        output: String,
+    },
+    /// Merge corpora into a minimal corpus covering the same edges (requires --contract, --casm and --function)
+    Merge {
+        #[arg(
+            help = "Inputs files or folders to merge (JSON or binary corpus)",
+            name = "SOURCES",
+            required = true
+        )]
+        sources: Vec<String>,
+        #[arg(
+            long,
+            help = "File of the merged corpus, a binary corpus if it ends with .corpus, a JSON inputs file otherwise",
+            name = "OUTPUT"
+        )]
+        output: String,
+    },
+    /// Chart a statistic of stats logs over time as an SVG file, one line per log (PNG is not supported)
+    Plot {
+        #[arg(
+            help = "Stats logs to chart (CSV or JSON lines)",
+            name = "STATS",
+            required = true
+        )]
+        stats: Vec<String>,
+        #[arg(
+            long,
+            value_enum,
+            help = "Statistic to chart",
+            name = "METRIC",
+            default_value = "coverage"
+        )]
+        metric: Metric,
+        #[arg(
+            long,
+            help = "SVG file of the chart, only SVG is supported",
+            name = "OUTPUT"
+        )]
+        output: String,
+    },
 }
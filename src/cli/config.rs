@@ -1,3 +1,6 @@
+use crate::fuzzer::schedule::Schedule;
+use crate::runner::runner::Engine;
+use cairo_fuzzer_core::stats_log::StatsFormat;
 use serde::{Deserialize, Serialize};
 use serde_json;
 use std::fs;
@@ -14,6 +17,10 @@ pub struct Config {
     pub input_folder: String,
     pub crash_folder: String,
     pub dict: String,
+    #[serde(default)]
+    pub auto_dict: bool,
+    #[serde(default)]
+    pub dump_dict: String,
     pub cores: i32,
     pub logs: bool,
     pub seed: Option<u64>,
@@ -22,6 +29,30 @@ pub struct Config {
     pub minimizer: bool,
     pub proptesting: bool,
     pub iter: i64,
+    #[serde(default)]
+    pub schedule: Schedule,
+    #[serde(default)]
+    pub max_steps: u64,
+    #[serde(default)]
+    pub maximize_steps: bool,
+    #[serde(default)]
+    pub diff_dump: String,
+    #[serde(default)]
+    pub engine: Engine,
+    #[serde(default)]
+    pub adapt_inputs: bool,
+    #[serde(default)]
+    pub state_dump: String,
+    #[serde(default)]
+    pub address_map: Vec<String>,
+    #[serde(default)]
+    pub coverage_report: String,
+    #[serde(default)]
+    pub tui: bool,
+    #[serde(default)]
+    pub stats_format: StatsFormat,
+    #[serde(default)]
+    pub metrics: String,
 }
 
 impl Config {
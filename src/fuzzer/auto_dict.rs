@@ -0,0 +1,89 @@
+use std::collections::HashSet;
+
+use cairo_lang_sierra::program::GenericArg;
+use cairo_lang_starknet::casm_contract_class::CasmContractClass;
+use cairo_lang_starknet::contract_class::ContractClass;
+use felt::Felt252;
+use num_traits::Zero;
+
+use super::cmplog::casm_immediates;
+use super::dict::{Dict, DictEntry, DictScope};
+
+/// Builds a dictionary from the constants of a contract
+///
+/// The values come from the Sierra program (`felt252_const`, `u128_const`,
+/// `storage_base_address_const`... libfuncs), the entry point selectors and the
+/// immediate operands of the CASM bytecode along with their negation, since `x == C`
+/// is compiled into an addition of `-C`.
+pub fn extract_dict(contract_content: &str, casm_contract_class: &CasmContractClass) -> Dict {
+    let mut values = Vec::new();
+
+    match serde_json::from_str::<ContractClass>(contract_content) {
+        Ok(contract_class) => {
+            // Constants used as generic arguments of the libfuncs
+            match contract_class.extract_sierra_program() {
+                Ok(program) => {
+                    for libfunc in &program.libfunc_declarations {
+                        for arg in &libfunc.long_id.generic_args {
+                            if let GenericArg::Value(value) = arg {
+                                values.push(Felt252::from(value));
+                            }
+                        }
+                    }
+                }
+                Err(e) => eprintln!("Warning: Could not extract Sierra program: {:?}", e),
+            }
+
+            // Selectors of the entry points
+            let entry_points = &contract_class.entry_points_by_type;
+            for entry_point in entry_points
+                .external
+                .iter()
+                .chain(&entry_points.l1_handler)
+                .chain(&entry_points.constructor)
+            {
+                values.push(Felt252::from(&entry_point.selector));
+            }
+        }
+        Err(e) => eprintln!("Warning: Could not parse Sierra contract class: {}", e),
+    }
+
+    // Immediate operands of the bytecode, sorted for a deterministic dictionary
+    let mut immediates: Vec<(usize, Felt252)> =
+        casm_immediates(casm_contract_class).into_iter().collect();
+    immediates.sort_by_key(|(offset, _)| *offset);
+    for (_, immediate) in immediates {
+        values.push(-immediate.clone());
+        values.push(immediate);
+    }
+
+    // Keep the first occurrence of each value
+    let mut seen = HashSet::new();
+    let entries = values
+        .into_iter()
+        .filter(|value| !value.is_zero() && seen.insert(value.clone()))
+        .map(|value| DictEntry {
+            value,
+            scope: DictScope::Global,
+        })
+        .collect();
+    Dict { entries }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_dict() {
+        let contract = std::fs::read_to_string("tests1.0/fuzzinglabs.json").unwrap();
+        let casm = std::fs::read_to_string("tests1.0/fuzzinglabs.casm").unwrap();
+        let casm_contract_class: CasmContractClass = serde_json::from_str(&casm).unwrap();
+        let dict = extract_dict(&contract, &casm_contract_class);
+        // `f == 'f'` in Fuzz_symbolic_execution
+        assert!(dict
+            .entries
+            .iter()
+            .any(|entry| entry.value == Felt252::from(b'f')));
+    }
+}
@@ -0,0 +1,424 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use cairo_lang_starknet::casm_contract_class::CasmContractClass;
+use felt::Felt252;
+
+use crate::custom_rand::rng::Rng;
+use crate::json::json_parser::{try_get_function_from_json, Function};
+use crate::mutator::strategy::MutationStrategy;
+use crate::runner::runner::{Felt252Field, Runner};
+use crate::runner::starknet_runner::RunnerStarknet;
+use crate::runner::state_dump::StateDump;
+
+use super::auto_dict::extract_dict;
+use super::cmplog::CmpLog;
+use super::corpus_store::CorpusStore;
+use super::dict::Dict;
+use super::executor::Executor;
+pub use super::executor::Step;
+use super::resources::ResourceLimits;
+use super::schedule::Schedule;
+use super::stats::{FuzzInput, Statistics};
+
+/// Limits of a `Campaign::run` call, the run stops at the first limit reached
+/// A budget without any limit executes nothing
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Budget {
+    /// Maximum number of executions
+    pub iterations: Option<u64>,
+    /// Maximum duration
+    pub duration: Option<Duration>,
+}
+
+impl Budget {
+    /// Budget of `iterations` executions
+    pub fn iterations(iterations: u64) -> Self {
+        Budget {
+            iterations: Some(iterations),
+            duration: None,
+        }
+    }
+
+    /// Budget of `duration` of fuzzing
+    pub fn duration(duration: Duration) -> Self {
+        Budget {
+            iterations: None,
+            duration: Some(duration),
+        }
+    }
+
+    fn exhausted(&self, executions: u64, elapsed: Duration) -> bool {
+        match (self.iterations, self.duration) {
+            (None, None) => true,
+            (iterations, duration) => {
+                iterations.map_or(false, |max| executions >= max)
+                    || duration.map_or(false, |max| elapsed >= max)
+            }
+        }
+    }
+}
+
+/// Findings and statistics of a campaign, since its creation
+/// The inputs are sorted so the results are deterministic for a given seed
+#[derive(Debug, Clone, Default)]
+pub struct CampaignResult {
+    /// Number of executions
+    pub executions: u64,
+    /// Time spent fuzzing
+    pub elapsed: Duration,
+    /// Number of distinct execution paths
+    pub coverage: usize,
+    /// Inputs of the corpus
+    pub corpus: Vec<FuzzInput>,
+    /// Inputs making the function panic
+    pub crashes: Vec<FuzzInput>,
+    /// Inputs the runner could not execute
+    pub tx_crashes: Vec<FuzzInput>,
+    /// Inputs exceeding the steps threshold
+    pub exhaustions: Vec<FuzzInput>,
+    /// Highest number of steps executed by an input
+    pub max_steps: u64,
+    /// Errors of the corpus store, the campaign goes on without saving the entry
+    pub errors: Vec<String>,
+}
+
+/// Callback called with the executions reporting an event
+pub type StepCallback = Box<dyn FnMut(&Step)>;
+
+/// A fuzzing campaign driven from Rust
+///
+/// Unlike `Fuzzer`, the campaign is built from in-memory artifacts and runs in the
+/// calling thread: it never prints or exits the process, and only writes the new
+/// corpus entries to the store given to `corpus_store`. The inputs are executed by the
+/// same `Executor` as the workers of `Fuzzer`.
+///
+/// The campaign runs on the starknet runner by default, `Campaign::with_runner` fuzzes
+/// the function on any runner of cairo-vm felts.
+///
+/// ```ignore
+/// let mut campaign = Campaign::new(&contract, &casm, "Fuzz_symbolic_execution")?
+///     .seed(1000)
+///     .on_crash(|step| println!("crash: {:?}", step.input));
+/// let result = campaign.run(Budget::iterations(10_000));
+/// ```
+pub struct Campaign<R = RunnerStarknet>
+where
+    R: Runner<Felt = Felt252, Field = Felt252Field> + Clone,
+{
+    /// Contract JSON content and contract_class for starknet-rs, unset for a campaign
+    /// created with `with_runner`
+    contract: Option<(String, CasmContractClass)>,
+    /// Contract function to fuzz
+    function: Function,
+    /// Seed of the mutator and of the corpus selection
+    seed: u64,
+    /// Mutates, executes and triages the inputs
+    executor: Executor<R>,
+    /// Dictionnary, its values are added to the corpus when the campaign starts
+    dict: Dict,
+    /// Corpus, findings and counters of the campaign
+    stats: Statistics,
+    /// Binary corpus the new inputs are appended to, if any
+    corpus_store: Option<CorpusStore>,
+    /// Errors of the corpus store
+    errors: Vec<String>,
+    on_new_coverage: Vec<StepCallback>,
+    on_crash: Vec<StepCallback>,
+    /// Time spent in `step`
+    elapsed: Duration,
+    started: bool,
+}
+
+impl Campaign<RunnerStarknet> {
+    /// Creates a campaign fuzzing `function_name`
+    /// `contract_content` is the Sierra contract class JSON and `casm_content` the compiled CASM JSON
+    pub fn new(
+        contract_content: &str,
+        casm_content: &str,
+        function_name: &str,
+    ) -> Result<Self, String> {
+        let function = try_get_function_from_json(contract_content, function_name)?;
+        let contract_class: CasmContractClass = serde_json::from_str(casm_content)
+            .map_err(|e| format!("Could not parse the casm contract: {}", e))?;
+
+        let runner = RunnerStarknet::new(&contract_class, function.selector_idx);
+        let cmplog = CmpLog::new(&contract_class, function.selector_idx);
+        let mut campaign = Campaign::with_runner(runner, function);
+        campaign.executor = campaign.executor.cmplog(cmplog);
+        campaign.contract = Some((contract_content.to_string(), contract_class));
+        Ok(campaign)
+    }
+
+    /// Adds the constants of the contract to the dictionnary
+    pub fn auto_dict(self) -> Self {
+        let auto_dict = match &self.contract {
+            Some((contract_content, contract_class)) => {
+                extract_dict(contract_content, contract_class)
+            }
+            None => return self,
+        };
+        self.dict(auto_dict)
+    }
+
+    /// Loads the contracts of `state` into the state the function is executed on
+    pub fn state(mut self, state: StateDump) -> Self {
+        if let Some((_, contract_class)) = &self.contract {
+            let runner =
+                RunnerStarknet::new_with_state(contract_class, self.function.selector_idx, &state);
+            self.executor = self.executor.runner(runner);
+        }
+        self
+    }
+}
+
+impl<R> Campaign<R>
+where
+    R: Runner<Felt = Felt252, Field = Felt252Field> + Clone,
+{
+    /// Creates a campaign fuzzing `function` on `runner`
+    /// The runner is given one felt per argument of the function
+    pub fn with_runner(runner: R, function: Function) -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Failed to get actual time")
+            .as_millis() as u64;
+        let executor = Executor::new(runner, function.clone(), seed);
+        Campaign {
+            contract: None,
+            function,
+            seed,
+            executor,
+            dict: Dict::default(),
+            stats: Statistics::default(),
+            corpus_store: None,
+            errors: Vec::new(),
+            on_new_coverage: Vec::new(),
+            on_crash: Vec::new(),
+            elapsed: Duration::ZERO,
+            started: false,
+        }
+    }
+
+    /// Sets the seed, the campaign is reproducible for a given seed and budget in iterations
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self.executor = self.executor.seed(seed);
+        self
+    }
+
+    /// Adds the entries of `dict` to the dictionnary used by the mutator
+    pub fn dict(mut self, dict: Dict) -> Self {
+        self.dict.entries.extend(dict.entries);
+        self.executor = self.executor.dict(self.dict.clone());
+        self
+    }
+
+    /// Adds inputs to the corpus, the inputs without one felt per argument are ignored
+    pub fn inputs(mut self, inputs: Vec<FuzzInput>) -> Self {
+        for input in inputs {
+            self.add_input(input);
+        }
+        self
+    }
+
+    /// Registers a custom mutation strategy
+    pub fn strategy(mut self, strategy: Box<dyn MutationStrategy<Felt252Field>>) -> Self {
+        self.executor = self.executor.strategy(strategy);
+        self
+    }
+
+    /// Sets the resource exhaustion detection settings
+    pub fn limits(mut self, limits: ResourceLimits) -> Self {
+        self.executor = self.executor.limits(limits);
+        self
+    }
+
+    /// Sets the power schedule of the corpus entries
+    pub fn schedule(mut self, schedule: Schedule) -> Self {
+        self.executor = self.executor.schedule(schedule);
+        self
+    }
+
+    /// Appends the inputs reaching new coverage to `corpus_store`
+    pub fn corpus_store(mut self, corpus_store: CorpusStore) -> Self {
+        self.corpus_store = Some(corpus_store);
+        self
+    }
+
+    /// Registers a callback called with every input reaching new coverage
+    pub fn on_new_coverage<F: FnMut(&Step) + 'static>(mut self, callback: F) -> Self {
+        self.on_new_coverage.push(Box::new(callback));
+        self
+    }
+
+    /// Registers a callback called with every new crashing input, whether the function
+    /// panicked or the runner could not execute it
+    pub fn on_crash<F: FnMut(&Step) + 'static>(mut self, callback: F) -> Self {
+        self.on_crash.push(Box::new(callback));
+        self
+    }
+
+    /// Function fuzzed by the campaign
+    pub fn function(&self) -> &Function {
+        &self.function
+    }
+
+    /// Mutates an input of the corpus and executes it
+    pub fn step(&mut self) -> Step {
+        let start = Instant::now();
+        if !self.started {
+            self.started = true;
+            // Seed the corpus with the dictionary values
+            let dict_inputs = self
+                .dict
+                .seed_inputs(&self.function.inputs, &Rng::seeded(self.seed));
+            for input in dict_inputs {
+                self.add_input(input);
+            }
+        }
+
+        let step = self.executor.step(&mut self.stats);
+        if let (Some(corpus_store), Some(entry)) = (&mut self.corpus_store, &step.entry) {
+            if let Err(e) = corpus_store.append(entry) {
+                self.errors.push(e);
+            }
+        }
+        self.elapsed += start.elapsed();
+
+        if step.new_coverage {
+            for callback in &mut self.on_new_coverage {
+                callback(&step);
+            }
+        }
+        if step.new_crash {
+            for callback in &mut self.on_crash {
+                callback(&step);
+            }
+        }
+        step
+    }
+
+    /// Fuzzes until the budget is exhausted and returns the results of the campaign
+    pub fn run(&mut self, budget: Budget) -> CampaignResult {
+        let executions = self.stats.fuzz_cases;
+        let elapsed = self.elapsed;
+        while !budget.exhausted(self.stats.fuzz_cases - executions, self.elapsed - elapsed) {
+            self.step();
+        }
+        self.result()
+    }
+
+    /// Results of the campaign since its creation
+    pub fn result(&self) -> CampaignResult {
+        fn sorted<'a>(inputs: impl Iterator<Item = &'a FuzzInput>) -> Vec<FuzzInput> {
+            let mut inputs: Vec<FuzzInput> = inputs.cloned().collect();
+            inputs.sort();
+            inputs
+        }
+        CampaignResult {
+            executions: self.stats.fuzz_cases,
+            elapsed: self.elapsed,
+            coverage: self.stats.corpus.coverage(),
+            corpus: sorted(self.stats.corpus.inputs().iter()),
+            crashes: sorted(self.stats.crash_db.iter()),
+            tx_crashes: sorted(self.stats.tx_crash_db.iter()),
+            exhaustions: sorted(self.stats.exhaustion_db.iter()),
+            max_steps: self.stats.max_steps,
+            errors: self.errors.clone(),
+        }
+    }
+
+    fn add_input(&mut self, input: FuzzInput) {
+        if input.len() == self.function.inputs.len() {
+            self.stats.corpus.add_input(&input);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::runner::runner::ExecutionResult;
+
+    #[test]
+    fn test_campaign() {
+        let contract = std::fs::read_to_string("tests1.0/fuzzinglabs.json").unwrap();
+        let casm = std::fs::read_to_string("tests1.0/fuzzinglabs.casm").unwrap();
+        assert!(Campaign::new(&contract, &casm, "not_a_function").is_err());
+
+        let new_coverage = Rc::new(Cell::new(0));
+        let counter = new_coverage.clone();
+        let mut campaign = Campaign::new(&contract, &casm, "Fuzz_symbolic_execution")
+            .expect("Could not create the campaign")
+            .seed(1000)
+            .on_new_coverage(move |_| counter.set(counter.get() + 1));
+        let result = campaign.run(Budget::iterations(200));
+        assert_eq!(result.executions, 200);
+        assert!(!result.corpus.is_empty());
+        assert_eq!(new_coverage.get(), result.corpus.len());
+        // The budget applies to each run
+        let result = campaign.run(Budget::iterations(100));
+        assert_eq!(result.executions, 300);
+
+        // The power schedules and the corpus store are shared with the workers
+        let path = std::env::temp_dir().join(format!("campaign_{}.corpus", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let path = path.to_string_lossy().to_string();
+        let function = campaign.function().clone();
+        let mut campaign = Campaign::new(&contract, &casm, "Fuzz_symbolic_execution")
+            .expect("Could not create the campaign")
+            .seed(1000)
+            .schedule(Schedule::Rare)
+            .corpus_store(CorpusStore::new(&path, &function.name, &function.inputs));
+        let result = campaign.run(Budget::iterations(200));
+        assert!(result.errors.is_empty());
+        let (_, _, entries) = CorpusStore::load(&path).expect("Could not load the corpus");
+        assert_eq!(entries.len(), result.corpus.len());
+        std::fs::remove_file(&path).unwrap();
+
+        // The fuzz loop is generic over the runner
+        #[derive(Clone)]
+        struct PanicOnSeven;
+        impl Runner for PanicOnSeven {
+            type Felt = Felt252;
+            type Field = Felt252Field;
+
+            fn run(self, data: &[Felt252]) -> Result<(Self, ExecutionResult<Felt252>), String> {
+                let result = ExecutionResult {
+                    failure_flag: data[0] == Felt252::from(7),
+                    coverage: vec![(data[0].to_be_bytes()[31] % 16) as u64],
+                    ..Default::default()
+                };
+                Ok((self, result))
+            }
+        }
+        let mut campaign = Campaign::with_runner(PanicOnSeven, function).seed(1000);
+        let result = campaign.run(Budget::iterations(500));
+        assert_eq!(result.executions, 500);
+        assert_eq!(result.coverage, result.corpus.len());
+        assert!(result
+            .crashes
+            .iter()
+            .all(|input| input[0] == Felt252::from(7)));
+    }
+
+    #[test]
+    fn test_campaign_malformed_contract() {
+        let casm = std::fs::read_to_string("tests1.0/fuzzinglabs.casm").unwrap();
+        let contract = std::fs::read_to_string("tests1.0/fuzzinglabs.json").unwrap();
+        let mut contract: serde_json::Value = serde_json::from_str(&contract).unwrap();
+
+        // A malformed artifact is an error, not a panic of the host process
+        let function = "Fuzz_symbolic_execution";
+        contract["abi"][0].as_object_mut().unwrap().remove("inputs");
+        let malformed_abi = contract.to_string();
+        contract.as_object_mut().unwrap().remove("abi");
+        let no_abi = contract.to_string();
+        for contract in ["{", no_abi.as_str(), malformed_abi.as_str()] {
+            assert!(Campaign::new(contract, &casm, function).is_err());
+        }
+    }
+}
@@ -0,0 +1,486 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use cairo_fuzzer_core::executor::CmpFeedback;
+use cairo_lang_starknet::casm_contract_class::CasmContractClass;
+use felt::Felt252;
+use num_traits::{ToPrimitive, Zero};
+
+/// Offset of the flags in an encoded CASM instruction
+const FLAGS_OFFSET: u64 = 48;
+/// Flag set when the second operand of an instruction is an immediate value
+const OP1_IMM_FLAG: u64 = 1 << 2;
+/// Number of recent operands remembered to not return them twice, the oldest are forgotten
+const MAX_CMP_VALUES: usize = 1024;
+/// Encoding of `ret`, the footer appended to the bytecode by the runner to get the builtin costs
+const RET_INSTRUCTION: u64 = 0x208b7fff7fff7ffe;
+
+/// Opcodes of the CASM instructions
+const OPCODE_CALL: u64 = 1;
+const OPCODE_RET: u64 = 2;
+const OPCODE_ASSERT_EQ: u64 = 4;
+/// Sources of the second operand
+const OP1_FP: u64 = 2;
+const OP1_AP: u64 = 4;
+/// Result logics
+const RES_OP1: u64 = 0;
+const RES_ADD: u64 = 1;
+const RES_MUL: u64 = 2;
+/// Conditional jump pc update
+const PC_JNZ: u64 = 4;
+/// Ap updates
+const AP_ADD_RES: u64 = 1;
+const AP_ADD1: u64 = 2;
+
+/// Memory segments tracked by the replay
+const EXECUTION_SEGMENT: usize = 0;
+const CALLDATA_SEGMENT: usize = 1;
+/// Offset of the initial frame in the execution segment, leaves room for the entry point arguments
+const INITIAL_FRAME: i64 = 16;
+
+/// Comparison operands logging (CmpLog)
+///
+/// Cairo compiles `x == y` into `[ap] = x + (-y)`, or `x = [ap] + y`, followed by a `jnz`
+/// on the difference. The execution is replayed from the pcs of its trace and the calldata to
+/// recover the memory cells of these additions, and the runtime values of both operands of the
+/// comparisons are fed to the mutator, which splices them into the inputs.
+pub struct CmpLog {
+    /// Decoded instructions, by bytecode offset
+    instructions: HashMap<usize, Instruction>,
+    /// Bytecode offset of the fuzzed entry point
+    entry_offset: usize,
+    /// Length of the bytecode, the runner appends a `ret` footer at this offset
+    bytecode_len: usize,
+    /// Recent values returned by `operands`
+    seen: HashSet<Felt252>,
+    /// Order of insertion of the `seen` values
+    seen_order: VecDeque<Felt252>,
+}
+
+impl CmpLog {
+    /// Decodes the bytecode of the contract
+    pub fn new(contract_class: &CasmContractClass, selector_idx: usize) -> Self {
+        let entry_offset = contract_class
+            .entry_points_by_type
+            .external
+            .get(selector_idx)
+            .expect("Could not get entry point")
+            .offset;
+
+        let mut instructions = HashMap::new();
+        let mut offset = 0;
+        while offset < contract_class.bytecode.len() {
+            let word = contract_class.bytecode[offset].value.to_u64();
+            let immediate = contract_class
+                .bytecode
+                .get(offset + 1)
+                .map(|immediate| Felt252::new(immediate.value.clone()));
+            match word {
+                Some(word) => {
+                    let instruction = Instruction::decode(word, immediate);
+                    let size = instruction.size();
+                    instructions.insert(offset, instruction);
+                    offset += size;
+                }
+                None => offset += 1,
+            }
+        }
+
+        CmpLog {
+            instructions,
+            entry_offset,
+            bytecode_len: contract_class.bytecode.len(),
+            seen: HashSet::new(),
+            seen_order: VecDeque::new(),
+        }
+    }
+
+    /// Returns the new comparison operands of an execution, given the pcs of its trace and
+    /// its calldata
+    pub fn operands(&mut self, pcs: &[usize], calldata: &[Felt252]) -> Vec<Felt252> {
+        // The first pc of the trace is the entry point, it gives the base of the bytecode
+        let base = match pcs.first() {
+            Some(pc) => pc.wrapping_sub(self.entry_offset),
+            None => return Vec::new(),
+        };
+
+        let footer = Instruction::decode(RET_INSTRUCTION, None);
+        let mut replay = Replay::new(calldata);
+        for pc in pcs {
+            let offset = pc.wrapping_sub(base);
+            let instruction = match self.instructions.get(&offset) {
+                Some(instruction) => instruction,
+                None if offset == self.bytecode_len => &footer,
+                None => break,
+            };
+            // The replay stops when the frame cannot be followed anymore
+            if !replay.step(instruction) {
+                break;
+            }
+        }
+
+        let mut operands = Vec::new();
+        for (lhs, rhs) in replay.comparisons {
+            for value in [lhs, rhs] {
+                if value.is_zero() || self.seen.contains(&value) {
+                    continue;
+                }
+                // Forget the oldest operand to make room for the new one
+                if self.seen_order.len() >= MAX_CMP_VALUES {
+                    if let Some(oldest) = self.seen_order.pop_front() {
+                        self.seen.remove(&oldest);
+                    }
+                }
+                self.seen.insert(value.clone());
+                self.seen_order.push_back(value.clone());
+                operands.push(value);
+            }
+        }
+        operands
+    }
+}
+
+/// Feeds the comparison operands to the mutator of the shared executor, the coverage
+/// of a cairo-vm execution is the pcs of its trace
+impl CmpFeedback<Felt252> for CmpLog {
+    fn operands(&mut self, coverage: &[u64], input: &[Felt252]) -> Vec<Felt252> {
+        let pcs: Vec<usize> = coverage.iter().map(|pc| *pc as usize).collect();
+        CmpLog::operands(self, &pcs, input)
+    }
+}
+
+/// Decoded CASM instruction
+#[derive(Debug, Clone)]
+struct Instruction {
+    off0: i64,
+    off1: i64,
+    off2: i64,
+    dst_fp: bool,
+    op0_fp: bool,
+    op1_src: u64,
+    res_logic: u64,
+    pc_update: u64,
+    ap_update: u64,
+    opcode: u64,
+    /// Immediate operand, if `op1_src` is the immediate
+    immediate: Option<Felt252>,
+}
+
+impl Instruction {
+    /// Decodes an instruction given its encoding and the following bytecode word
+    fn decode(word: u64, immediate: Option<Felt252>) -> Self {
+        let offset = |shift: u64| ((word >> shift) & 0xffff) as i64 - (1 << 15);
+        let flags = word >> FLAGS_OFFSET;
+        Instruction {
+            off0: offset(0),
+            off1: offset(16),
+            off2: offset(32),
+            dst_fp: flags & 1 != 0,
+            op0_fp: flags & 2 != 0,
+            op1_src: (flags >> 2) & 7,
+            res_logic: (flags >> 5) & 3,
+            pc_update: (flags >> 7) & 7,
+            ap_update: (flags >> 10) & 3,
+            opcode: (flags >> 12) & 7,
+            immediate: match flags & OP1_IMM_FLAG != 0 {
+                true => immediate,
+                false => None,
+            },
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self.op1_src & 1 != 0 {
+            true => 2,
+            false => 1,
+        }
+    }
+}
+
+/// Address of a memory cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Address {
+    segment: usize,
+    offset: i64,
+}
+
+impl Address {
+    fn add(self, offset: i64) -> Self {
+        Address {
+            segment: self.segment,
+            offset: self.offset + offset,
+        }
+    }
+}
+
+/// Value of a memory cell
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Felt(Felt252),
+    Ptr(Address),
+}
+
+impl Value {
+    fn felt(&self) -> Option<&Felt252> {
+        match self {
+            Value::Felt(felt) => Some(felt),
+            Value::Ptr(_) => None,
+        }
+    }
+}
+
+/// Replay of an execution, follows the registers and the memory cells derived from the calldata
+///
+/// The cells written by hints are unknown until an instruction asserts them.
+struct Replay {
+    memory: HashMap<Address, Value>,
+    ap: Address,
+    fp: Address,
+    /// Frame pointers of the callers
+    frames: Vec<Address>,
+    /// Operands of the additions asserted into each cell, as the two values compared when a
+    /// `jnz` tests the cell
+    sums: HashMap<Address, (Felt252, Felt252)>,
+    /// Operands of the comparisons, in order of execution
+    comparisons: Vec<(Felt252, Felt252)>,
+}
+
+impl Replay {
+    /// Sets up the frame of the entry point, the calldata array is given by its start and end
+    /// pointers at `[fp - 4]` and `[fp - 3]`
+    fn new(calldata: &[Felt252]) -> Self {
+        let fp = Address {
+            segment: EXECUTION_SEGMENT,
+            offset: INITIAL_FRAME,
+        };
+        let calldata_start = Address {
+            segment: CALLDATA_SEGMENT,
+            offset: 0,
+        };
+        let mut memory: HashMap<Address, Value> = calldata
+            .iter()
+            .enumerate()
+            .map(|(i, value)| (calldata_start.add(i as i64), Value::Felt(value.clone())))
+            .collect();
+        memory.insert(fp.add(-4), Value::Ptr(calldata_start));
+        memory.insert(
+            fp.add(-3),
+            Value::Ptr(calldata_start.add(calldata.len() as i64)),
+        );
+        Replay {
+            memory,
+            ap: fp,
+            fp,
+            frames: Vec::new(),
+            sums: HashMap::new(),
+            comparisons: Vec::new(),
+        }
+    }
+
+    /// Executes an instruction, returns false if the registers cannot be followed anymore
+    fn step(&mut self, instruction: &Instruction) -> bool {
+        let register = |fp: bool| if fp { self.fp } else { self.ap };
+        let dst_addr = register(instruction.dst_fp).add(instruction.off0);
+        let op0_addr = register(instruction.op0_fp).add(instruction.off1);
+        let op1_addr = match instruction.op1_src {
+            OP1_FP => Some(self.fp.add(instruction.off2)),
+            OP1_AP => Some(self.ap.add(instruction.off2)),
+            0 => match self.memory.get(&op0_addr) {
+                Some(Value::Ptr(address)) => Some(address.add(instruction.off2)),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        match instruction.opcode {
+            OPCODE_CALL => {
+                self.frames.push(self.fp);
+                self.ap = self.ap.add(2);
+                self.fp = self.ap;
+                return true;
+            }
+            OPCODE_RET => {
+                return match self.frames.pop() {
+                    Some(fp) => {
+                        self.fp = fp;
+                        true
+                    }
+                    None => false,
+                };
+            }
+            _ => {}
+        }
+
+        if instruction.pc_update == PC_JNZ {
+            if let Some(comparison) = self.sums.get(&dst_addr) {
+                self.comparisons.push(comparison.clone());
+            }
+            return self.update_ap(instruction, None);
+        }
+
+        let op0 = self.memory.get(&op0_addr).cloned();
+        let op1 = match &instruction.immediate {
+            Some(immediate) => Some(Value::Felt(immediate.clone())),
+            None => op1_addr.and_then(|address| self.memory.get(&address).cloned()),
+        };
+        let mut res = match (instruction.res_logic, &op0, &op1) {
+            (RES_OP1, _, op1) => op1.clone(),
+            (RES_ADD, Some(Value::Felt(a)), Some(Value::Felt(b))) => {
+                Some(Value::Felt(a.clone() + b.clone()))
+            }
+            (RES_ADD, Some(Value::Ptr(a)), Some(Value::Felt(b))) => {
+                to_offset(b).map(|b| Value::Ptr(a.add(b)))
+            }
+            (RES_MUL, Some(Value::Felt(a)), Some(Value::Felt(b))) => {
+                Some(Value::Felt(a.clone() * b.clone()))
+            }
+            _ => None,
+        };
+
+        if instruction.opcode == OPCODE_ASSERT_EQ {
+            let dst = self.memory.get(&dst_addr).cloned();
+            match (&dst, &res) {
+                (None, Some(res)) => {
+                    self.memory.insert(dst_addr, res.clone());
+                }
+                // Deduce the unknown operand from the asserted value
+                (Some(dst), None) => match instruction.res_logic {
+                    RES_OP1 => {
+                        if let Some(address) = op1_addr.filter(|_| op1.is_none()) {
+                            self.memory.insert(address, dst.clone());
+                        }
+                    }
+                    RES_ADD => {
+                        let dst = dst.felt();
+                        match (
+                            dst,
+                            op0.as_ref().and_then(Value::felt),
+                            op1.as_ref().and_then(Value::felt),
+                        ) {
+                            (Some(dst), None, Some(op1)) if op0.is_none() => {
+                                let op0 = dst.clone() - op1.clone();
+                                self.memory.insert(op0_addr, Value::Felt(op0));
+                            }
+                            (Some(dst), Some(op0), None) if op1.is_none() => {
+                                if let Some(address) = op1_addr {
+                                    let op1 = dst.clone() - op0.clone();
+                                    self.memory.insert(address, Value::Felt(op1));
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+            res = self.memory.get(&dst_addr).cloned();
+
+            // Keep the operands of the additions, the comparisons are the `jnz` on one of them
+            if instruction.res_logic == RES_ADD {
+                let get = |address: Option<Address>| {
+                    address.and_then(|address| match self.memory.get(&address) {
+                        Some(Value::Felt(felt)) => Some(felt.clone()),
+                        _ => None,
+                    })
+                };
+                let op1_value = match &instruction.immediate {
+                    Some(immediate) => Some(immediate.clone()),
+                    None => get(op1_addr),
+                };
+                if let (Some(dst), Some(op0), Some(op1)) =
+                    (get(Some(dst_addr)), get(Some(op0_addr)), op1_value)
+                {
+                    // `dst = op0 + op1` is zero when `op0 == -op1`
+                    self.sums.insert(dst_addr, (op0.clone(), -op1.clone()));
+                    // `op0 = dst - op1` is zero when `dst == op1`
+                    self.sums.insert(op0_addr, (dst.clone(), op1));
+                    // `op1 = dst - op0` is zero when `dst == op0`
+                    if let Some(address) = op1_addr {
+                        self.sums.insert(address, (dst, op0));
+                    }
+                }
+            }
+        }
+        self.update_ap(instruction, res)
+    }
+
+    fn update_ap(&mut self, instruction: &Instruction, res: Option<Value>) -> bool {
+        match instruction.ap_update {
+            AP_ADD_RES => match res.as_ref().and_then(Value::felt).and_then(to_offset) {
+                Some(offset) => self.ap = self.ap.add(offset),
+                None => return false,
+            },
+            AP_ADD1 => self.ap = self.ap.add(1),
+            _ => {}
+        }
+        true
+    }
+}
+
+/// Converts a felt to a signed offset, the negative offsets are close to P
+fn to_offset(value: &Felt252) -> Option<i64> {
+    match value.to_u64() {
+        Some(offset) => offset.to_i64(),
+        None => (-value.clone()).to_u64()?.to_i64().map(|offset| -offset),
+    }
+}
+
+/// Decodes the bytecode of a contract and returns the immediate operands by bytecode offset
+pub fn casm_immediates(contract_class: &CasmContractClass) -> HashMap<usize, Felt252> {
+    let mut immediates = HashMap::new();
+    let mut offset = 0;
+    while offset < contract_class.bytecode.len() {
+        let has_immediate = contract_class.bytecode[offset]
+            .value
+            .to_u64()
+            .map_or(false, |word| (word >> FLAGS_OFFSET) & OP1_IMM_FLAG != 0);
+        if has_immediate {
+            if let Some(immediate) = contract_class.bytecode.get(offset + 1) {
+                immediates.insert(offset, Felt252::new(immediate.value.clone()));
+            }
+            offset += 2;
+        } else {
+            offset += 1;
+        }
+    }
+    immediates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::runner::Runner;
+    use crate::runner::starknet_runner::RunnerStarknet;
+
+    #[test]
+    fn test_cmplog_operands() {
+        let casm_content =
+            std::fs::read_to_string("tests1.0/fuzzinglabs.casm").expect("Could not read casm file");
+        let contract_class: CasmContractClass =
+            serde_json::from_str(&casm_content).expect("could not get contractclass");
+        let mut cmplog = CmpLog::new(&contract_class, 0);
+
+        // f == 'f' passes and u == 'u' is compared with 5
+        let mut input = vec![Felt252::from(0); 11];
+        input[0] = Felt252::from(b'f');
+        input[1] = Felt252::from(5);
+        let runner = RunnerStarknet::new(&contract_class, 0);
+        let (_, result) = runner.run(&input).expect("Could not run the contract");
+        let pcs: Vec<usize> = result.coverage.iter().map(|pc| *pc as usize).collect();
+        let operands = cmplog.operands(&pcs, &input);
+        assert!(operands.contains(&Felt252::from(b'u')));
+        assert!(operands.contains(&Felt252::from(5)));
+        // Operands are only returned once
+        assert!(cmplog.operands(&pcs, &input).is_empty());
+
+        // The runtime value of the input is read, not only the constants
+        input[1] = Felt252::from(b'u');
+        input[2] = Felt252::from(7);
+        let runner = RunnerStarknet::new(&contract_class, 0);
+        let (_, result) = runner.run(&input).expect("Could not run the contract");
+        let pcs: Vec<usize> = result.coverage.iter().map(|pc| *pc as usize).collect();
+        let operands = cmplog.operands(&pcs, &input);
+        assert!(operands.contains(&Felt252::from(b'z')));
+        assert!(operands.contains(&Felt252::from(7)));
+    }
+}
@@ -9,7 +9,6 @@ use std::fs;
 use std::fs::create_dir;
 use std::fs::write;
 use std::path::Path;
-use std::process;
 use std::time::SystemTime;
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -24,6 +23,11 @@ pub struct CrashFile {
 impl CrashFile {
     /// Init a new CrashFile using the Function object
     pub fn new_from_function(function: &Function, workspace: &String) -> Self {
+        Self::new_with_prefix(function, workspace, "CRASHES")
+    }
+
+    /// Init a new CrashFile whose file name starts with `prefix`
+    pub fn new_with_prefix(function: &Function, workspace: &String, prefix: &str) -> Self {
         let d = SystemTime::now();
         // Create DateTime from SystemTime
         let datetime = DateTime::<Utc>::from(d);
@@ -32,9 +36,10 @@ impl CrashFile {
         CrashFile {
             workspace: workspace.to_string(),
             path: format!(
-                "{}/{}/CRASHES_{}_{}.json",
+                "{}/{}/{}_{}_{}.json",
                 workspace.to_string(),
                 function.name,
+                prefix,
                 function.name,
                 timestamp_str
             ),
@@ -45,54 +50,25 @@ impl CrashFile {
     }
 
     /// Function to load a crashes corpus
-    pub fn load_from_file(filename: &String, workspace: &String) -> Self {
-        // Try to load the file
-        let contents =
-            fs::read_to_string(filename).expect("Should have been able to read the file");
-        // Extract json data
-        let data: Value = serde_json::from_str(&contents).expect("JSON was not well-formatted");
-        // Load old crashes to prevent overwriting and to use it as a dictionary for the mutator
-        let mut crashes_vec: Vec<Vec<Felt252>> = Vec::new();
-        if let Some(inputs) = data.get("crashes") {
-            if let Some(inputs_array) = inputs.as_array() {
-                for input in inputs_array {
-                    if let Some(input_array) = input.as_array() {
-                        let mut felt_vec: Vec<Felt252> = Vec::new();
-                        for element in input_array {
-                            let value: Felt252 = serde_json::from_value(element.clone())
-                                .expect("Could not get values");
-                            felt_vec.push(value);
-                        }
-                        crashes_vec.push(felt_vec.clone());
-                    }
-                }
-            }
-        }
-
-        return CrashFile {
+    pub fn load_from_file(filename: &String, workspace: &String) -> Result<Self, String> {
+        let (name, args, crashes) = load_json_crashes(Path::new(filename))?;
+        Ok(CrashFile {
             workspace: workspace.to_string(),
             path: filename.clone(),
-            name: data["name"]
-                .as_str()
-                .expect("Failed to get name from crashfile")
-                .to_string(),
-            args: data["args"]
-                .as_array()
-                .expect("Failed to get args from input file as array")
-                .iter()
-                .map(|input_array| {
-                    input_array
-                        .as_str()
-                        .expect("Failed to get input array as string")
-                        .to_string()
-                })
-                .collect(),
-            crashes: crashes_vec,
-        };
+            name: name.ok_or_else(|| format!("Crash file {} has no name", filename))?,
+            args,
+            crashes,
+        })
     }
 
     /// Load all the old corpora
-    pub fn load_from_folder(foldername: &String, workspace: &String) -> Self {
+    /// Returns `None` if the folder does not contain any crash file
+    /// The files which are not crash files are skipped, their errors are pushed to `skipped`
+    pub fn load_from_folder(
+        foldername: &String,
+        workspace: &String,
+        skipped: &mut Vec<String>,
+    ) -> Result<Option<Self>, String> {
         let folder = Path::new(&foldername);
         let function_name = foldername
             .clone()
@@ -100,80 +76,97 @@ impl CrashFile {
             .last()
             .expect("Failed to split foldername")
             .to_string();
+        let mut name: Option<String> = None;
         let mut args: Option<Vec<String>> = None;
         let mut inputs: Vec<Vec<Felt252>> = Vec::new();
         // Check if the path is a directory
         if folder.is_dir() {
             // Iterate over the entries in the directory
-            for entry in fs::read_dir(folder).expect("Failed to read directory") {
-                let entry = entry.expect("Failed to get entry");
-                let path = entry.path();
+            let entries = fs::read_dir(folder)
+                .map_err(|e| format!("Could not read {}: {}", folder.display(), e))?;
+            for entry in entries {
+                let path = entry
+                    .map_err(|e| format!("Could not read {}: {}", folder.display(), e))?
+                    .path();
+                // The resource exhaustions are saved in the same format, they are not crashes
+                let is_exhaustion = path.file_name().map_or(false, |file_name| {
+                    file_name.to_string_lossy().starts_with("EXHAUSTIONS_")
+                });
                 // Check if the entry is a file
-                if path.is_file() {
-                    // Read the file and do something with its contents
-                    let contents =
-                        fs::read_to_string(&path).expect("Failed to read string from the file");
-                    let data: Value =
-                        serde_json::from_str(&contents).expect("JSON was not well-formatted");
-                    let args_data: Vec<String> = data["args"]
-                        .as_array()
-                        .expect("Failed to get args from input file as array")
-                        .iter()
-                        .map(|input_array| {
-                            input_array
-                                .as_str()
-                                .expect("Failed to get input array as string")
-                                .to_string()
-                        })
-                        .collect();
-                    if args.is_none() {
-                        args = Some(args_data);
-                    } else {
-                        if let Some(args_to_compare) = args.clone() {
-                            if args_to_compare != args_data {
-                                println!("Uncompatible inputs files");
-                                process::exit(1);
-                            }
-                        }
+                if !path.is_file() || is_exhaustion {
+                    continue;
+                }
+                // Other files of the folder are skipped
+                let (name_data, args_data, mut crashes_vec) = match load_json_crashes(&path) {
+                    Ok(crashes) => crashes,
+                    Err(e) => {
+                        skipped.push(format!("Skipping {}", e));
+                        continue;
                     }
-                    let mut crashes_vec: Vec<Vec<Felt252>> = Vec::new();
-                    if let Some(inputs) = data.get("crashes") {
-                        if let Some(inputs_array) = inputs.as_array() {
-                            for input in inputs_array {
-                                if let Some(input_array) = input.as_array() {
-                                    let mut felt_vec: Vec<Felt252> = Vec::new();
-                                    for element in input_array {
-                                        let value: Felt252 =
-                                            serde_json::from_value(element.clone())
-                                                .expect("Could not get values");
-                                        felt_vec.push(value);
-                                    }
-                                    crashes_vec.push(felt_vec.clone());
-                                }
-                            }
-                        }
+                };
+                if let Some(args_to_compare) = &args {
+                    if *args_to_compare != args_data || name != name_data {
+                        return Err(format!(
+                            "Uncompatible crashes file {}: generated for another function",
+                            path.display()
+                        ));
                     }
-                    inputs.append(&mut crashes_vec);
+                } else {
+                    args = Some(args_data);
+                    name = name_data;
                 }
+                inputs.append(&mut crashes_vec);
             }
         }
+        let args = match args {
+            Some(args) => args,
+            None => return Ok(None),
+        };
         let d = SystemTime::now();
         // Create DateTime from SystemTime
         let datetime = DateTime::<Utc>::from(d);
         // Formats the combined date and time with the specified format string.
         let timestamp_str = datetime.format("%Y-%m-%d--%H:%M:%S").to_string();
-        let data_args = if let Some(content) = args {
-            content
-        } else {
-            Vec::new()
-        };
-        return CrashFile {
+        Ok(Some(CrashFile {
             workspace: workspace.to_string(),
             path: format!("{}_{}.json", function_name.clone(), timestamp_str),
-            name: function_name.clone(),
-            args: data_args,
+            name: name.unwrap_or(function_name),
+            args,
             crashes: inputs,
-        };
+        }))
+    }
+
+    /// Checks that the crashes were found on `function`: same name, same argument types
+    /// and one felt per argument in each crash
+    pub fn check_function(&self, function: &Function) -> Result<(), String> {
+        if self.name != function.name {
+            return Err(format!(
+                "Crash file {} was generated for function {}, not {}",
+                self.path, self.name, function.name
+            ));
+        }
+        if self.args != function.inputs {
+            return Err(format!(
+                "Crash file {} was generated for arguments {:?}, {} takes {:?}",
+                self.path, self.args, function.name, function.inputs
+            ));
+        }
+        if let Some((idx, crash)) = self
+            .crashes
+            .iter()
+            .enumerate()
+            .find(|(_, crash)| crash.len() != function.inputs.len())
+        {
+            return Err(format!(
+                "Crash {} of {} has {} felts, {} takes {} arguments",
+                idx,
+                self.path,
+                crash.len(),
+                function.name,
+                function.inputs.len()
+            ));
+        }
+        Ok(())
     }
 
     /// Function to dump the crashes corpus
@@ -195,3 +188,117 @@ impl CrashFile {
         .expect("Failed to save input to disk");
     }
 }
+
+/// Reads the function name, the argument types and the crashes of a JSON crashes file
+fn load_json_crashes(
+    path: &Path,
+) -> Result<(Option<String>, Vec<String>, Vec<Vec<Felt252>>), String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("{}: could not read the file: {}", path.display(), e))?;
+    let data: Value = serde_json::from_str(&contents)
+        .map_err(|e| format!("{}: invalid JSON: {}", path.display(), e))?;
+    let args: Vec<String> = data["args"]
+        .as_array()
+        .ok_or_else(|| format!("{}: no args array", path.display()))?
+        .iter()
+        .map(|arg| arg.as_str().map(|arg| arg.to_string()))
+        .collect::<Option<_>>()
+        .ok_or_else(|| format!("{}: the args are not strings", path.display()))?;
+    let mut crashes_vec: Vec<Vec<Felt252>> = Vec::new();
+    if let Some(inputs_array) = data.get("crashes").and_then(Value::as_array) {
+        for input in inputs_array {
+            if let Some(input_array) = input.as_array() {
+                let felt_vec = input_array
+                    .iter()
+                    .map(|element| serde_json::from_value(element.clone()))
+                    .collect::<Result<Vec<Felt252>, _>>()
+                    .map_err(|e| format!("{}: invalid crash: {}", path.display(), e))?;
+                crashes_vec.push(felt_vec);
+            }
+        }
+    }
+    let name = data["name"].as_str().map(|name| name.to_string());
+    Ok((name, args, crashes_vec))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crash_file_check_function() {
+        let function = Function {
+            name: "Fuzz_symbolic_execution".to_string(),
+            selector_idx: 0,
+            inputs: vec!["core::felt252".to_string(), "core::integer::u8".to_string()],
+            outputs: Vec::new(),
+        };
+        let workspace = "fuzzer_workspace".to_string();
+        let mut crashes = CrashFile::new_from_function(&function, &workspace);
+        crashes
+            .crashes
+            .push(vec![Felt252::from(0); function.inputs.len()]);
+        assert!(crashes.check_function(&function).is_ok());
+
+        let mut other = crashes.clone();
+        other.name = "Fuzz_one".to_string();
+        assert!(other.check_function(&function).is_err());
+        let mut other = crashes.clone();
+        other.args.push("felt".to_string());
+        assert!(other.check_function(&function).is_err());
+        let mut other = crashes.clone();
+        other.crashes.push(vec![Felt252::from(0)]);
+        assert!(other.check_function(&function).is_err());
+    }
+
+    #[test]
+    fn test_load_from_folder() {
+        let folder = std::env::temp_dir().join("cairo_fuzzer_test_crash_folder");
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        let foldername = folder.to_string_lossy().to_string();
+        let workspace = "fuzzer_workspace".to_string();
+        let mut skipped = Vec::new();
+        assert!(
+            CrashFile::load_from_folder(&foldername, &workspace, &mut skipped)
+                .unwrap()
+                .is_none()
+        );
+
+        // Exhaustions and files that are not crash files are skipped
+        let crash_file = |name: &str, args: Vec<String>, crashes: Vec<Vec<Felt252>>| {
+            let crash_file = CrashFile {
+                name: name.to_string(),
+                args,
+                crashes,
+                ..Default::default()
+            };
+            serde_json::to_string(&crash_file).unwrap()
+        };
+        let crash = crash_file(
+            "Fuzz_one",
+            vec!["core::felt252".to_string()],
+            vec![vec![Felt252::from(1)]],
+        );
+        fs::write(folder.join("EXHAUSTIONS_Fuzz_one.json"), &crash).unwrap();
+        fs::write(folder.join("notes.txt"), "not json").unwrap();
+        assert!(
+            CrashFile::load_from_folder(&foldername, &workspace, &mut skipped)
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(skipped.len(), 1);
+
+        fs::write(folder.join("CRASHES_Fuzz_one.json"), crash).unwrap();
+        let crashes = CrashFile::load_from_folder(&foldername, &workspace, &mut skipped)
+            .unwrap()
+            .unwrap();
+        assert_eq!(crashes.name, "Fuzz_one");
+        assert_eq!(crashes.crashes, vec![vec![Felt252::from(1)]]);
+
+        let other = crash_file("Fuzz_two", Vec::new(), Vec::new());
+        fs::write(folder.join("CRASHES_Fuzz_two.json"), other).unwrap();
+        assert!(CrashFile::load_from_folder(&foldername, &workspace, &mut skipped).is_err());
+        let _ = fs::remove_dir_all(&folder);
+    }
+}
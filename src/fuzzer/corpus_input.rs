@@ -1,4 +1,5 @@
 //use crate::cairo_vm::cairo_types::Felt252;
+use crate::fuzzer::corpus_store::CorpusStore;
 use crate::json::json_parser::Function;
 use chrono::DateTime;
 use chrono::Utc;
@@ -9,9 +10,16 @@ use std::fs;
 use std::fs::create_dir;
 use std::fs::write;
 use std::path::Path;
-use std::process;
 use std::time::SystemTime;
 
+/// Problem found when checking an inputs file against a function
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub message: String,
+    /// Set if the input can't be executed as is: the worker only runs inputs with one felt per argument
+    pub error: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct InputFile {
     pub workspace: String,
@@ -45,54 +53,25 @@ impl InputFile {
     }
 
     /// Function to load the previous corpus if it exists
-    pub fn load_from_file(filename: &String, workspace: &String) -> Self {
-        // Try to load the file
-        let contents =
-            fs::read_to_string(filename).expect("Should have been able to read the file");
-        // Extract json data
-        let data: Value = serde_json::from_str(&contents).expect("JSON was not well-formatted");
-        // Load inputs
-        let mut inputs_vec: Vec<Vec<Felt252>> = Vec::new();
-        if let Some(inputs) = data.get("inputs") {
-            if let Some(inputs_array) = inputs.as_array() {
-                for input in inputs_array {
-                    if let Some(input_array) = input.as_array() {
-                        let mut felt_vec: Vec<Felt252> = Vec::new();
-                        for element in input_array {
-                            let value: Felt252 = serde_json::from_value(element.clone())
-                                .expect("Could not get values");
-                            felt_vec.push(value);
-                        }
-                        inputs_vec.push(felt_vec.clone());
-                    }
-                }
-            }
+    pub fn load_from_file(filename: &String, workspace: &String) -> Result<Self, String> {
+        // Binary corpus written by the fuzzer
+        if CorpusStore::is_corpus_file(filename) {
+            let mut input_file = CorpusStore::export(filename, workspace)?;
+            input_file.path = filename.clone();
+            return Ok(input_file);
         }
-
-        return InputFile {
+        let (name, args, inputs) = load_json_inputs(Path::new(filename))?;
+        Ok(InputFile {
             workspace: workspace.to_string(),
             path: filename.clone(),
-            name: data["name"]
-                .as_str()
-                .expect("Failed to get name from inputfile")
-                .to_string(),
-            args: data["args"]
-                .as_array()
-                .expect("Failed to get args from input file as array")
-                .iter()
-                .map(|input_array| {
-                    input_array
-                        .as_str()
-                        .expect("Failed to get input array as string")
-                        .to_string()
-                })
-                .collect(),
-            inputs: inputs_vec,
-        };
+            name: name.ok_or_else(|| format!("{}: no function name", filename))?,
+            args,
+            inputs,
+        })
     }
 
     /// Load all the old corpora
-    pub fn load_from_folder(foldername: &String, workspace: &String) -> Self {
+    pub fn load_from_folder(foldername: &String, workspace: &String) -> Result<Self, String> {
         let folder = Path::new(&foldername);
         let function_name = foldername
             .clone()
@@ -100,61 +79,47 @@ impl InputFile {
             .last()
             .expect("Failed to split foldername")
             .to_string();
+        let mut name: Option<String> = None;
         let mut args: Option<Vec<String>> = None;
         let mut inputs: Vec<Vec<Felt252>> = Vec::new();
         // Check if the path is a directory
         if folder.is_dir() {
             // Iterate over the entries in the directory
-            for entry in fs::read_dir(folder).expect("Failed to read directory") {
-                let entry = entry.expect("Failed to get entry");
-                let path = entry.path();
+            let entries = fs::read_dir(folder)
+                .map_err(|e| format!("Could not read {}: {}", folder.display(), e))?;
+            for entry in entries {
+                let path = entry
+                    .map_err(|e| format!("Could not read {}: {}", folder.display(), e))?
+                    .path();
                 // Check if the entry is a file
-                if path.is_file() {
-                    // Read the file and do something with its contents
-                    let contents =
-                        fs::read_to_string(&path).expect("Failed to read string from the file");
-                    let data: Value =
-                        serde_json::from_str(&contents).expect("JSON was not well-formatted");
-                    let args_data: Vec<String> = data["args"]
-                        .as_array()
-                        .expect("Failed to get args from input file as array")
-                        .iter()
-                        .map(|input_array| {
-                            input_array
-                                .as_str()
-                                .expect("Failed to get input array as string")
-                                .to_string()
-                        })
-                        .collect();
-                    if args.is_none() {
-                        args = Some(args_data);
+                if !path.is_file() {
+                    continue;
+                }
+                let path_str = path.to_string_lossy().to_string();
+                // Binary corpus written by the fuzzer
+                let (name_data, args_data, mut inputs_vec) =
+                    if CorpusStore::is_corpus_file(&path_str) {
+                        let input_file = CorpusStore::export(&path_str, workspace)?;
+                        (Some(input_file.name), input_file.args, input_file.inputs)
                     } else {
-                        if let Some(args_to_compare) = args.clone() {
-                            if args_to_compare != args_data {
-                                println!("Uncompatible inputs files");
-                                process::exit(1);
-                            }
-                        }
+                        load_json_inputs(&path)?
+                    };
+                match &args {
+                    Some(args_to_compare) if *args_to_compare != args_data => {
+                        return Err(format!(
+                            "Uncompatible inputs files: {} was generated for arguments {:?}, the previous files for {:?}",
+                            path.display(),
+                            args_data,
+                            args_to_compare
+                        ));
                     }
-                    let mut inputs_vec: Vec<Vec<Felt252>> = Vec::new();
-                    if let Some(inputs) = data.get("inputs") {
-                        if let Some(inputs_array) = inputs.as_array() {
-                            for input in inputs_array {
-                                if let Some(input_array) = input.as_array() {
-                                    let mut felt_vec: Vec<Felt252> = Vec::new();
-                                    for element in input_array {
-                                        let value: Felt252 =
-                                            serde_json::from_value(element.clone())
-                                                .expect("Could not get values");
-                                        felt_vec.push(value);
-                                    }
-                                    inputs_vec.push(felt_vec.clone());
-                                }
-                            }
-                        }
+                    Some(_) => {}
+                    None => {
+                        args = Some(args_data);
+                        name = name_data;
                     }
-                    inputs.append(&mut inputs_vec);
                 }
+                inputs.append(&mut inputs_vec);
             }
         }
         let d = SystemTime::now();
@@ -162,34 +127,281 @@ impl InputFile {
         let datetime = DateTime::<Utc>::from(d);
         // Formats the combined date and time with the specified format string.
         let timestamp_str = datetime.format("%Y-%m-%d--%H:%M:%S").to_string();
-        let data_args = if let Some(content) = args {
-            content
-        } else {
-            Vec::new()
-        };
-        return InputFile {
+        Ok(InputFile {
             workspace: workspace.to_string(),
             path: format!("{}_{}.json", function_name.clone(), timestamp_str),
-            name: function_name.clone(),
-            args: data_args,
-            inputs: inputs,
-        };
+            name: name.unwrap_or(function_name),
+            args: args.unwrap_or_default(),
+            inputs,
+        })
+    }
+    /// Checks the inputs against the signature of `function`
+    /// Returns a diagnostic for each problem, empty if the inputs match the function
+    /// A different name or argument types only give warnings, as a corpus stays useful
+    /// after a function is renamed
+    pub fn check_function(&self, function: &Function) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        if self.name != function.name {
+            diagnostics.push(Diagnostic {
+                message: format!(
+                    "{}: generated for function {}, not {}",
+                    self.path, self.name, function.name
+                ),
+                error: false,
+            });
+        }
+        if self.args != function.inputs {
+            diagnostics.push(Diagnostic {
+                message: format!(
+                    "{}: generated for arguments {:?}, {} takes {:?}",
+                    self.path, self.args, function.name, function.inputs
+                ),
+                error: false,
+            });
+        }
+        for (idx, input) in self.inputs.iter().enumerate() {
+            if input.len() != function.inputs.len() {
+                diagnostics.push(Diagnostic {
+                    message: format!(
+                        "{}: input {} has {} felts, {} takes {} arguments",
+                        self.path,
+                        idx,
+                        input.len(),
+                        function.name,
+                        function.inputs.len()
+                    ),
+                    error: true,
+                });
+            }
+            for (arg_idx, (value, arg_type)) in input.iter().zip(&function.inputs).enumerate() {
+                if !fits_type(value, arg_type) {
+                    diagnostics.push(Diagnostic {
+                        message: format!(
+                            "{}: input {}, argument {}: {} does not fit in {}",
+                            self.path, idx, arg_idx, value, arg_type
+                        ),
+                        error: false,
+                    });
+                }
+            }
+        }
+        diagnostics
     }
+
+    /// Adapts the inputs to the signature of `function`: missing arguments are set to 0,
+    /// extra felts are dropped and the values are truncated to the bits of their type
+    /// Returns the number of modified inputs
+    pub fn adapt_to_function(&mut self, function: &Function) -> usize {
+        let mut adapted = 0;
+        for input in &mut self.inputs {
+            let original = input.clone();
+            input.resize(function.inputs.len(), Felt252::from(0));
+            for (value, arg_type) in input.iter_mut().zip(&function.inputs) {
+                *value = fit_to_type(value, arg_type);
+            }
+            if *input != original {
+                adapted += 1;
+            }
+        }
+        self.name = function.name.clone();
+        self.args = function.inputs.clone();
+        adapted
+    }
+
     /// Function to dump the inputs corpus
     pub fn dump_json(&self) {
         let _ = create_dir(&self.workspace);
         let _ = create_dir(format!("{}/{}", &self.workspace, self.name.clone()));
         let _ = create_dir(format!("{}/{}/inputs", &self.workspace, self.name.clone()));
+        self.write_json().expect("Failed to save input to disk");
+    }
+
+    /// Writes the inputs corpus to its path
+    pub fn write_json(&self) -> Result<(), String> {
         let buf = Vec::new();
         let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
         let mut inputs_ser = serde_json::Serializer::with_formatter(buf.clone(), formatter.clone());
         self.serialize(&mut inputs_ser)
-            .expect("Failed to serialize");
-        let dump_file = format!("{}", self.path);
+            .map_err(|e| format!("Failed to serialize: {}", e))?;
         write(
-            &dump_file,
+            &self.path,
             String::from_utf8(inputs_ser.into_inner()).expect("Failed to dump string as utf8"),
         )
-        .expect("Failed to save input to disk");
+        .map_err(|e| format!("Could not write {}: {}", self.path, e))
+    }
+}
+
+/// Reads the function name, the argument types and the inputs of a JSON inputs file
+fn load_json_inputs(
+    path: &Path,
+) -> Result<(Option<String>, Vec<String>, Vec<Vec<Felt252>>), String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("{}: could not read the file: {}", path.display(), e))?;
+    let data: Value = serde_json::from_str(&contents)
+        .map_err(|e| format!("{}: invalid JSON: {}", path.display(), e))?;
+    let args_data: Vec<String> = data["args"]
+        .as_array()
+        .ok_or_else(|| format!("{}: no args array", path.display()))?
+        .iter()
+        .map(|arg| arg.as_str().map(|arg| arg.to_string()))
+        .collect::<Option<_>>()
+        .ok_or_else(|| format!("{}: the args are not strings", path.display()))?;
+    let mut inputs_vec: Vec<Vec<Felt252>> = Vec::new();
+    if let Some(inputs_array) = data.get("inputs").and_then(Value::as_array) {
+        for (idx, input) in inputs_array.iter().enumerate() {
+            if let Some(input_array) = input.as_array() {
+                let felt_vec = input_array
+                    .iter()
+                    .map(|element| serde_json::from_value(element.clone()))
+                    .collect::<Result<Vec<Felt252>, _>>()
+                    .map_err(|e| format!("{}: invalid input {}: {}", path.display(), idx, e))?;
+                inputs_vec.push(felt_vec);
+            }
+        }
+    }
+    let name = data["name"].as_str().map(|name| name.to_string());
+    Ok((name, args_data, inputs_vec))
+}
+
+/// Range of the values of an argument type as its number of bits and its signedness,
+/// `None` for the felts and the types spanning several felts
+/// A u256 argument takes one felt of the input, as its u128 limbs
+fn type_range(arg_type: &str) -> Option<(u32, bool)> {
+    match arg_type {
+        "core::integer::u8" => Some((8, false)),
+        "core::integer::u16" => Some((16, false)),
+        "core::integer::u32" => Some((32, false)),
+        "core::integer::u64" => Some((64, false)),
+        "core::integer::u128" | "core::integer::u256" => Some((128, false)),
+        "core::integer::i8" => Some((8, true)),
+        "core::integer::i16" => Some((16, true)),
+        "core::integer::i32" => Some((32, true)),
+        "core::integer::i64" => Some((64, true)),
+        "core::integer::i128" => Some((128, true)),
+        "core::bool" => Some((1, false)),
+        _ => None,
+    }
+}
+
+/// Returns true if `value` is in the range of `arg_type`, the negative values of the
+/// signed integers are represented modulo P
+fn fits_type(value: &Felt252, arg_type: &str) -> bool {
+    match type_range(arg_type) {
+        Some((bits, false)) => value.bits() <= bits as u64,
+        // -2^(bits-1) <= value < 2^(bits-1)
+        Some((bits, true)) => {
+            value.bits() < bits as u64
+                || (Felt252::from(0) - value.clone() - Felt252::from(1)).bits() < bits as u64
+        }
+        None => true,
+    }
+}
+
+/// Truncates `value` to the bits of `arg_type`, the signed integers keep the two's
+/// complement of their truncated bits
+fn fit_to_type(value: &Felt252, arg_type: &str) -> Felt252 {
+    if fits_type(value, arg_type) {
+        return value.clone();
+    }
+    match type_range(arg_type) {
+        Some((bits, signed)) => {
+            let bound = Felt252::from(1) << bits;
+            let truncated = value & &(bound.clone() - Felt252::from(1));
+            match signed && truncated.bits() == bits as u64 {
+                true => truncated - bound,
+                false => truncated,
+            }
+        }
+        None => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_file_check_function() {
+        let function = Function {
+            name: "Fuzz_symbolic_execution".to_string(),
+            selector_idx: 0,
+            inputs: vec!["core::felt252".to_string(), "core::integer::u8".to_string()],
+            outputs: Vec::new(),
+        };
+        let workspace = "fuzzer_workspace".to_string();
+        let mut inputs = InputFile::new_from_function(&function, &workspace);
+        inputs
+            .inputs
+            .push(vec![Felt252::from(1), Felt252::from(255)]);
+        assert!(inputs.check_function(&function).is_empty());
+
+        inputs.name = "Fuzz_one".to_string();
+        inputs.inputs.push(vec![Felt252::from(1)]);
+        inputs
+            .inputs
+            .push(vec![Felt252::from(1), Felt252::from(256), Felt252::from(3)]);
+        // Function name, u8 overflow and length of the two last inputs
+        let diagnostics = inputs.check_function(&function);
+        assert_eq!(diagnostics.len(), 4);
+        assert_eq!(diagnostics.iter().filter(|d| d.error).count(), 2);
+
+        assert_eq!(inputs.adapt_to_function(&function), 2);
+        assert!(inputs.check_function(&function).is_empty());
+        assert_eq!(inputs.inputs[1], vec![Felt252::from(1), Felt252::from(0)]);
+        assert_eq!(inputs.inputs[2], vec![Felt252::from(1), Felt252::from(0)]);
+    }
+
+    #[test]
+    fn test_fit_to_type() {
+        let minus = |value: u64| Felt252::from(0) - Felt252::from(value);
+        assert!(fits_type(&Felt252::from(127), "core::integer::i8"));
+        assert!(fits_type(&minus(128), "core::integer::i8"));
+        assert!(!fits_type(&Felt252::from(128), "core::integer::i8"));
+        assert!(!fits_type(&minus(129), "core::integer::i8"));
+        assert!(!fits_type(&minus(1), "core::integer::u128"));
+        assert!(fits_type(&minus(1), "core::felt252"));
+        assert_eq!(
+            fit_to_type(&Felt252::from(255), "core::integer::i8"),
+            minus(1)
+        );
+        assert_eq!(
+            fit_to_type(&Felt252::from(256), "core::integer::u8"),
+            Felt252::from(0)
+        );
+        assert_eq!(
+            fit_to_type(&(Felt252::from(1) << 200), "core::integer::u256"),
+            Felt252::from(0)
+        );
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let folder = std::env::temp_dir().join("cairo_fuzzer_test_input_folder");
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        let foldername = folder.to_string_lossy().to_string();
+        let workspace = "fuzzer_workspace".to_string();
+        let input_file = |args: Vec<&str>| {
+            let input_file = InputFile {
+                name: "Fuzz_one".to_string(),
+                args: args.into_iter().map(String::from).collect(),
+                ..Default::default()
+            };
+            serde_json::to_string(&input_file).unwrap()
+        };
+
+        let malformed = folder.join("malformed.json");
+        fs::write(&malformed, "{").unwrap();
+        let malformed = malformed.to_string_lossy().to_string();
+        assert!(InputFile::load_from_file(&malformed, &workspace).is_err());
+        assert!(InputFile::load_from_folder(&foldername, &workspace).is_err());
+        fs::remove_file(&malformed).unwrap();
+
+        fs::write(folder.join("a.json"), input_file(vec!["core::felt252"])).unwrap();
+        assert!(InputFile::load_from_folder(&foldername, &workspace).is_ok());
+        fs::write(folder.join("b.json"), input_file(vec!["core::integer::u8"])).unwrap();
+        let error = InputFile::load_from_folder(&foldername, &workspace).unwrap_err();
+        assert!(error.starts_with("Uncompatible inputs files"));
+        let _ = fs::remove_dir_all(&folder);
     }
 }
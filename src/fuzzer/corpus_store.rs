@@ -0,0 +1,382 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Utc};
+use felt::Felt252;
+
+use super::corpus_input::InputFile;
+use super::stats::FuzzInput;
+use crate::json::json_parser::Function;
+
+/// First bytes of a binary corpus file
+const MAGIC: &[u8; 4] = b"CFZC";
+/// Version of the binary corpus format
+const VERSION: u8 = 1;
+
+/// An input of the corpus and the metadata of its discovery
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorpusEntry {
+    /// Arguments of the function
+    pub calldata: FuzzInput,
+    /// Name of the fuzzed function
+    pub function: String,
+    /// Discovery time, in milliseconds since the Unix epoch
+    pub discovered: u64,
+    /// Hash of the corpus input mutated into this one, if any
+    pub parent: Option<u64>,
+    /// Mutation strategies applied to the parent, comma separated
+    pub mutation: String,
+    /// Hash of the coverage reached by the input
+    pub coverage: u64,
+    /// Number of Cairo steps executed by the input
+    pub steps: u64,
+}
+
+impl CorpusEntry {
+    /// Creates an entry without discovery metadata, e.g. for imported inputs
+    pub fn new(function: &str, calldata: FuzzInput) -> Self {
+        CorpusEntry {
+            calldata,
+            function: function.to_string(),
+            discovered: now_millis(),
+            parent: None,
+            mutation: String::new(),
+            coverage: 0,
+            steps: 0,
+        }
+    }
+}
+
+/// Milliseconds since the Unix epoch
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Failed to get actual time")
+        .as_millis() as u64
+}
+
+/// Append-only binary corpus
+///
+/// The file starts with a header (magic, version, function name and argument types)
+/// followed by the entries, each prefixed by its length as a little endian u32.
+/// Adding an input only appends its entry, unlike `InputFile::dump_json` which rewrites
+/// the whole corpus. A truncated last entry (e.g. the fuzzer was killed while writing)
+/// is ignored when loading.
+pub struct CorpusStore {
+    /// Path of the corpus file
+    pub path: String,
+    /// Name of the fuzzed function
+    pub name: String,
+    /// Types of the arguments of the function
+    pub args: Vec<String>,
+    /// Opened on the first append
+    writer: Option<BufWriter<File>>,
+}
+
+impl CorpusStore {
+    /// Creates a store appending to `path`, the file is created on the first append
+    pub fn new(path: &str, name: &str, args: &[String]) -> Self {
+        CorpusStore {
+            path: path.to_string(),
+            name: name.to_string(),
+            args: args.to_vec(),
+            writer: None,
+        }
+    }
+
+    /// Init a new store in the inputs folder of the function
+    pub fn new_from_function(function: &Function, workspace: &String) -> Self {
+        let datetime = DateTime::<Utc>::from(SystemTime::now());
+        let timestamp_str = datetime.format("%Y-%m-%d--%H:%M:%S").to_string();
+        CorpusStore::new(
+            &format!(
+                "{}/{}/inputs/{}_{}.corpus",
+                workspace, function.name, function.name, timestamp_str
+            ),
+            &function.name,
+            &function.inputs,
+        )
+    }
+
+    /// Appends an entry to the corpus file
+    pub fn append(&mut self, entry: &CorpusEntry) -> Result<(), String> {
+        self.append_all(std::slice::from_ref(entry))
+    }
+
+    /// Appends entries to the corpus file, the file is flushed once all are written
+    pub fn append_all(&mut self, entries: &[CorpusEntry]) -> Result<(), String> {
+        if self.writer.is_none() {
+            self.writer = Some(self.open()?);
+        }
+        let writer = self.writer.as_mut().expect("Corpus file not opened");
+        let mut write = || -> std::io::Result<()> {
+            for entry in entries {
+                let payload = encode_entry(entry);
+                writer.write_all(&(payload.len() as u32).to_le_bytes())?;
+                writer.write_all(&payload)?;
+            }
+            writer.flush()
+        };
+        write().map_err(|e| format!("Could not write {}: {}", self.path, e))
+    }
+
+    /// Opens the corpus file for appending, writes the header if the file is new
+    /// A truncated last entry is removed so the new entries stay readable
+    fn open(&self) -> Result<BufWriter<File>, String> {
+        if let Some(parent) = Path::new(&self.path).parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Could not create {}: {}", parent.display(), e))?;
+        }
+        let is_new = !Path::new(&self.path).exists();
+        if !is_new {
+            let data =
+                fs::read(&self.path).map_err(|e| format!("Could not read {}: {}", self.path, e))?;
+            let mut reader = Reader::new(&data);
+            decode_header(&mut reader).map_err(|e| format!("{}: {}", self.path, e))?;
+            let mut valid_len = reader.pos;
+            while let Some(len) = reader.u32() {
+                if reader.bytes(len as usize).is_none() {
+                    break;
+                }
+                valid_len = reader.pos;
+            }
+            if valid_len < data.len() {
+                OpenOptions::new()
+                    .write(true)
+                    .open(&self.path)
+                    .and_then(|file| file.set_len(valid_len as u64))
+                    .map_err(|e| format!("Could not truncate {}: {}", self.path, e))?;
+            }
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| format!("Could not open {}: {}", self.path, e))?;
+        let mut writer = BufWriter::new(file);
+        if is_new {
+            writer
+                .write_all(&encode_header(&self.name, &self.args))
+                .map_err(|e| format!("Could not write {}: {}", self.path, e))?;
+        }
+        Ok(writer)
+    }
+
+    /// Loads a corpus file, returns the function name, the argument types and the entries
+    pub fn load(path: &str) -> Result<(String, Vec<String>, Vec<CorpusEntry>), String> {
+        let data = fs::read(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+        let mut reader = Reader::new(&data);
+        let (name, args) = decode_header(&mut reader).map_err(|e| format!("{}: {}", path, e))?;
+        let mut entries = Vec::new();
+        while let Some(len) = reader.u32() {
+            match reader.bytes(len as usize) {
+                Some(payload) => {
+                    let entry = decode_entry(&mut Reader::new(payload))
+                        .map_err(|e| format!("{}: entry {}: {}", path, entries.len(), e))?;
+                    entries.push(entry);
+                }
+                // Truncated last entry
+                None => break,
+            }
+        }
+        Ok((name, args, entries))
+    }
+
+    /// Returns true if the file starts with the binary corpus magic
+    pub fn is_corpus_file(path: &str) -> bool {
+        let mut magic = [0u8; 4];
+        match File::open(path) {
+            Ok(mut file) => {
+                std::io::Read::read_exact(&mut file, &mut magic).is_ok() && &magic == MAGIC
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Writes the inputs of a JSON inputs file to a new corpus file at `path`
+    pub fn import(input_file: &InputFile, path: &str) -> Result<usize, String> {
+        if Path::new(path).exists() {
+            return Err(format!("{} already exists", path));
+        }
+        let mut store = CorpusStore::new(path, &input_file.name, &input_file.args);
+        let entries: Vec<CorpusEntry> = input_file
+            .inputs
+            .iter()
+            .map(|input| CorpusEntry::new(&input_file.name, input.clone()))
+            .collect();
+        store.append_all(&entries)?;
+        Ok(entries.len())
+    }
+
+    /// Loads the corpus file at `path` as a JSON inputs file, the metadata is dropped
+    pub fn export(path: &str, workspace: &str) -> Result<InputFile, String> {
+        let (name, args, entries) = CorpusStore::load(path)?;
+        Ok(InputFile {
+            workspace: workspace.to_string(),
+            path: String::new(),
+            name,
+            args,
+            inputs: entries.into_iter().map(|entry| entry.calldata).collect(),
+        })
+    }
+}
+
+fn encode_header(name: &str, args: &[String]) -> Vec<u8> {
+    let mut buf = MAGIC.to_vec();
+    buf.push(VERSION);
+    put_str(&mut buf, name);
+    buf.extend_from_slice(&(args.len() as u32).to_le_bytes());
+    for arg in args {
+        put_str(&mut buf, arg);
+    }
+    buf
+}
+
+fn decode_header(reader: &mut Reader) -> Result<(String, Vec<String>), String> {
+    if reader.bytes(MAGIC.len()) != Some(&MAGIC[..]) {
+        return Err("not a corpus file".to_string());
+    }
+    match reader.bytes(1) {
+        Some([VERSION]) => {}
+        Some([version]) => return Err(format!("unsupported corpus version {}", version)),
+        _ => return Err("truncated header".to_string()),
+    }
+    let name = reader.string().ok_or("truncated header")?;
+    let args_len = reader.u32().ok_or("truncated header")?;
+    let mut args = Vec::new();
+    for _ in 0..args_len {
+        args.push(reader.string().ok_or("truncated header")?);
+    }
+    Ok((name, args))
+}
+
+fn encode_entry(entry: &CorpusEntry) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(64 + entry.calldata.len() * 32);
+    put_str(&mut buf, &entry.function);
+    buf.extend_from_slice(&entry.discovered.to_le_bytes());
+    match entry.parent {
+        Some(parent) => {
+            buf.push(1);
+            buf.extend_from_slice(&parent.to_le_bytes());
+        }
+        None => buf.push(0),
+    }
+    put_str(&mut buf, &entry.mutation);
+    buf.extend_from_slice(&entry.coverage.to_le_bytes());
+    buf.extend_from_slice(&entry.steps.to_le_bytes());
+    buf.extend_from_slice(&(entry.calldata.len() as u32).to_le_bytes());
+    for felt in &entry.calldata {
+        // Felts are stored as 32 bytes big endian
+        let bytes = felt.to_bytes_be();
+        buf.resize(buf.len() + 32 - bytes.len(), 0);
+        buf.extend_from_slice(&bytes);
+    }
+    buf
+}
+
+fn decode_entry(reader: &mut Reader) -> Result<CorpusEntry, String> {
+    let truncated = || "truncated entry".to_string();
+    let function = reader.string().ok_or_else(truncated)?;
+    let discovered = reader.u64().ok_or_else(truncated)?;
+    let parent = match reader.bytes(1) {
+        Some([0]) => None,
+        Some([1]) => Some(reader.u64().ok_or_else(truncated)?),
+        _ => return Err("invalid parent".to_string()),
+    };
+    let mutation = reader.string().ok_or_else(truncated)?;
+    let coverage = reader.u64().ok_or_else(truncated)?;
+    let steps = reader.u64().ok_or_else(truncated)?;
+    let calldata_len = reader.u32().ok_or_else(truncated)?;
+    let mut calldata = Vec::with_capacity(calldata_len as usize);
+    for _ in 0..calldata_len {
+        calldata.push(Felt252::from_bytes_be(
+            reader.bytes(32).ok_or_else(truncated)?,
+        ));
+    }
+    Ok(CorpusEntry {
+        calldata,
+        function,
+        discovered,
+        parent,
+        mutation,
+        coverage,
+        steps,
+    })
+}
+
+fn put_str(buf: &mut Vec<u8>, value: &str) {
+    buf.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    buf.extend_from_slice(value.as_bytes());
+}
+
+/// Cursor over the bytes of a corpus file, every read returns `None` past the end
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Reader { data, pos: 0 }
+    }
+
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.bytes(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.bytes(8)?.try_into().ok()?))
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let len = self.u32()? as usize;
+        String::from_utf8(self.bytes(len)?.to_vec()).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+
+    use super::{CorpusEntry, CorpusStore};
+    use felt::Felt252;
+
+    #[test]
+    fn test_corpus_store() {
+        let path = std::env::temp_dir().join("cairo_fuzzer_test_corpus_store.corpus");
+        let path = path.to_str().expect("Invalid temp path").to_string();
+        let _ = fs::remove_file(&path);
+
+        let mut entry = CorpusEntry::new("f", vec![Felt252::from(42), Felt252::from(0)]);
+        entry.parent = Some(7);
+        entry.mutation = "felt_boundary,add_cmp_value".to_string();
+        entry.coverage = 3;
+        entry.steps = 120;
+        let mut store = CorpusStore::new(&path, "f", &["felt".to_string(), "u8".to_string()]);
+        store.append(&entry).expect("Could not append");
+        drop(store);
+
+        // A truncated last entry is ignored, then dropped by the next append
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[100, 0, 0, 0, 1, 2]).unwrap();
+        let (name, args, entries) = CorpusStore::load(&path).expect("Could not load");
+        assert_eq!(name, "f");
+        assert_eq!(args, vec!["felt", "u8"]);
+        assert_eq!(entries, vec![entry.clone()]);
+        let mut store = CorpusStore::new(&path, "f", &[]);
+        store.append(&entry).expect("Could not append");
+
+        let input_file = CorpusStore::export(&path, "workspace").unwrap();
+        assert_eq!(input_file.inputs, vec![entry.calldata.clone(); 2]);
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+
+use cairo_fuzzer_core::coverage::{
+    CoverageAnnotations, CoverageReport, SourceMap, COVERAGE_ANNOTATIONS,
+};
+use cairo_lang_sierra::extensions::gas::CostTokenType;
+use cairo_lang_sierra_to_casm::compiler::compile;
+use cairo_lang_sierra_to_casm::metadata::{calc_metadata, MetadataComputationConfig};
+use cairo_lang_starknet::casm_contract_class::CasmContractClass;
+use cairo_lang_starknet::contract_class::ContractClass;
+use serde_json::Value;
+
+/// Gas cost of an entry point call, set by the compiler of the CASM contract class
+const ENTRY_POINT_COST: i32 = 10000;
+
+/// Source coverage of the executions of a function
+///
+/// The executed pcs are mapped to the Sierra statements they were compiled from, using
+/// the code offsets of the statements when the Sierra program is compiled again, and the
+/// statements to the Cairo source lines using the annotations of the Sierra debug info.
+pub struct SourceCoverage {
+    source_map: SourceMap,
+    /// Sierra statement starting at each bytecode offset, the statements without
+    /// instructions are left out
+    statements: HashMap<usize, usize>,
+    /// Bytecode offset of the fuzzed entry point
+    entry_offset: usize,
+    /// Number of executions of each statement
+    hits: HashMap<usize, u64>,
+}
+
+impl SourceCoverage {
+    /// Maps the bytecode of `casm_contract_class` to the sources of the Sierra contract class
+    pub fn new(
+        contract_content: &str,
+        casm_contract_class: &CasmContractClass,
+        selector_idx: usize,
+    ) -> Result<Self, String> {
+        let value: Value = serde_json::from_str(contract_content)
+            .map_err(|e| format!("Could not parse the contract: {}", e))?;
+        let annotations = match value
+            .get("sierra_program_debug_info")
+            .and_then(|debug_info| debug_info.get("annotations"))
+            .and_then(|annotations| annotations.get(COVERAGE_ANNOTATIONS))
+        {
+            Some(annotations) => serde_json::from_value::<CoverageAnnotations>(annotations.clone())
+                .map_err(|e| format!("Could not parse the coverage annotations: {}", e))?,
+            None => {
+                return Err(
+                    "the Sierra debug info has no statements locations, compile the contract with \
+                     unstable-add-statements-code-locations-debug-info"
+                        .to_string(),
+                )
+            }
+        };
+        let source_map = SourceMap::from_annotations(&annotations)?;
+
+        let contract_class: ContractClass = serde_json::from_value(value)
+            .map_err(|e| format!("Could not parse the contract: {}", e))?;
+        let offsets = statement_offsets(&contract_class, casm_contract_class)?;
+        let mut statements = HashMap::new();
+        let mut hits = HashMap::new();
+        for (statement, offset) in offsets.iter().enumerate() {
+            let end = offsets
+                .get(statement + 1)
+                .copied()
+                .unwrap_or(casm_contract_class.bytecode.len());
+            if end > *offset {
+                statements.insert(*offset, statement);
+                hits.insert(statement, 0);
+            }
+        }
+        let entry_offset = casm_contract_class
+            .entry_points_by_type
+            .external
+            .get(selector_idx)
+            .ok_or("Could not get entry point")?
+            .offset;
+
+        Ok(SourceCoverage {
+            source_map,
+            statements,
+            entry_offset,
+            hits,
+        })
+    }
+
+    /// Counts the statements executed by a trace
+    pub fn add_trace(&mut self, pcs: &[usize]) {
+        // The first pc of the trace is the entry point, it gives the base of the bytecode
+        let base = match pcs.first() {
+            Some(pc) => pc.wrapping_sub(self.entry_offset),
+            None => return,
+        };
+        for pc in pcs {
+            if let Some(statement) = self.statements.get(&pc.wrapping_sub(base)) {
+                *self.hits.entry(*statement).or_default() += 1;
+            }
+        }
+    }
+
+    /// Coverage of the source lines and functions by the added traces
+    pub fn report(&self) -> CoverageReport {
+        CoverageReport::new(&self.source_map, &self.hits)
+    }
+}
+
+/// Compiles the Sierra program of the contract class as done for its CASM contract class
+/// and returns the bytecode offset of each statement
+fn statement_offsets(
+    contract_class: &ContractClass,
+    casm_contract_class: &CasmContractClass,
+) -> Result<Vec<usize>, String> {
+    let program = contract_class
+        .extract_sierra_program()
+        .map_err(|e| format!("Could not extract the Sierra program: {:?}", e))?;
+    let entry_points = &contract_class.entry_points_by_type;
+    let function_set_costs = entry_points
+        .external
+        .iter()
+        .chain(&entry_points.l1_handler)
+        .chain(&entry_points.constructor)
+        .map(|entry_point| {
+            (
+                program.funcs[entry_point.function_idx].id.clone(),
+                [(CostTokenType::Const, ENTRY_POINT_COST)]
+                    .into_iter()
+                    .collect(),
+            )
+        })
+        .collect();
+    let metadata = calc_metadata(&program, MetadataComputationConfig { function_set_costs })
+        .map_err(|e| format!("Could not compute the Sierra metadata: {:?}", e))?;
+    let cairo_program = compile(&program, &metadata, true)
+        .map_err(|e| format!("Could not compile the Sierra program: {:?}", e))?;
+
+    let size: usize = cairo_program
+        .instructions
+        .iter()
+        .map(|instruction| instruction.body.op_size())
+        .sum();
+    if size != casm_contract_class.bytecode.len() {
+        return Err(
+            "the CASM contract class was not compiled from this Sierra contract class".to_string(),
+        );
+    }
+    Ok(cairo_program
+        .debug_info
+        .sierra_statement_info
+        .iter()
+        .map(|info| info.code_offset)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::runner::Runner;
+    use crate::runner::starknet_runner::RunnerStarknet;
+    use felt::Felt252;
+
+    #[test]
+    fn test_source_coverage() {
+        // The 2.1.0-rc2 compiler doesn't emit the statements locations, they were written
+        // for the Sierra statements of Fuzz_symbolic_execution in tests1.0/fuzzinglabs.cairo:
+        // statement 630 compares `f == 'f'` on line 31, the panic is on line 42
+        let contract_content =
+            std::fs::read_to_string("tests1.0/fuzzinglabs_coverage.json").unwrap();
+        let casm_content = std::fs::read_to_string("tests1.0/fuzzinglabs.casm").unwrap();
+        let contract_class: CasmContractClass = serde_json::from_str(&casm_content).unwrap();
+        let mut coverage = SourceCoverage::new(&contract_content, &contract_class, 0).unwrap();
+        let path = "tests1.0/fuzzinglabs.cairo";
+        let line_hits = |coverage: &SourceCoverage, line: usize| {
+            let report = coverage.report();
+            let file = report.files.iter().find(|file| file.path == path).unwrap();
+            file.lines[&line]
+        };
+
+        // A trace loaded at a nonzero base: the entry point and the first statement of the
+        // function body
+        let offset = coverage
+            .statements
+            .iter()
+            .find(|(_, statement)| **statement == 630)
+            .map(|(offset, _)| *offset)
+            .expect("Statement 630 has no instructions");
+        let base = 1000;
+        coverage.add_trace(&[base + coverage.entry_offset, base + offset]);
+        assert_eq!(coverage.hits[&630], 1);
+        assert_eq!(line_hits(&coverage, 31), 1);
+        assert_eq!(line_hits(&coverage, 42), 0);
+
+        // Executions of the contract: the first comparison fails, then the crashing input
+        let run = |input: &Vec<Felt252>| {
+            let runner = RunnerStarknet::new(&contract_class, 0);
+            let (_, result) = runner.run(input).expect("Could not run the contract");
+            result
+                .coverage
+                .iter()
+                .map(|pc| *pc as usize)
+                .collect::<Vec<_>>()
+        };
+        coverage.add_trace(&run(&vec![Felt252::from(0); 11]));
+        assert_eq!(line_hits(&coverage, 32), 0);
+        assert!(line_hits(&coverage, 54) > 0);
+        let crash: Vec<Felt252> = b"fuzzinglabs".iter().map(|c| Felt252::from(*c)).collect();
+        coverage.add_trace(&run(&crash));
+        assert!(line_hits(&coverage, 31) > 1);
+        assert!(line_hits(&coverage, 41) > 0);
+        assert!(line_hits(&coverage, 42) > 0);
+
+        let report = coverage.report();
+        assert_eq!(report.lines_found(), 13);
+        assert_eq!(report.files[0].functions.len(), 1);
+        assert!(report.files[0].functions[0].hits > 0);
+
+        // Without the annotations the source lines are unknown
+        let contract_content = std::fs::read_to_string("tests1.0/fuzzinglabs.json").unwrap();
+        assert!(SourceCoverage::new(&contract_content, &contract_class, 0).is_err());
+    }
+}
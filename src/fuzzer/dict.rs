@@ -1,29 +1,46 @@
+//! Dictionaries of the mutator, shared with the cairo-native fuzzer
 use felt::Felt252;
-use std::fs;
 
-#[derive(Debug, Clone, Default)]
-pub struct Dict {
-    pub inputs: Vec<Felt252>,
+use crate::runner::runner::Felt252Field;
+
+pub use cairo_fuzzer_core::dict::{DictEntry, DictScope};
+
+/// Dictionary of cairo-vm felts, see `cairo_fuzzer_core::dict::Dict` for the format
+pub type Dict = cairo_fuzzer_core::dict::Dict<Felt252Field>;
+
+/// Parses a decimal, hex, negative, short string or string value into a felt
+pub(crate) fn parse_value(value: &str) -> Result<Felt252, String> {
+    cairo_fuzzer_core::dict::parse_value::<Felt252Field>(value)
 }
-impl Dict {
-    pub fn read_dict(path: &String) -> Dict {
-        println!("\t\t\t\t\t\t\tReading and parsing dict: {}", path);
-        let contents = fs::read_to_string(path).expect("Could not read dictionnary");
-        let lines = contents.split('\n');
 
-        let mut data: Vec<Felt252> = Vec::new();
+#[cfg(test)]
+mod tests {
+    use super::{parse_value, Dict};
+    use felt::Felt252;
+
+    #[test]
+    fn test_parse_dict() {
+        let dict = Dict::parse(
+            "key1=9999999999999999999999999999999\n\
+             owner='admin'\n\
+             minus_one=-1\n\
+             small[core::integer::u8]=0xff\n",
+        )
+        .expect("Could not parse dict");
 
-        for line in lines {
-            let mut parts = line.trim().split('=');
-            if let Some(_) = parts.next() {
-                if let Some(value) = parts.next() {
-                    let val: Result<u128, _> = value.to_owned().parse();
-                    data.push(Felt252::from(
-                        val.expect("could not get u128 from value in dict"),
-                    ));
-                }
-            }
-        }
-        return Dict { inputs: data };
+        let values: Vec<Felt252> = dict.entries.iter().map(|e| e.value.clone()).collect();
+        assert_eq!(
+            values,
+            vec![
+                Felt252::parse_bytes(b"9999999999999999999999999999999", 10).unwrap(),
+                Felt252::from_bytes_be(b"admin"),
+                Felt252::from(0) - Felt252::from(1),
+                Felt252::from(255),
+            ]
+        );
+        assert!(
+            parse_value("0x800000000000011000000000000000000000000000000000000000000000001")
+                .is_err()
+        );
     }
 }
@@ -0,0 +1,139 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use cairo_lang_starknet::casm_contract_class::CasmContractClass;
+use felt::Felt252;
+use serde::{Deserialize, Serialize};
+
+use crate::runner::runner::Runner;
+use crate::runner::starknet_runner::RunnerStarknet;
+
+/// Result of an execution on the cairo-vm runner
+///
+/// The records are written as JSON lines and replayed by the cairo-native fuzzer
+/// (`--differential`), which reports the executions whose results differ.
+/// Felts are encoded as `0x` prefixed hex strings.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExecutionRecord {
+    /// Name of the executed function
+    pub function: String,
+    /// Arguments of the function
+    pub calldata: Vec<String>,
+    /// Set if the function panicked or could not be executed
+    pub failure_flag: bool,
+    /// Return data, or panic data if the function panicked
+    pub retdata: Vec<String>,
+    /// Error returned by the runner
+    pub error: Option<String>,
+    /// Storage writes of the execution as (key, value), sorted by key
+    pub storage_writes: Vec<(String, String)>,
+}
+
+fn to_hex(felt: &Felt252) -> String {
+    format!("0x{}", felt.to_str_radix(16))
+}
+
+/// Executes `calldata` on a fresh state and records the result
+pub fn record_execution(
+    contract_class: &CasmContractClass,
+    selector_idx: usize,
+    function: &str,
+    calldata: &Vec<Felt252>,
+) -> ExecutionRecord {
+    let runner = RunnerStarknet::new(contract_class, selector_idx);
+    let mut record = ExecutionRecord {
+        function: function.to_string(),
+        calldata: calldata.iter().map(to_hex).collect(),
+        failure_flag: true,
+        retdata: Vec::new(),
+        error: None,
+        storage_writes: Vec::new(),
+    };
+    match runner.run(calldata) {
+        Ok((_, result)) => {
+            record.failure_flag = result.failure_flag;
+            record.retdata = result.retdata.iter().map(to_hex).collect();
+            let mut storage_writes = result.storage_writes;
+            storage_writes.sort();
+            record.storage_writes = storage_writes
+                .iter()
+                .map(|(key, value)| (to_hex(key), to_hex(value)))
+                .collect();
+        }
+        Err(e) => record.error = Some(e),
+    }
+    record
+}
+
+/// Writes the records to `path`, one JSON object per line
+pub fn dump_records(path: &str, records: &[ExecutionRecord]) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| format!("Could not create {}: {}", path, e))?;
+    let mut writer = BufWriter::new(file);
+    for record in records {
+        let line = serde_json::to_string(record).map_err(|e| e.to_string())?;
+        writeln!(writer, "{}", line).map_err(|e| format!("Could not write {}: {}", path, e))?;
+    }
+    writer
+        .flush()
+        .map_err(|e| format!("Could not write {}: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::json_parser::Function;
+
+    #[test]
+    fn test_record_execution() {
+        let casm = std::fs::read_to_string("tests1.0/fuzzinglabs.casm").unwrap();
+        let contract_class: CasmContractClass = serde_json::from_str(&casm).unwrap();
+        let types = [
+            "felt252",
+            "felt252",
+            "integer::u16",
+            "integer::u32",
+            "integer::u64",
+            "integer::u128",
+            "integer::u128",
+            "integer::u128",
+            "felt252",
+            "felt252",
+            "integer::u8",
+        ];
+        let function = Function {
+            name: "Fuzz_symbolic_execution".to_string(),
+            selector_idx: 0,
+            inputs: types.iter().map(|ty| format!("core::{}", ty)).collect(),
+            outputs: Vec::new(),
+        };
+        let execute = |input: &Vec<Felt252>| {
+            record_execution(
+                &contract_class,
+                function.selector_idx,
+                &function.name,
+                input,
+            )
+        };
+        let input = vec![Felt252::from(0); function.inputs.len()];
+        let record = execute(&input);
+        assert_eq!(record.calldata, vec!["0x0"; input.len()]);
+        assert!(!record.failure_flag);
+        assert!(record.retdata.is_empty());
+        assert!(record.error.is_none());
+
+        // The arguments of the input reaching the assert spell the name of the contract
+        let input: Vec<Felt252> = "fuzzinglabs".bytes().map(Felt252::from).collect();
+        let record = execute(&input);
+        assert!(record.failure_flag);
+        assert_eq!(
+            record.retdata,
+            vec![to_hex(&Felt252::from_bytes_be(b"!(f & t)"))]
+        );
+
+        // Records are exchanged as JSON lines
+        let line = serde_json::to_string(&record).expect("Could not serialize the record");
+        let parsed: ExecutionRecord =
+            serde_json::from_str(&line).expect("Could not parse the record");
+        assert_eq!(parsed, record);
+    }
+}
@@ -0,0 +1,239 @@
+//! Execution and triage of the fuzzed inputs, shared by the workers of `Fuzzer` and by `Campaign`
+use felt::Felt252;
+
+use crate::json::json_parser::Function;
+use crate::mutator::mutator_felt252::Mutator;
+use crate::mutator::strategy::MutationStrategy;
+use crate::runner::runner::{ExecutionResult, Felt252Field, Runner};
+use crate::runner::starknet_runner::RunnerStarknet;
+
+use super::cmplog::CmpLog;
+use super::corpus_store::{now_millis, CorpusEntry};
+use super::dict::Dict;
+use super::resources::ResourceLimits;
+use super::schedule::Schedule;
+use super::stats::{FuzzInput, Statistics};
+use super::utils::hash_vector;
+
+/// Execution of a single input
+#[derive(Debug, Clone)]
+pub struct Step {
+    /// Executed input
+    pub input: FuzzInput,
+    /// Result of the execution, `Err` if the runner could not execute the function
+    pub result: Result<ExecutionResult<Felt252>, String>,
+    /// Set if the input reached new coverage and was added to the corpus
+    pub new_coverage: bool,
+    /// Set if the input is a crashing input never seen before
+    pub new_crash: bool,
+    /// Set if the input is a resource exhaustion finding never seen before
+    pub new_exhaustion: bool,
+    /// Corpus entry of the input and its discovery metadata, set with `new_coverage`
+    pub entry: Option<CorpusEntry>,
+}
+
+/// Picks, mutates, executes and triages the inputs of the fuzzed function
+///
+/// The inputs are mutated and executed by the executor of `cairo_fuzzer_core`, shared
+/// with the cairo-native fuzzer. The corpus and the findings are read from and recorded
+/// in the `Statistics` given to `step`: the workers of `Fuzzer` pass their local
+/// statistics and publish the findings to the shared ones, `Campaign` keeps its own.
+pub struct Executor<R = RunnerStarknet>
+where
+    R: Runner<Felt = Felt252, Field = Felt252Field> + Clone,
+{
+    /// Contract function to fuzz
+    function: Function,
+    executor: cairo_fuzzer_core::executor::Executor<R>,
+}
+
+impl<R> Executor<R>
+where
+    R: Runner<Felt = Felt252, Field = Felt252Field> + Clone,
+{
+    /// Creates an executor running `function` on `runner`
+    /// Every execution of the function gets one felt per argument
+    pub fn new(runner: R, function: Function, seed: u64) -> Self {
+        let mutator = Mutator::new()
+            .max_input_size(function.inputs.len())
+            .types(function.inputs.clone());
+        let executor = cairo_fuzzer_core::executor::Executor::new(runner, mutator, seed)
+            .input_len(function.inputs.len())
+            .path_coverage(true);
+        Executor { function, executor }
+    }
+
+    /// Sets the seed of the mutator and of the corpus selection
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.executor = self.executor.seed(seed);
+        self
+    }
+
+    /// Replaces the runner, e.g. with a runner loaded with a state dump
+    pub fn runner(mut self, runner: R) -> Self {
+        self.executor = self.executor.runner(runner);
+        self
+    }
+
+    /// Feeds the comparison operands recovered by `cmplog` to the mutator
+    pub fn cmplog(mut self, cmplog: CmpLog) -> Self {
+        self.executor = self.executor.cmplog(Box::new(cmplog));
+        self
+    }
+
+    /// Sets the dictionnary used by the mutator
+    pub fn dict(mut self, dict: Dict) -> Self {
+        self.executor = self.executor.dict(dict);
+        self
+    }
+
+    /// Registers a custom mutation strategy
+    pub fn strategy(mut self, strategy: Box<dyn MutationStrategy<Felt252Field>>) -> Self {
+        self.executor = self.executor.strategy(strategy);
+        self
+    }
+
+    /// Sets the power schedule of the corpus entries
+    pub fn schedule(mut self, schedule: Schedule) -> Self {
+        self.executor = self.executor.schedule(schedule);
+        self
+    }
+
+    /// Sets the resource exhaustion detection settings
+    pub fn limits(mut self, limits: ResourceLimits) -> Self {
+        self.executor = self.executor.limits(limits);
+        self
+    }
+
+    /// Contract function fuzzed by the executor
+    pub fn function(&self) -> &Function {
+        &self.function
+    }
+
+    /// Mutator of the executor, e.g. to collect the results of the strategies
+    pub fn mutator(&mut self) -> &mut Mutator {
+        self.executor.mutator()
+    }
+
+    /// Mutates an input of the corpus of `stats` and executes it
+    pub fn step(&mut self, stats: &mut Statistics) -> Step {
+        // The most expensive input may have been found by another worker
+        self.executor.set_max_cost(stats.max_steps);
+        let step = self.executor.step(&mut stats.corpus);
+        self.triage(step, stats)
+    }
+
+    /// Executes `input` without mutating it, e.g. to replay a corpus
+    pub fn execute(&mut self, input: FuzzInput, stats: &mut Statistics) -> Step {
+        self.executor.set_max_cost(stats.max_steps);
+        let step = self.executor.execute(input, &mut stats.corpus);
+        self.triage(step, stats)
+    }
+
+    /// Records the findings of an execution in `stats`
+    fn triage(
+        &self,
+        step: cairo_fuzzer_core::executor::Step<Felt252>,
+        stats: &mut Statistics,
+    ) -> Step {
+        let mut triaged = Step {
+            input: step.input,
+            result: Err(String::new()),
+            new_coverage: step.new_coverage,
+            new_crash: false,
+            new_exhaustion: false,
+            entry: None,
+        };
+        stats.fuzz_cases += 1;
+        let result = match step.result {
+            Ok(result) => result,
+            Err(e) => {
+                stats.tx_crashes += 1;
+                triaged.new_crash = stats.tx_crash_db.insert(triaged.input.clone());
+                triaged.result = Err(e);
+                return triaged;
+            }
+        };
+
+        let cost = step.cost;
+        stats.max_steps = stats.max_steps.max(cost.steps);
+        for (name, count) in &cost.builtins {
+            let max = stats.max_builtins.entry(name.clone()).or_insert(0);
+            *max = (*max).max(*count);
+        }
+        // failure_flag is set if the smart contract raise an error
+        if result.failure_flag {
+            stats.crashes += 1;
+            triaged.new_crash = stats.crash_db.insert(triaged.input.clone());
+        }
+        // Resource exhaustion: the input executed more steps than the threshold
+        if step.exhausted {
+            stats.exhaustions += 1;
+            triaged.new_exhaustion = stats.exhaustion_db.insert(triaged.input.clone());
+        }
+
+        if step.new_coverage {
+            triaged.entry = Some(CorpusEntry {
+                calldata: triaged.input.clone(),
+                function: self.function.name.clone(),
+                discovered: now_millis(),
+                parent: step.parent.map(|parent| hash_vector(&parent)),
+                mutation: step.mutation,
+                coverage: hash_vector(&result.coverage),
+                steps: cost.steps,
+            });
+        }
+        triaged.result = Ok(result);
+        triaged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_lang_starknet::casm_contract_class::CasmContractClass;
+
+    use super::*;
+    use crate::fuzzer::resources::ExecutionCost;
+
+    #[test]
+    fn test_resource_limits() {
+        let casm = std::fs::read_to_string("tests1.0/fuzzinglabs.casm").unwrap();
+        let contract_class: CasmContractClass = serde_json::from_str(&casm).unwrap();
+        let function = Function {
+            name: "Fuzz_symbolic_execution".to_string(),
+            selector_idx: 0,
+            inputs: vec!["core::felt252".to_string(); 11],
+            outputs: Vec::new(),
+        };
+        let input = vec![Felt252::from(0); function.inputs.len()];
+        let runner = RunnerStarknet::new(&contract_class, function.selector_idx);
+        let (_, result) = runner
+            .clone()
+            .run(&input)
+            .expect("Could not run the function");
+        let cost = ExecutionCost::from_result(&result);
+        assert!(cost.steps > 0);
+        let limits = ResourceLimits {
+            max_steps: cost.steps - 1,
+            maximize: false,
+        };
+        assert!(limits.exceeded(&cost));
+        let limits = ResourceLimits {
+            max_steps: 0,
+            maximize: true,
+        };
+        assert!(!limits.exceeded(&cost));
+
+        // An input executing more steps than the threshold is an exhaustion finding
+        let mut executor = Executor::new(runner, function, 0).limits(ResourceLimits {
+            max_steps: cost.steps - 1,
+            maximize: false,
+        });
+        let mut stats = Statistics::default();
+        let step = executor.execute(input.clone(), &mut stats);
+        assert!(step.new_exhaustion);
+        assert!(!executor.execute(input, &mut stats).new_exhaustion);
+        assert_eq!(stats.exhaustions, 2);
+        assert_eq!(stats.max_steps, cost.steps);
+    }
+}
@@ -1,22 +1,39 @@
 use std::{
-    fs::{self, File},
-    process,
+    collections::{BTreeMap, HashSet},
+    fs,
     sync::{Arc, Mutex},
     time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use crate::{
     cli::config::Config,
+    fuzzer::auto_dict::extract_dict,
+    fuzzer::coverage_report::SourceCoverage,
     fuzzer::dict::Dict,
-    fuzzer::starknet_worker::StarknetWorker,
+    fuzzer::differential::{dump_records, record_execution, ExecutionRecord},
+    fuzzer::resources::ResourceLimits,
+    fuzzer::schedule::Schedule,
+    fuzzer::starknet_worker::{StarknetWorker, WorkerOptions},
+    fuzzer::stats_log::{stats_log_path, StatsLog},
     json::json_parser::{get_function_from_json, Function},
+    mutator::strategy::{MutationStrategy, StrategyFactory},
+    runner::runner::{Felt252Field, Runner},
+    runner::starknet_runner::{RunnerStarknet, CONTRACT_ADDRESS},
+    runner::state_dump::{parse_address_map, StateDump},
 };
 
-use super::{corpus_crash::CrashFile, corpus_input::InputFile, stats::Statistics};
+use super::{
+    corpus_crash::CrashFile, corpus_input::InputFile, corpus_store::CorpusStore, stats::Statistics,
+};
+use crate::custom_rand::rng::Rng;
+use cairo_fuzzer_core::coverage::CoverageReport;
+use cairo_fuzzer_core::dashboard::{
+    is_terminal, Dashboard, DashboardState, FunctionProgress, WorkerStatus,
+};
+use cairo_fuzzer_core::metrics::{Heartbeats, Metrics, WorkerMetrics};
+use cairo_fuzzer_core::stats_log::{StatsFormat, StatsRecord, StrategyRecord};
 use cairo_lang_starknet::casm_contract_class::CasmContractClass;
 use felt::Felt252;
-use rand::Rng;
-use std::io::Write;
 
 #[derive(Clone)]
 pub struct Fuzzer {
@@ -34,6 +51,8 @@ pub struct Fuzzer {
     pub function: Function,
     /// Store local/on-disk logs
     pub logs: bool,
+    /// Format of the stats log written in the workspace
+    pub stats_format: StatsFormat,
     /// Replay mode
     pub replay: bool,
     /// Corpus minimization
@@ -44,6 +63,8 @@ pub struct Fuzzer {
     pub workspace: String,
     /// Inputs file path
     pub input_file: Arc<Mutex<InputFile>>,
+    /// Binary corpus the new inputs are appended to
+    pub corpus_store: Arc<Mutex<CorpusStore>>,
     /// Crashes file path
     pub crash_file: Arc<Mutex<CrashFile>>,
     /// Number of second the fuzzing session will last
@@ -58,12 +79,35 @@ pub struct Fuzzer {
     pub proptesting: bool,
     /// Dictionnary struct
     pub dict: Dict,
+    /// Custom mutation strategies, instantiated in each worker
+    pub strategies: Vec<StrategyFactory>,
+    /// Power schedule of the corpus entries
+    pub schedule: Schedule,
+    /// Resource exhaustion detection settings
+    pub limits: ResourceLimits,
+    /// Resource exhaustion findings file path
+    pub exhaustion_file: Arc<Mutex<CrashFile>>,
+    /// Contracts state loaded into the runners
+    pub state: Arc<StateDump>,
+    /// Draw the dashboard instead of the stats lines
+    pub tui: bool,
+    /// Functions of the property testing session, shown by the dashboard
+    pub progress: Vec<FunctionProgress>,
+    /// Metrics served by the metrics endpoint, updated every second
+    pub metrics: Option<Arc<Mutex<Metrics>>>,
+    /// Warnings about the loaded inputs, crashes and state dump
+    pub warnings: Vec<String>,
+    /// Information about the loaded artifacts, printed with the banner
+    pub notes: Vec<String>,
 }
 
 impl Fuzzer {
     /// Create the fuzzer using the given Config struct
-    pub fn new(config: &Config) -> Self {
+    /// Nothing is printed, the caller prints the `warnings` and `notes` of the fuzzer
+    pub fn new(config: &Config) -> Result<Self, String> {
         let stats = Arc::new(Mutex::new(Statistics::default()));
+        let mut warnings = Vec::new();
+        let mut notes = Vec::new();
         // Set seed if provided or generate a new seed using `SystemTime`
         let seed = match config.seed {
             Some(val) => val,
@@ -75,61 +119,113 @@ impl Fuzzer {
 
         // Read contract JSON artifact and get its content
         let contents = fs::read_to_string(&config.contract_file)
-            .expect("Should have been able to read the file");
-        let casm_content = fs::read_to_string(&config.casm_file).expect("Could not read casm file");
-        let function = match get_function_from_json(&contents, &config.function_name) {
-            Some(func) => func,
-            None => {
-                eprintln!("Error: Could not parse json file");
-                process::exit(1)
-            }
-        };
+            .map_err(|e| format!("Could not read {}: {}", config.contract_file, e))?;
+        let casm_content = fs::read_to_string(&config.casm_file)
+            .map_err(|e| format!("Could not read {}: {}", config.casm_file, e))?;
+        let function =
+            get_function_from_json(&contents, &config.function_name).ok_or_else(|| {
+                format!(
+                    "Function {} not found in the contract",
+                    config.function_name
+                )
+            })?;
         // Load inputs from the input file if provided
-        let mut inputs: InputFile =
-            match config.input_file.is_empty() && config.input_folder.is_empty() {
-                true => InputFile::new_from_function(&function, &config.workspace),
-                false => match config.input_folder.is_empty() {
-                    true => InputFile::load_from_file(&config.input_file, &config.workspace),
-                    false => InputFile::load_from_folder(&config.input_folder, &config.workspace),
-                },
-            };
+        let mut inputs = match config.input_file.is_empty() && config.input_folder.is_empty() {
+            true => InputFile::new_from_function(&function, &config.workspace),
+            false => match config.input_folder.is_empty() {
+                true => InputFile::load_from_file(&config.input_file, &config.workspace)?,
+                false => InputFile::load_from_folder(&config.input_folder, &config.workspace)?,
+            },
+        };
+        // Check the loaded inputs against the function signature
+        let diagnostics = inputs.check_function(&function);
+        let errors: Vec<&str> = diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.error)
+            .map(|diagnostic| diagnostic.message.as_str())
+            .collect();
+        if config.adapt_inputs && !diagnostics.is_empty() {
+            warnings.extend(diagnostics.iter().map(|d| d.message.clone()));
+            let adapted = inputs.adapt_to_function(&function);
+            notes.push(format!("{} inputs adapted to {}", adapted, function.name));
+        } else if !errors.is_empty() {
+            return Err(format!(
+                "{}\nthe loaded inputs do not match the arguments of {}, use --adapt-inputs to pad or truncate them",
+                errors.join("\n"),
+                function.name
+            ));
+        } else {
+            warnings.extend(diagnostics.iter().map(|d| d.message.clone()));
+        }
+
+        let contract_class: CasmContractClass = serde_json::from_str(&casm_content)
+            .map_err(|e| format!("Could not parse {}: {}", config.casm_file, e))?;
+
+        let state = load_state(&config.state_dump, &config.address_map, &mut warnings)?;
+        if !config.state_dump.is_empty() {
+            notes.push(format!(
+                "State dump: {} contracts loaded",
+                state.contracts.len()
+            ));
+        }
 
-        let dict = match &config.dict.is_empty() {
-            true => Dict { inputs: Vec::new() },
-            false => Dict::read_dict(&config.dict),
+        let mut dict = match &config.dict.is_empty() {
+            true => Dict::default(),
+            false => {
+                notes.push(format!("Reading and parsing dict: {}", config.dict));
+                Dict::read_dict(&config.dict)?
+            }
         };
 
-        let nbr_args = function.inputs.len();
-        for val in &dict.inputs {
-            let mut value_vec: Vec<Felt252> = Vec::new();
-            value_vec.push(val.clone()); // to ensure that all values of the dict will be in the inputs vector
-            for _ in 0..nbr_args - 1 {
-                value_vec
-                    .push(dict.inputs[rand::thread_rng().gen_range(0..dict.inputs.len())].clone());
+        // Seed the corpus with the dictionary values, using the fuzzer seed for determinism
+        inputs
+            .inputs
+            .extend(dict.seed_inputs(&function.inputs, &Rng::seeded(seed)));
+
+        // Add the contract constants to the dictionary used by the mutator
+        if config.auto_dict {
+            let auto_dict = extract_dict(&contents, &contract_class);
+            notes.push(format!(
+                "Automatic dictionnary: {} values",
+                auto_dict.entries.len()
+            ));
+            if !config.dump_dict.is_empty() {
+                auto_dict.dump(&config.dump_dict)?;
             }
-            inputs.inputs.push(value_vec);
+            dict.entries.extend(auto_dict.entries);
         }
 
         // Load existing inputs in shared database
         if inputs.inputs.len() > 0 {
             let mut stats_db = stats.lock().expect("Failed to lock stats mutex");
             for input in &inputs.inputs {
-                if stats_db.input_db.insert(input.clone()) {
-                    stats_db.input_db.insert(input.clone());
-                    stats_db.input_len += 1;
-                }
+                stats_db.corpus.add_input(input);
             }
         }
 
-        // Load crashes from the crash file if provided
-        let crashes: CrashFile =
-            match config.crash_file.is_empty() && config.crash_folder.is_empty() {
-                true => CrashFile::new_from_function(&function, &config.workspace),
-                false => match config.input_folder.is_empty() {
-                    true => CrashFile::load_from_file(&config.input_file, &config.workspace),
-                    false => CrashFile::load_from_folder(&config.input_folder, &config.workspace),
-                },
-            };
+        // Load crashes from the crash file or folder if provided
+        let loaded = match (config.crash_file.is_empty(), config.crash_folder.is_empty()) {
+            (true, true) => None,
+            (false, true) => Some(CrashFile::load_from_file(
+                &config.crash_file,
+                &config.workspace,
+            )?),
+            (_, false) => {
+                CrashFile::load_from_folder(&config.crash_folder, &config.workspace, &mut warnings)?
+            }
+        };
+        let crashes: CrashFile = match loaded {
+            Some(mut crashes) => {
+                crashes.check_function(&function)?;
+                // The crashes of a folder are dumped with the new ones in the workspace
+                if !config.crash_folder.is_empty() {
+                    crashes.path = CrashFile::new_from_function(&function, &config.workspace).path;
+                }
+                crashes
+            }
+            // No crash file was loaded
+            None => CrashFile::new_from_function(&function, &config.workspace),
+        };
 
         // Load existing crashes in shared database
         if crashes.crashes.len() > 0 {
@@ -140,26 +236,24 @@ impl Fuzzer {
             }
         }
 
-        let contract_class =
-            serde_json::from_str(&casm_content).expect("could not get contractclass");
-        println!(
-            "
-        =============================================================================================================================================================
-        ╔═╗ ┌─┐ ┬ ┬─┐ ┌───┐   ╔═╗ ┬ ┬ ┌─┐ ┌─┐ ┌─┐ ┬─┐      | Seed -- {}
-        ║   ├─┤ │ ├┬┘ │1.0│───╠╣  │ │ ┌─┘ ┌─┘ ├┤  ├┬┘      | Inputs loaded -- {}
-        ╚═╝ ┴ ┴ ┴ ┴└─ └───┘   ╚   └─┘ └─┘ └─┘ └─┘ ┴└─      | Threads to run -- {}",
-            seed,
-            inputs.inputs.len(),
-            config.cores,
-        );
+        // The dashboard needs a terminal, the stats lines are kept otherwise
+        let tui = config.tui && is_terminal();
+        stats.lock().expect("Failed to lock stats mutex").tui = tui;
+
         // Setup the mutex for the inputs corpus and crash corpus
         let inputs = Arc::new(Mutex::new(inputs));
         let crashes = Arc::new(Mutex::new(crashes));
+        let exhaustions = Arc::new(Mutex::new(CrashFile::new_with_prefix(
+            &function,
+            &config.workspace,
+            "EXHAUSTIONS",
+        )));
         // Setup the fuzzer
-        Fuzzer {
+        Ok(Fuzzer {
             stats: stats,
             cores: config.cores,
             logs: config.logs,
+            stats_format: config.stats_format,
             run_time: config.run_time,
             replay: config.replay,
             minimizer: config.minimizer,
@@ -171,11 +265,49 @@ impl Fuzzer {
             start_time: Instant::now(),
             seed: seed,
             input_file: inputs,
+            corpus_store: Arc::new(Mutex::new(CorpusStore::new_from_function(
+                &function,
+                &config.workspace,
+            ))),
             crash_file: crashes,
             workspace: config.workspace.clone(),
             running_workers: 0,
             iter: config.iter,
             proptesting: config.proptesting,
+            strategies: Vec::new(),
+            schedule: config.schedule,
+            limits: ResourceLimits {
+                max_steps: config.max_steps,
+                maximize: config.maximize_steps,
+            },
+            exhaustion_file: exhaustions,
+            state: Arc::new(state),
+            tui,
+            progress: Vec::new(),
+            metrics: None,
+            warnings,
+            notes,
+        })
+    }
+
+    /// Registers a custom mutation strategy
+    /// `factory` is called once per worker to create its own instance of the strategy
+    pub fn add_strategy<F>(&mut self, factory: F)
+    where
+        F: Fn() -> Box<dyn MutationStrategy<Felt252Field>> + Send + Sync + 'static,
+    {
+        self.strategies.push(Arc::new(factory));
+    }
+
+    /// Settings shared by all the workers
+    fn worker_options(&self) -> WorkerOptions {
+        WorkerOptions {
+            dict: self.dict.clone(),
+            strategies: self.strategies.clone(),
+            schedule: self.schedule,
+            limits: self.limits,
+            exhaustion_file: self.exhaustion_file.clone(),
+            state: self.state.clone(),
         }
     }
 
@@ -186,12 +318,12 @@ impl Fuzzer {
             // create dedicated statistics per thread
             let stats = self.stats.clone();
             let function = self.function.clone();
-            let input_file = self.input_file.clone();
+            let corpus_store = self.corpus_store.clone();
             let crash_file = self.crash_file.clone();
             let contract_class = self.contract_class.clone();
             let seed = self.seed + (i as u64);
             let iter = self.iter;
-            //let dict = self.dict.clone();
+            let options = self.worker_options();
             // Spawn threads
             std::thread::spawn(move || {
                 let starknet_worker = StarknetWorker::new(
@@ -200,9 +332,10 @@ impl Fuzzer {
                     contract_class,
                     function,
                     seed,
-                    input_file,
+                    corpus_store,
                     crash_file,
                     iter,
+                    options,
                 );
                 starknet_worker.fuzz();
             });
@@ -219,7 +352,8 @@ impl Fuzzer {
         // Replay all inputs
         let stats_db = self.stats.lock().expect("Failed to lock stats mutex");
         // Load inputs
-        let mut corpus_hashset = stats_db.input_db.clone();
+        let mut corpus_hashset: HashSet<Vec<Felt252>> =
+            stats_db.corpus.inputs().iter().cloned().collect();
         println!(
             "\t\t\t\t\t\t\t   | Total inputs to replay -- {}",
             corpus_hashset.len()
@@ -248,11 +382,11 @@ impl Fuzzer {
             let stats_thread = self.stats.clone();
             let function = self.function.clone();
             let seed = self.seed;
-            let input_file = self.input_file.clone();
+            let corpus_store = self.corpus_store.clone();
             let crash_file = self.crash_file.clone();
             let contract_class = self.contract_class.clone();
             let iter = if self.proptesting { self.iter } else { 0 };
-            //let dict = self.dict.clone();
+            let options = self.worker_options();
             let chunk = chunks[i].clone();
             threads.push(std::thread::spawn(move || {
                 let mut starknet_worker = StarknetWorker::new(
@@ -261,9 +395,10 @@ impl Fuzzer {
                     contract_class,
                     function,
                     seed,
-                    input_file,
+                    corpus_store,
                     crash_file,
                     iter,
+                    options,
                 );
                 starknet_worker.replay(chunk);
             }));
@@ -290,7 +425,7 @@ impl Fuzzer {
                 inputs: Vec::<Vec<Felt252>>::new(),
             };
             // Push every input to the struct
-            for input in &stats.input_db {
+            for input in stats.corpus.inputs() {
                 dump_inputs.inputs.push(input.clone().to_vec());
             }
             println!("Size after minimization : {}", dump_inputs.inputs.len());
@@ -299,74 +434,333 @@ impl Fuzzer {
         }
     }
 
+    /// Executes the inputs and crashes of the corpus on a fresh state and writes the
+    /// results to `path`, to be compared with cairo-native by `cairo-native-fuzzer --differential`
+    /// Returns the number of executed inputs
+    pub fn dump_executions(&self, path: &str) -> Result<usize, String> {
+        let stats = self.stats.lock().expect("Failed to lock stats mutex");
+        let mut corpus: Vec<Vec<Felt252>> = stats
+            .corpus
+            .inputs()
+            .iter()
+            .chain(&stats.crash_db)
+            .cloned()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        drop(stats);
+        // Sort the inputs so the dump is deterministic
+        corpus.sort();
+
+        let records: Vec<ExecutionRecord> = corpus
+            .iter()
+            .map(|input| {
+                record_execution(
+                    &self.contract_class,
+                    self.function.selector_idx,
+                    &self.function.name,
+                    input,
+                )
+            })
+            .collect();
+        dump_records(path, &records)?;
+        Ok(records.len())
+    }
+
+    /// Executes the inputs and crashes of the corpus on a fresh state and returns the
+    /// coverage of the contract source lines and functions
+    pub fn source_coverage(&self) -> Result<CoverageReport, String> {
+        let mut coverage = SourceCoverage::new(
+            &self.contract_content,
+            &self.contract_class,
+            self.function.selector_idx,
+        )?;
+        let stats = self.stats.lock().expect("Failed to lock stats mutex");
+        let corpus: HashSet<Vec<Felt252>> = stats
+            .corpus
+            .inputs()
+            .iter()
+            .chain(&stats.crash_db)
+            .cloned()
+            .collect();
+        drop(stats);
+
+        for input in &corpus {
+            let runner = RunnerStarknet::new_with_state(
+                &self.contract_class,
+                self.function.selector_idx,
+                &self.state,
+            );
+            if let Ok((_, result)) = runner.run(input) {
+                let pcs: Vec<usize> = result.coverage.iter().map(|pc| *pc as usize).collect();
+                coverage.add_trace(&pcs);
+            }
+        }
+        Ok(coverage.report())
+    }
+
     /// Function to print stats of the running fuzzer
     fn monitor(&self) {
         let mut log = None;
         if self.logs {
-            log = Some(File::create("fuzz_stats.txt").expect("Failed to lock stats mutex"));
+            match StatsLog::create(
+                &stats_log_path(&self.workspace, &self.function.name, self.stats_format),
+                self.stats_format,
+            ) {
+                Ok(stats_log) => log = Some(stats_log),
+                Err(e) => eprintln!("Error: {}", e),
+            }
         }
+        // Uptime and executions at the last tick
+        let mut last_tick = (0.0, 0);
+        let mut heartbeats = Heartbeats::default();
+
+        let mut dashboard = Dashboard::default();
 
         // Monitoring loop
+        let mut ticks: u64 = 0;
         loop {
             // wait 1 second
             std::thread::sleep(Duration::from_millis(1000));
+            ticks += 1;
 
             // Get uptime
             let uptime = (Instant::now() - self.start_time).as_secs_f64();
 
-            // Get access to the global stats
-            {
+            // Snapshot the global stats, the lock is released before drawing or printing
+            // so the workers are not blocked by the terminal
+            let (snapshot, record, metrics, finished) = {
                 let stats = self.stats.lock().expect("Failed to lock stats mutex");
 
                 // number of executions
                 let fuzz_case = stats.fuzz_cases;
-                print!(
-                    "{:12.2} uptime | {:9} fuzz cases | {:12.2} fcps | \
-                            {:6} coverage | {:6} inputs | {:6} crashes [{:6} unique] | {:6} TX_crashes [{:6} unique]\n",
+                // A worker is running while its executions counter progresses
+                let executions: BTreeMap<usize, u64> = stats
+                    .worker_executions
+                    .iter()
+                    .map(|(id, executions)| (*id as usize, *executions))
+                    .collect();
+                let workers = heartbeats.update(uptime, &executions, stats.stop);
+                let snapshot = match self.tui {
+                    true => Snapshot::Dashboard(self.dashboard_state(&stats, uptime, &workers)),
+                    false => Snapshot::Lines(monitor_lines(&stats, uptime, ticks)),
+                };
+                // Executions per second since the last tick
+                let (last_uptime, last_fuzz_case) = last_tick;
+                let execs_per_sec = (fuzz_case - last_fuzz_case) as f64 / (uptime - last_uptime);
+                last_tick = (uptime, fuzz_case);
+                let record = log
+                    .as_ref()
+                    .map(|_| stats_record(&stats, uptime, execs_per_sec));
+                let metrics = self.metrics.as_ref().map(|_| Metrics {
+                    function: self.function.name.clone(),
                     uptime,
-                    fuzz_case,
-                    fuzz_case as f64 / uptime,
-                    stats.coverage_db.len(),
-                    stats.input_len,
-                    stats.crashes,
-                    stats.crash_db.len(),
-                    stats.tx_crashes,
-                    stats.tx_crash_db.len()
-                );
-                // Writing inside logging file
-                if let Some(ref mut file) = log {
-                    write!(
-                        file,
-                        "{:12.0} {:7} {:8} {:5} {:6} {:6} {:6} {:6}\n",
-                        uptime,
-                        fuzz_case,
-                        stats.coverage_db.len(),
-                        stats.input_len,
-                        stats.crashes,
-                        stats.crash_db.len(),
-                        stats.tx_crashes,
-                        stats.tx_crash_db.len()
-                    )
-                    .expect("Failed to write logs in log file");
-                    file.flush().expect("Failed to flush the file");
-                }
-
+                    executions: fuzz_case,
+                    execs_per_sec,
+                    coverage: stats.corpus.coverage(),
+                    corpus: stats.corpus.len(),
+                    crashes: stats.crashes + stats.tx_crashes,
+                    unique_crashes: stats.crash_db.len() + stats.tx_crash_db.len(),
+                    workers: workers.clone(),
+                });
                 // Only for replay: all thread are finished
-                if (self.replay && stats.threads_finished == self.running_workers)
-                    || (self.iter < fuzz_case as i64 && self.iter != -1)
-                {
-                    break;
+                let finished = (self.replay && stats.threads_finished == self.running_workers)
+                    || (self.iter < fuzz_case as i64 && self.iter != -1);
+                (snapshot, record, metrics, finished)
+            };
+
+            match snapshot {
+                Snapshot::Dashboard(state) => {
+                    dashboard.update(&state);
+                    if let Err(e) = dashboard.draw(&state) {
+                        eprintln!("Error: Could not draw the dashboard: {}", e);
+                    }
+                }
+                Snapshot::Lines(lines) => print!("{}", lines),
+            }
+            // Append the stats to the stats log
+            if let (Some(stats_log), Some(record)) = (&mut log, record) {
+                if let Err(e) = stats_log.write(&record) {
+                    eprintln!("Error: {}", e);
+                    log = None;
                 }
             }
+            // Update the metrics of the metrics endpoint
+            if let (Some(shared), Some(metrics)) = (&self.metrics, metrics) {
+                *shared.lock().expect("Failed to lock metrics mutex") = metrics;
+            }
+            if finished {
+                break;
+            }
 
             // time over, fuzzing session is finished
             if let Some(run_time) = self.run_time {
                 if uptime > run_time as f64 {
-                    process::exit(0);
+                    break;
                 }
             }
         }
+        // Stop the workers still running
+        self.stats.lock().expect("Failed to lock stats mutex").stop = true;
+        if let Some(metrics) = &self.metrics {
+            let mut metrics = metrics.lock().expect("Failed to lock metrics mutex");
+            for worker in &mut metrics.workers {
+                worker.stopped = true;
+            }
+        }
+    }
+
+    /// Snapshot of the session drawn by the dashboard
+    fn dashboard_state(
+        &self,
+        stats: &Statistics,
+        uptime: f64,
+        workers: &[WorkerMetrics],
+    ) -> DashboardState {
+        let mut functions = self.progress.clone();
+        for function in &mut functions {
+            if function.name == self.function.name {
+                function.executions = stats.fuzz_cases;
+            }
+        }
+        DashboardState {
+            title: format!("cairo-fuzzer -- {}", self.function.name),
+            uptime,
+            executions: stats.fuzz_cases,
+            coverage: stats.corpus.coverage(),
+            corpus: stats.corpus.len(),
+            crashes: stats.crashes + stats.tx_crashes,
+            crash_buckets: stats.crash_triage.buckets(),
+            workers: workers
+                .iter()
+                .map(|worker| WorkerStatus {
+                    id: worker.id,
+                    executions: worker.executions,
+                    running: worker.is_up(),
+                })
+                .collect(),
+            functions,
+            last_crash: stats.last_crash.clone(),
+        }
+    }
+}
+
+/// Output of a monitor tick, taken under the stats lock and drawn after releasing it
+enum Snapshot {
+    Dashboard(DashboardState),
+    Lines(String),
+}
+
+/// Plain lines printed by the monitor at `uptime`
+/// The results of the strategies and the resources are printed every 10 ticks
+fn monitor_lines(stats: &Statistics, uptime: f64, ticks: u64) -> String {
+    let fuzz_case = stats.fuzz_cases;
+    let mut lines = format!(
+        "{:12.2} uptime | {:9} fuzz cases | {:12.2} fcps | \
+            {:6} coverage | {:6} inputs | {:6} crashes [{:6} unique] | {:6} TX_crashes [{:6} unique]\n",
+        uptime,
+        fuzz_case,
+        fuzz_case as f64 / uptime,
+        stats.corpus.coverage(),
+        stats.corpus.len(),
+        stats.crashes,
+        stats.crash_db.len(),
+        stats.tx_crashes,
+        stats.tx_crash_db.len()
+    );
+    // Results of the mutation strategies
+    if ticks % 10 == 0 && !stats.strategy_stats.is_empty() {
+        let strategies: Vec<String> = stats
+            .strategy_stats
+            .iter()
+            .map(|(name, s)| format!("{} {}/{}", name, s.finds, s.uses))
+            .collect();
+        lines += &format!(
+            "{:>12} finds/uses | {}\n",
+            "strategies",
+            strategies.join(" | ")
+        );
+    }
+    // Resources of the most expensive executions
+    if ticks % 10 == 0 && stats.max_steps > 0 {
+        let builtins: Vec<String> = stats
+            .max_builtins
+            .iter()
+            .map(|(name, count)| format!("{} {}", name, count))
+            .collect();
+        lines += &format!(
+            "{:>12} max steps | {:6} exhaustions [{:6} unique] | {}\n",
+            stats.max_steps,
+            stats.exhaustions,
+            stats.exhaustion_db.len(),
+            builtins.join(" | ")
+        );
+    }
+    lines
+}
+
+/// Record of the stats log at `uptime`
+fn stats_record(stats: &Statistics, uptime: f64, execs_per_sec: f64) -> StatsRecord {
+    StatsRecord {
+        time: uptime,
+        execs: stats.fuzz_cases,
+        execs_per_sec,
+        coverage: stats.corpus.coverage(),
+        corpus: stats.corpus.len(),
+        crashes: stats.crashes,
+        unique_crashes: stats.crash_db.len(),
+        tx_crashes: stats.tx_crashes,
+        unique_tx_crashes: stats.tx_crash_db.len(),
+        crash_buckets: stats.crash_triage.buckets().into_iter().collect(),
+        strategies: stats
+            .strategy_stats
+            .iter()
+            .map(|(name, s)| {
+                let record = StrategyRecord {
+                    uses: s.uses,
+                    finds: s.finds,
+                };
+                (name.clone(), record)
+            })
+            .collect(),
+    }
+}
+
+/// Loads the state dump at `path` and maps its addresses, an empty path is an empty state
+/// The contracts which can't be loaded as in the dump are reported in `warnings`
+pub fn load_state(
+    path: &str,
+    address_map: &[String],
+    warnings: &mut Vec<String>,
+) -> Result<StateDump, String> {
+    if path.is_empty() {
+        return Ok(StateDump::default());
+    }
+    let mut state = StateDump::load(path)?;
+    let map = address_map
+        .iter()
+        .map(|mapping| parse_address_map(mapping))
+        .collect::<Result<Vec<_>, _>>()?;
+    state.map_addresses(&map);
+    if !state
+        .contracts
+        .contains_key(&Felt252::from(CONTRACT_ADDRESS))
+    {
+        warnings.push(format!(
+            "the state dump has no contract at {}, the address of the fuzzed contract, use --map-address <ADDRESS>={} to load the state of its deployed instance",
+            CONTRACT_ADDRESS, CONTRACT_ADDRESS
+        ));
+    }
+    for (address, class_hash) in state.missing_classes() {
+        // The fuzzed contract keeps its own class
+        if address != Felt252::from(CONTRACT_ADDRESS) {
+            warnings.push(format!(
+                "the class {} of contract {} is not in the classes of the state dump, its class hash is not loaded",
+                class_hash, address
+            ));
+        }
     }
+    Ok(state)
 }
 
 #[cfg(test)]
@@ -375,23 +769,30 @@ mod tests {
     use std::{thread, time::Duration};
 
     use crate::cli::config::Config;
+    use crate::fuzzer::schedule::Schedule;
+    use crate::runner::runner::Engine;
 
     use super::Fuzzer;
+    use cairo_fuzzer_core::stats_log::StatsFormat;
     #[test]
     fn test_loading_config_file() {
         let config_file = "tests1.0/config.json".to_string();
         let config = Config::load_config(&config_file);
-        let fuzzer = Fuzzer::new(&config);
+        let fuzzer = Fuzzer::new(&config).unwrap();
         assert_eq!(fuzzer.cores, 1);
         assert_eq!(fuzzer.logs, false);
         assert_eq!(fuzzer.function.name, "Fuzz_symbolic_execution");
+        // The loading errors are returned instead of exiting
+        let mut config = config;
+        config.function_name = "not_a_function".to_string();
+        assert!(Fuzzer::new(&config).is_err());
     }
 
     #[test]
     fn test_run_fuzzer_from_config_file() {
         let config_file = "tests1.0/config.json".to_string();
         let config = Config::load_config(&config_file);
-        let mut fuzzer = Fuzzer::new(&config);
+        let mut fuzzer = Fuzzer::new(&config).unwrap();
         // Create a new thread
         let handle = thread::spawn(move || {
             fuzzer.run_time = Some(10);
@@ -445,8 +846,22 @@ mod tests {
             iter,
             proptesting,
             dict,
+            auto_dict: false,
+            dump_dict: "".to_string(),
+            schedule: Schedule::Uniform,
+            max_steps: 0,
+            maximize_steps: false,
+            diff_dump: "".to_string(),
+            engine: Engine::Vm,
+            adapt_inputs: false,
+            state_dump: "".to_string(),
+            address_map: Vec::new(),
+            coverage_report: "".to_string(),
+            tui: false,
+            stats_format: StatsFormat::Csv,
+            metrics: "".to_string(),
         };
-        let fuzzer = Fuzzer::new(&config);
+        let fuzzer = Fuzzer::new(&config).unwrap();
         assert_eq!(fuzzer.cores, 1);
         assert_eq!(fuzzer.logs, false);
         assert_eq!(fuzzer.function.name, "Fuzz_symbolic_execution");
@@ -489,9 +904,23 @@ mod tests {
             iter,
             proptesting,
             dict,
+            auto_dict: false,
+            dump_dict: "".to_string(),
+            schedule: Schedule::Uniform,
+            max_steps: 0,
+            maximize_steps: false,
+            diff_dump: "".to_string(),
+            engine: Engine::Vm,
+            adapt_inputs: false,
+            state_dump: "".to_string(),
+            address_map: Vec::new(),
+            coverage_report: "".to_string(),
+            tui: false,
+            stats_format: StatsFormat::Csv,
+            metrics: "".to_string(),
         };
         // create the fuzzer
-        let mut fuzzer = Fuzzer::new(&config);
+        let mut fuzzer = Fuzzer::new(&config).unwrap();
 
         // Create a new thread
         let handle = thread::spawn(move || {
@@ -546,9 +975,23 @@ mod tests {
             iter,
             proptesting,
             dict,
+            auto_dict: false,
+            dump_dict: "".to_string(),
+            schedule: Schedule::Uniform,
+            max_steps: 0,
+            maximize_steps: false,
+            diff_dump: "".to_string(),
+            engine: Engine::Vm,
+            adapt_inputs: false,
+            state_dump: "".to_string(),
+            address_map: Vec::new(),
+            coverage_report: "".to_string(),
+            tui: false,
+            stats_format: StatsFormat::Csv,
+            metrics: "".to_string(),
         };
         // create the fuzzer
-        let mut fuzzer = Fuzzer::new(&config);
+        let mut fuzzer = Fuzzer::new(&config).unwrap();
 
         // Create a new thread
         let handle = thread::spawn(move || {
@@ -604,14 +1047,28 @@ mod tests {
             iter,
             proptesting,
             dict,
+            auto_dict: false,
+            dump_dict: "".to_string(),
+            schedule: Schedule::Uniform,
+            max_steps: 0,
+            maximize_steps: false,
+            diff_dump: "".to_string(),
+            engine: Engine::Vm,
+            adapt_inputs: false,
+            state_dump: "".to_string(),
+            address_map: Vec::new(),
+            coverage_report: "".to_string(),
+            tui: false,
+            stats_format: StatsFormat::Csv,
+            metrics: "".to_string(),
         };
         // create the fuzzer
-        let mut fuzzer = Fuzzer::new(&config);
+        let mut fuzzer = Fuzzer::new(&config).unwrap();
 
         fuzzer.replay();
 
         let stats = fuzzer.stats.lock().expect("Failed to lock stats mutex");
-        assert_ne!(stats.coverage_db.len(), 0);
+        assert_ne!(stats.corpus.coverage(), 0);
     }
 
     #[test]
@@ -652,27 +1109,41 @@ mod tests {
             iter,
             proptesting,
             dict,
+            auto_dict: false,
+            dump_dict: "".to_string(),
+            schedule: Schedule::Uniform,
+            max_steps: 0,
+            maximize_steps: false,
+            diff_dump: "".to_string(),
+            engine: Engine::Vm,
+            adapt_inputs: false,
+            state_dump: "".to_string(),
+            address_map: Vec::new(),
+            coverage_report: "".to_string(),
+            tui: false,
+            stats_format: StatsFormat::Csv,
+            metrics: "".to_string(),
         };
         // create the fuzzer
-        let mut fuzzer = Fuzzer::new(&config);
+        let mut fuzzer = Fuzzer::new(&config).unwrap();
 
         fuzzer.replay();
 
         let stats = fuzzer.stats.lock().expect("Failed to lock stats mutex");
-        assert_ne!(stats.coverage_db.len(), 0);
+        assert_ne!(stats.corpus.coverage(), 0);
     }
 
     #[test]
     fn test_dict() {
         let config_file = "tests1.0/config.json".to_string();
         let config = Config::load_config(&config_file);
-        let fuzzer = Fuzzer::new(&config);
+        let fuzzer = Fuzzer::new(&config).unwrap();
         assert_ne!(
             fuzzer
                 .stats
                 .lock()
                 .expect("Failed to lock stats mutex")
-                .input_db
+                .corpus
                 .len(),
             0
         );
@@ -0,0 +1,230 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use cairo_lang_starknet::casm_contract_class::CasmContractClass;
+
+use crate::json::json_parser::Function;
+use crate::runner::runner::Runner;
+use crate::runner::starknet_runner::RunnerStarknet;
+use crate::runner::state_dump::StateDump;
+
+use super::corpus_store::CorpusEntry;
+use super::schedule::trace_edges;
+use super::stats::FuzzInput;
+use super::utils::hash_vector;
+
+/// Inputs of a corpus to merge, loaded from a file or a folder
+pub struct MergeSource {
+    /// Path of the file or folder
+    pub path: String,
+    pub inputs: Vec<FuzzInput>,
+}
+
+/// Contribution of a source to the merged corpus
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceReport {
+    /// Path of the file or folder
+    pub path: String,
+    /// Number of inputs of the source
+    pub inputs: usize,
+    /// Number of inputs that could not be executed or do not match the function arguments
+    pub invalid: usize,
+    /// Number of edges covered by the inputs of the source
+    pub edges: usize,
+    /// Number of edges covered only by this source
+    pub unique_edges: usize,
+    /// Number of inputs of the source kept in the merged corpus
+    pub kept: usize,
+}
+
+/// Result of a corpus merge
+#[derive(Debug, Clone, Default)]
+pub struct MergeReport {
+    /// Contribution of each source, in the order of the sources
+    pub sources: Vec<SourceReport>,
+    /// Number of edges covered by the union of the sources
+    pub edges: usize,
+    /// Minimal set of inputs covering the same edges, with their coverage and steps
+    pub kept: Vec<CorpusEntry>,
+}
+
+/// An input of the sources and its execution
+struct Candidate {
+    input: FuzzInput,
+    edges: Vec<u64>,
+    steps: u64,
+    /// Index of the first source containing the input
+    source: usize,
+}
+
+/// Replays the inputs of the sources on `state` and selects a minimal set of inputs preserving the
+/// union of their covered edges
+///
+/// The selection is a greedy set cover: the input covering the most edges not covered
+/// yet is kept first, preferring the inputs executing fewer Cairo steps, until every edge
+/// is covered. Each input is executed on a fresh state so its coverage does not depend
+/// on the order of the sources.
+pub fn merge(
+    contract_class: &CasmContractClass,
+    function: &Function,
+    state: &StateDump,
+    sources: &[MergeSource],
+) -> MergeReport {
+    let mut reports: Vec<SourceReport> = Vec::new();
+    let mut candidates: Vec<Candidate> = Vec::new();
+    // Index of each input in `candidates`, an input shared by several sources is run once
+    let mut known: HashMap<FuzzInput, usize> = HashMap::new();
+    // Sources covering each edge
+    let mut edge_sources: HashMap<u64, HashSet<usize>> = HashMap::new();
+    for (source_idx, source) in sources.iter().enumerate() {
+        let mut report = SourceReport {
+            path: source.path.clone(),
+            inputs: source.inputs.len(),
+            ..Default::default()
+        };
+        let mut source_edges: HashSet<u64> = HashSet::new();
+        for input in &source.inputs {
+            let edges = match known.get(input) {
+                Some(&idx) => candidates[idx].edges.clone(),
+                None => {
+                    if input.len() != function.inputs.len() {
+                        report.invalid += 1;
+                        continue;
+                    }
+                    let runner = RunnerStarknet::new_with_state(
+                        contract_class,
+                        function.selector_idx,
+                        state,
+                    );
+                    match runner.run(input) {
+                        Ok((_, result)) => {
+                            let mut edges =
+                                trace_edges(result.coverage.iter().map(|pc| *pc as usize));
+                            edges.sort();
+                            known.insert(input.clone(), candidates.len());
+                            candidates.push(Candidate {
+                                input: input.clone(),
+                                edges: edges.clone(),
+                                steps: result.steps,
+                                source: source_idx,
+                            });
+                            edges
+                        }
+                        Err(_) => {
+                            report.invalid += 1;
+                            continue;
+                        }
+                    }
+                }
+            };
+            source_edges.extend(edges);
+        }
+        for edge in &source_edges {
+            edge_sources.entry(*edge).or_default().insert(source_idx);
+        }
+        report.edges = source_edges.len();
+        reports.push(report);
+    }
+    for sources in edge_sources.values() {
+        if sources.len() == 1 {
+            let source_idx = *sources.iter().next().expect("Empty edge sources");
+            reports[source_idx].unique_edges += 1;
+        }
+    }
+
+    // Lazy greedy set cover: the gain of an input can only decrease as edges get covered,
+    // so a popped input whose gain is still up to date is the best one
+    let mut covered: HashSet<u64> = HashSet::new();
+    let mut heap: BinaryHeap<(usize, Reverse<u64>, Reverse<usize>)> = candidates
+        .iter()
+        .enumerate()
+        .map(|(idx, candidate)| {
+            (
+                candidate.edges.len(),
+                Reverse(candidate.steps),
+                Reverse(idx),
+            )
+        })
+        .collect();
+    let mut kept = Vec::new();
+    while let Some((gain, steps, Reverse(idx))) = heap.pop() {
+        if gain == 0 {
+            break;
+        }
+        let candidate = &candidates[idx];
+        let new_gain = candidate
+            .edges
+            .iter()
+            .filter(|edge| !covered.contains(edge))
+            .count();
+        if new_gain < gain {
+            heap.push((new_gain, steps, Reverse(idx)));
+            continue;
+        }
+        covered.extend(candidate.edges.iter().copied());
+        reports[candidate.source].kept += 1;
+        let mut entry = CorpusEntry::new(&function.name, candidate.input.clone());
+        entry.coverage = hash_vector(&candidate.edges);
+        entry.steps = candidate.steps;
+        kept.push(entry);
+    }
+
+    MergeReport {
+        sources: reports,
+        edges: edge_sources.len(),
+        kept,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use felt::Felt252;
+
+    #[test]
+    fn test_merge() {
+        let casm = std::fs::read_to_string("tests1.0/fuzzinglabs.casm").unwrap();
+        let contract_class: CasmContractClass = serde_json::from_str(&casm).unwrap();
+        let function = Function {
+            name: "Fuzz_symbolic_execution".to_string(),
+            selector_idx: 0,
+            inputs: [
+                "felt252",
+                "felt252",
+                "integer::u16",
+                "integer::u32",
+                "integer::u64",
+                "integer::u128",
+                "integer::u128",
+                "integer::u128",
+                "felt252",
+                "felt252",
+                "integer::u8",
+            ]
+            .iter()
+            .map(|ty| format!("core::{}", ty))
+            .collect(),
+            outputs: Vec::new(),
+        };
+        let zeros = vec![Felt252::from(0); function.inputs.len()];
+        let mut other = zeros.clone();
+        other[0] = Felt252::from(b'f');
+        let sources = vec![
+            MergeSource {
+                path: "first".to_string(),
+                inputs: vec![zeros.clone(), zeros.clone(), vec![Felt252::from(0)]],
+            },
+            MergeSource {
+                path: "second".to_string(),
+                inputs: vec![zeros.clone(), other],
+            },
+        ];
+        let report = merge(&contract_class, &function, &StateDump::default(), &sources);
+        assert_eq!(report.sources[0].invalid, 1);
+        assert_eq!(report.sources[0].unique_edges, 0);
+        assert!(report.edges >= report.sources[1].edges);
+        assert!(!report.kept.is_empty() && report.kept.len() <= 2);
+        let kept: usize = report.sources.iter().map(|source| source.kept).sum();
+        assert_eq!(kept, report.kept.len());
+    }
+}
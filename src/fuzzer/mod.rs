@@ -1,8 +1,20 @@
 //pub mod cairo_worker;
+pub mod auto_dict;
+pub mod campaign;
+pub mod cmplog;
 pub mod corpus_crash;
 pub mod corpus_input;
+pub mod corpus_store;
+pub mod coverage_report;
 pub mod dict;
+pub mod differential;
+pub mod executor;
 pub mod fuzzer;
+pub mod merge;
+pub mod resources;
+pub mod schedule;
 pub mod starknet_worker;
 pub mod stats;
+pub mod stats_log;
+pub mod tx_import;
 pub mod utils;
@@ -0,0 +1,2 @@
+//! Resource exhaustion detection, shared with the cairo-native fuzzer
+pub use cairo_fuzzer_core::resources::{ExecutionCost, ResourceLimits};
@@ -0,0 +1,49 @@
+//! Power schedules, shared with the cairo-native fuzzer
+pub use cairo_fuzzer_core::schedule::{trace_edges, Schedule, Scheduler, SeedMeta};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::custom_rand::rng::Rng;
+    use crate::runner::runner::Runner;
+    use crate::runner::starknet_runner::RunnerStarknet;
+    use cairo_lang_starknet::casm_contract_class::CasmContractClass;
+    use felt::Felt252;
+
+    #[test]
+    fn test_rare_schedule() {
+        let casm_content =
+            std::fs::read_to_string("tests1.0/fuzzinglabs.casm").expect("Could not read casm file");
+        let contract_class: CasmContractClass =
+            serde_json::from_str(&casm_content).expect("could not get contractclass");
+        let mut scheduler = Scheduler::new(Schedule::Rare);
+        let mut execute = |input: &Vec<Felt252>| {
+            let runner = RunnerStarknet::new(&contract_class, 0);
+            let (_, result) = runner.run(input).expect("Could not run the contract");
+            let edges = trace_edges(result.coverage.iter().map(|pc| *pc as usize));
+            scheduler.observe(&edges);
+            SeedMeta {
+                edges,
+                steps: result.steps,
+            }
+        };
+
+        // Most executions fail the first comparison, one passes `f == 'f'`
+        let common = vec![Felt252::from(0); 11];
+        let mut rare = common.clone();
+        rare[0] = Felt252::from(b'f');
+        let mut common_meta = SeedMeta::default();
+        for _ in 0..50 {
+            common_meta = execute(&common);
+        }
+        let rare_meta = execute(&rare);
+        scheduler.add(common.clone(), common_meta);
+        scheduler.add(rare.clone(), rare_meta);
+
+        let rng = Rng::seeded(1);
+        let rare_picks = (0..1000)
+            .filter(|_| scheduler.pick(&rng) == Some(&rare))
+            .count();
+        assert!(rare_picks > 900);
+    }
+}
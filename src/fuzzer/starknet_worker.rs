@@ -1,16 +1,21 @@
-use crate::mutator::mutator_felt252::{EmptyDatabase, Mutator};
-use crate::runner::runner::Runner;
 use felt::Felt252;
 use starknet_rs::CasmContractClass;
 use std::sync::{Arc, Mutex};
 
+use crate::mutator::strategy::StrategyFactory;
+
+use super::cmplog::CmpLog;
+use super::corpus_crash::CrashFile;
+use super::corpus_store::CorpusStore;
+use super::dict::Dict;
+use super::executor::{Executor, Step};
+use super::resources::{ExecutionCost, ResourceLimits};
+use super::schedule::Schedule;
 use super::stats::*;
-use super::{corpus_crash::CrashFile, corpus_input::InputFile};
 
-use crate::custom_rand::rng::Rng;
-use crate::fuzzer::utils::hash_vector;
 use crate::json::json_parser::Function;
 use crate::runner::starknet_runner::RunnerStarknet;
+use crate::runner::state_dump::StateDump;
 
 pub struct StarknetWorker {
     stats: Arc<Mutex<Statistics>>,
@@ -18,9 +23,32 @@ pub struct StarknetWorker {
     contract_class: CasmContractClass,
     function: Function,
     seed: u64,
-    input_file: Arc<Mutex<InputFile>>,
+    corpus_store: Arc<Mutex<CorpusStore>>,
     crash_file: Arc<Mutex<CrashFile>>,
     iter: i64,
+    dict: Dict,
+    strategies: Vec<StrategyFactory>,
+    schedule: Schedule,
+    limits: ResourceLimits,
+    exhaustion_file: Arc<Mutex<CrashFile>>,
+    state: Arc<StateDump>,
+}
+
+/// Mutation, scheduling and execution settings shared by the workers of a campaign
+#[derive(Clone)]
+pub struct WorkerOptions {
+    /// Values used by the mutator
+    pub dict: Dict,
+    /// Factories of the custom mutation strategies, called once per worker
+    pub strategies: Vec<StrategyFactory>,
+    /// Power schedule of the corpus entries
+    pub schedule: Schedule,
+    /// Steps and memory limits of an execution
+    pub limits: ResourceLimits,
+    /// File storing the inputs exhausting the limits
+    pub exhaustion_file: Arc<Mutex<CrashFile>>,
+    /// Contract state the executions start from
+    pub state: Arc<StateDump>,
 }
 
 impl StarknetWorker {
@@ -30,172 +58,66 @@ impl StarknetWorker {
         contract_class: CasmContractClass,
         function: Function,
         seed: u64,
-        input_file: Arc<Mutex<InputFile>>,
+        corpus_store: Arc<Mutex<CorpusStore>>,
         crash_file: Arc<Mutex<CrashFile>>,
         iter: i64,
+        options: WorkerOptions,
     ) -> Self {
+        let WorkerOptions {
+            dict,
+            strategies,
+            schedule,
+            limits,
+            exhaustion_file,
+            state,
+        } = options;
         StarknetWorker {
             stats,
             worker_id,
             contract_class,
             function,
             seed: seed,
-            input_file,
+            corpus_store,
             crash_file,
             iter,
+            dict,
+            strategies,
+            schedule,
+            limits,
+            exhaustion_file,
+            state,
+        }
+    }
+
+    /// Creates the executor of the worker
+    fn executor(&self) -> Executor {
+        let runner = RunnerStarknet::new_with_state(
+            &self.contract_class,
+            self.function.selector_idx,
+            &self.state,
+        );
+        // Recover the comparison operands from the executed instructions
+        let cmplog = CmpLog::new(&self.contract_class, self.function.selector_idx);
+        let mut executor = Executor::new(runner, self.function.clone(), self.seed)
+            .cmplog(cmplog)
+            .dict(self.dict.clone())
+            .schedule(self.schedule)
+            .limits(self.limits);
+        // Register the custom strategies
+        for factory in &self.strategies {
+            executor = executor.strategy(factory());
         }
+        executor
     }
 
     pub fn fuzz(self) {
         // Local stats database
         let mut local_stats = Statistics::default();
-        // Create an RNG for this thread, seed is unique per thread
-        // to prevent duplication of efforts
-        let rng = Rng::seeded(self.seed);
-        let inputs_len = self.function.inputs.len();
-        // Create a mutator
-        let mut mutator = Mutator::new()
-            .seed(self.seed)
-            .max_input_size(inputs_len)
-            .types(self.function.inputs);
-        let mut starknet_runner =
-            RunnerStarknet::new(&self.contract_class, self.function.selector_idx);
-        'next_case: loop {
-            // clear previous data
-            mutator.input.clear();
-            if local_stats.input_len > 0 {
-                let index: usize = rng.rand_usize() % local_stats.input_len;
-                // pick from feedback corpora
-                mutator
-                    .input
-                    .extend_from_slice(&local_stats.get_input_by_index(index));
-            } else {
-                mutator
-                    .input
-                    .extend_from_slice(&vec![Felt252::from(b'\0'); inputs_len]);
-            }
-
-            // Corrupt it with 4 mutation passes
-            mutator.mutate(4, &EmptyDatabase);
-
-            // not the good size, drop this input // Useless verification
-            if mutator.input.len() != inputs_len {
-                println!(
-                    "Corrupted input size {} != {}",
-                    mutator.input.len(),
-                    inputs_len
-                );
-                continue 'next_case;
-            }
-
-            // run the cairo vm
-            match starknet_runner.clone().run(&mutator.input) {
-                Ok(res) => {
-                    starknet_runner = res.0;
-                    let call_info = res.1;
-                    let vec_trace = call_info.trace;
-                    let hash_vec = hash_vector(&vec_trace);
-                    let failure_flag = call_info.failure_flag;
-
-                    // Mutex locking is limited to this scope
-                    {
-                        let mut stats = self.stats.lock().expect("Failed to get mutex");
-                        // failure_flag is set if the smart contract raise an error or if for example the function could not be called because of an error in the arguments
-                        // TODO: to be more accurate in the args gen, we should generate the same type as in the prototype
-                        if failure_flag {
-                            // Update crash counters
-                            local_stats.crashes += 1;
-                            stats.crashes += 1;
-                            // Add the crash input to the local crash database
-                            local_stats.crash_db.insert(mutator.input.clone());
-
-                            // Add the crash input to the shared crash database
-                            if stats.crash_db.insert(mutator.input.clone()) {
-                                // add input to the crash corpus
-                                // New crashing input, we dump the crash on the disk
-                                let mut crash_file_lock =
-                                    self.crash_file.lock().expect("Failed to get mutex");
-                                crash_file_lock.crashes.push(mutator.input.to_vec());
-                                crash_file_lock.dump_json();
-                            }
-                        }
-                        if self.iter > 0 && self.iter < stats.fuzz_cases as i64 {
-                            return;
-                        }
-                        // verify if new input has been found by other fuzzers
-                        // if so, update our statistics
-                        if local_stats.input_len != stats.input_len {
-                            local_stats.coverage_db = stats.coverage_db.clone();
-                            local_stats.input_len = stats.input_len;
-                            local_stats.input_db = stats.input_db.clone();
-                            //local_stats.input_list = stats.input_list.clone();
-                            local_stats.crash_db = stats.crash_db.clone();
-                            local_stats.tx_crash_db = stats.tx_crash_db.clone();
-                        }
-                    }
-
-                    // Mutex locking is limited to this scope
-                    {
-                        // Check if this coverage entry is something we've never seen before
-                        if !local_stats.coverage_db.contains_key(&hash_vec) {
-                            // Coverage entry is new, save the fuzz input in the input database
-                            local_stats.input_db.insert(mutator.input.clone());
-
-                            // Update the module+offset in the coverage database to reflect that this input caused this coverage to occur
-                            local_stats
-                                .coverage_db
-                                .insert(hash_vec, mutator.input.clone());
-
-                            // Get access to global stats
-                            let mut stats = self.stats.lock().expect("Failed to get mutex");
-
-                            if !stats.coverage_db.contains_key(&hash_vec) {
-                                // Save input to global input database
-                                if stats.input_db.insert(mutator.input.clone()) {
-                                    // Copy in the input list
-                                    //stats.input_list.push(mutator.input.clone()); Useless since we have the unique hashset input_db
-                                    stats.input_len += 1;
-                                    // Copy locally
-                                    let mut input_file_lock =
-                                        self.input_file.lock().expect("Failed to get mutex");
-                                    input_file_lock.inputs.push(mutator.input.clone());
-                                    input_file_lock.dump_json();
-                                }
-                                // Save coverage to global coverage database
-                                stats.coverage_db.insert(hash_vec, mutator.input.clone());
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    // Mutex locking is limited to this scope
-                    {
-                        // Get access to global stats
-                        let mut stats = self.stats.lock().expect("Failed to get mutex");
-
-                        // Update crash counters
-                        local_stats.tx_crashes += 1;
-                        stats.tx_crashes += 1;
-
-                        // Add the crash input to the local crash database
-                        local_stats.tx_crash_db.insert(mutator.input.clone());
-
-                        // Add the crash input to the shared crash database
-                        if stats.tx_crash_db.insert(mutator.input.clone()) {
-                            // add input to the crash corpus
-                            // New crashing input, we dump the crash on the disk
-                            let mut crash_file_lock =
-                                self.crash_file.lock().expect("Failed to get mutex");
-                            crash_file_lock.crashes.push(mutator.input.clone());
-                            crash_file_lock.dump_json();
-
-                            println!(
-                                "WORKER {} -- INPUT => {:?} -- ERROR \"{:?}\"",
-                                self.worker_id, &mutator.input, e
-                            );
-                        }
-                    }
-                }
+        let mut executor = self.executor();
+        loop {
+            let step = executor.step(&mut local_stats);
+            if !self.publish(&step, &mut local_stats) {
+                return;
             }
             let counter_update = 1000;
             if local_stats.fuzz_cases % counter_update == 1 {
@@ -203,120 +125,151 @@ impl StarknetWorker {
                 let mut stats = self.stats.lock().expect("Failed to get mutex");
                 // Update fuzz case count
                 stats.fuzz_cases += counter_update;
+                stats
+                    .worker_executions
+                    .insert(self.worker_id, local_stats.fuzz_cases);
+                // Update the results of the mutation strategies
+                for (name, strategy_stats) in executor.mutator().drain_strategy_stats() {
+                    let entry = stats.strategy_stats.entry(name).or_default();
+                    entry.uses += strategy_stats.uses;
+                    entry.finds += strategy_stats.finds;
+                }
             }
-            local_stats.fuzz_cases += 1;
         }
     }
 
-    //TODO : Fix to match the new runner
     pub fn replay(&mut self, inputs: Vec<Vec<Felt252>>) {
         // Local stats database
         let mut local_stats = Statistics::default();
-        let mut starknet_runner =
-            RunnerStarknet::new(&self.contract_class, self.function.selector_idx);
+        let mut executor = self.executor();
         for input in inputs {
-            let fuzz_input = input;
-            match starknet_runner.clone().run(&fuzz_input) {
-                Ok(res) => {
-                    starknet_runner = res.0;
-                    let call_info = res.1;
-                    let vec_trace = call_info.trace;
-                    let hash_vec = hash_vector(&vec_trace);
-                    let failure_flag = call_info.failure_flag;
+            let step = executor.execute(input, &mut local_stats);
+            self.publish(&step, &mut local_stats);
 
-                    // Mutex locking is limited to this scope
-                    {
-                        let mut stats = self.stats.lock().expect("Failed to get mutex");
-                        if failure_flag {
-                            // Update crash counters
-                            local_stats.crashes += 1;
-                            stats.crashes += 1;
-                            // Add the crash input to the local crash database
-                            local_stats.crash_db.insert(fuzz_input.clone());
+            // Get access to global stats
+            let mut stats = self.stats.lock().expect("Failed to get mutex");
+            // Update fuzz case count
+            stats.fuzz_cases += 1;
+            stats
+                .worker_executions
+                .insert(self.worker_id, local_stats.fuzz_cases);
+        }
 
-                            // Add the crash input to the shared crash database
-                            if stats.crash_db.insert(fuzz_input.clone()) {
-                                // add input to the crash corpus
-                                // New crashing input, we dump the crash on the disk
-                                let mut crash_file_lock =
-                                    self.crash_file.lock().expect("Failed to get mutex");
-                                crash_file_lock.crashes.push(fuzz_input.to_vec());
-                                crash_file_lock.dump_json();
-                            }
-                        }
-                        // verify if new input has been found by other fuzzers
-                        // if so, update our statistics
-                        if local_stats.input_db.len() != stats.input_db.len() {
-                            local_stats.coverage_db = stats.coverage_db.clone();
-                            local_stats.input_db = stats.input_db.clone();
-                            local_stats.crash_db = stats.crash_db.clone();
-                        }
-                    }
-                    // Check if this coverage entry is something we've never seen before
-                    if !local_stats.coverage_db.contains_key(&hash_vec) {
-                        // Coverage entry is new, save the fuzz input in the input database
-                        local_stats.input_db.insert(fuzz_input.clone());
+        // Update the threads_finished when the worker executes all the corpus chunk
+        let mut stats = self.stats.lock().expect("Failed to get mutex");
+        stats.threads_finished += 1;
+    }
 
-                        // Update the module+offset in the coverage database to reflect that this input caused this coverage to occur
-                        local_stats.coverage_db.insert(hash_vec, fuzz_input.clone());
+    /// Publishes the findings of an execution to the shared statistics, the crash files
+    /// and the corpus store, and syncs the local statistics with the inputs found by the
+    /// other workers
+    /// Returns false when the fuzzing session is over
+    fn publish(&self, step: &Step, local_stats: &mut Statistics) -> bool {
+        // Mutex locking is limited to this scope
+        let mut stats = self.stats.lock().expect("Failed to get mutex");
+        let result = match &step.result {
+            Ok(result) => result,
+            Err(e) => {
+                if stats.stop {
+                    return false;
+                }
+                // Update crash counters
+                stats.tx_crashes += 1;
+                stats.record_crash(self.worker_id, &step.input, e.clone(), &[]);
 
-                        // Mutex locking is limited to this scope
-                        {
-                            // Get access to global stats
-                            let mut stats = self.stats.lock().expect("Failed to get mutex");
+                // Add the crash input to the shared crash database
+                if stats.tx_crash_db.insert(step.input.clone()) {
+                    // add input to the crash corpus
+                    // New crashing input, we dump the crash on the disk
+                    let mut crash_file_lock = self.crash_file.lock().expect("Failed to get mutex");
+                    crash_file_lock.crashes.push(step.input.clone());
+                    crash_file_lock.dump_json();
 
-                            if !stats.coverage_db.contains_key(&hash_vec) {
-                                // Save input to global input database
-                                if stats.input_db.insert(fuzz_input.clone()) {
-                                    //stats.input_list.push(fuzz_input.clone());
-                                    stats.input_len += 1;
-                                }
-                                // Save coverage to global coverage database
-                                stats.coverage_db.insert(hash_vec, fuzz_input.clone());
-                            }
-                        }
+                    if !stats.tui {
+                        println!(
+                            "WORKER {} -- INPUT => {:?} -- ERROR \"{:?}\"",
+                            self.worker_id, &step.input, e
+                        );
                     }
                 }
-                Err(e) => {
-                    // Mutex locking is limited to this scope
-                    {
-                        // Get access to global stats
-                        let mut stats = self.stats.lock().expect("Failed to get mutex");
-
-                        // Update crash counters
-                        local_stats.tx_crashes += 1;
-                        stats.tx_crashes += 1;
-
-                        // Add the crash input to the local crash database
-                        local_stats.tx_crash_db.insert(fuzz_input.clone());
+                return true;
+            }
+        };
+        let cost = ExecutionCost::from_result(result);
 
-                        // Add the crash input to the shared crash database
-                        if stats.tx_crash_db.insert(fuzz_input.clone()) {
-                            // add input to the crash corpus
-                            // New crashing input, we dump the crash on the disk
-                            let mut crash_file_lock =
-                                self.crash_file.lock().expect("Failed to get mutex");
-                            crash_file_lock.crashes.push(fuzz_input.to_vec());
-                            crash_file_lock.dump_json();
+        // failure_flag is set if the smart contract raise an error or if for example the function could not be called because of an error in the arguments
+        if result.failure_flag {
+            // Update crash counters
+            stats.crashes += 1;
+            stats.record_crash(
+                self.worker_id,
+                &step.input,
+                panic_message(&result.retdata),
+                &result.retdata,
+            );
+            // Add the crash input to the shared crash database
+            if stats.crash_db.insert(step.input.clone()) {
+                // add input to the crash corpus
+                // New crashing input, we dump the crash on the disk
+                let mut crash_file_lock = self.crash_file.lock().expect("Failed to get mutex");
+                crash_file_lock.crashes.push(step.input.clone());
+                crash_file_lock.dump_json();
+            }
+        }
+        // Keep track of the most expensive executions
+        stats.max_steps = stats.max_steps.max(cost.steps);
+        for (name, count) in &cost.builtins {
+            let max = stats.max_builtins.entry(name.clone()).or_insert(0);
+            *max = (*max).max(*count);
+        }
+        // Resource exhaustion: the input executed more steps than the threshold
+        if self.limits.exceeded(&cost) {
+            stats.exhaustions += 1;
+            if stats.exhaustion_db.insert(step.input.clone()) {
+                let mut exhaustion_file_lock =
+                    self.exhaustion_file.lock().expect("Failed to get mutex");
+                exhaustion_file_lock.crashes.push(step.input.clone());
+                exhaustion_file_lock.dump_json();
 
-                            println!(
-                                "WORKER {} -- INPUT => {:?} -- ERROR \"{:?}\"",
-                                self.worker_id, fuzz_input, e
-                            );
-                        }
-                    }
+                if !stats.tui {
+                    println!(
+                        "WORKER {} -- INPUT => {:?} -- RESOURCE EXHAUSTION \"{} steps, builtins {:?}\"",
+                        self.worker_id, &step.input, cost.steps, cost.builtins
+                    );
                 }
             }
+        }
 
-            // Get access to global stats
-            let mut stats = self.stats.lock().expect("Failed to get mutex");
-            // Update fuzz case count
-            stats.fuzz_cases += 1;
-            local_stats.fuzz_cases += 1;
+        // Save the new corpus entry unless another worker reached the same coverage,
+        // the most expensive input is kept when maximizing the steps
+        if let Some(entry) = &step.entry {
+            let max_steps_found = self.limits.maximize && entry.steps >= stats.max_steps;
+            let new_input = !stats.corpus.contains(&step.input);
+            // Save coverage and input to the global corpus
+            let new_coverage = stats.corpus.add(&step.input, &[entry.coverage]);
+            if new_input && (new_coverage || max_steps_found) {
+                stats.corpus.add_input(&step.input);
+                let meta = local_stats.corpus.meta(&step.input);
+                stats.corpus.set_meta(&step.input, meta);
+                // Append the input to the corpus on the disk
+                let mut corpus_store_lock = self.corpus_store.lock().expect("Failed to get mutex");
+                if let Err(e) = corpus_store_lock.append(entry) {
+                    eprintln!("Error: {}", e);
+                }
+            }
         }
 
-        // Update the threads_finished when the worker executes all the corpus chunk
-        let mut stats = self.stats.lock().expect("Failed to get mutex");
-        stats.threads_finished += 1;
+        if stats.stop || (self.iter > 0 && self.iter < stats.fuzz_cases as i64) {
+            return false;
+        }
+        // verify if new input has been found by other fuzzers
+        // if so, update our statistics
+        if local_stats.corpus.len() != stats.corpus.len() {
+            local_stats.corpus = stats.corpus.clone();
+            local_stats.crash_db = stats.crash_db.clone();
+            local_stats.tx_crash_db = stats.tx_crash_db.clone();
+        }
+        local_stats.max_steps = stats.max_steps;
+        true
     }
 }
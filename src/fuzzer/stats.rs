@@ -1,24 +1,20 @@
+use cairo_fuzzer_core::corpus::Corpus;
+use cairo_fuzzer_core::crashes::CrashTriage;
 use felt::Felt252;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashSet};
 pub type FuzzInput = Vec<Felt252>;
 
+pub use cairo_fuzzer_core::mutator::StrategyStats;
+
 /// Fuzz case statistics
 #[derive(Default, Debug)]
 pub struct Statistics {
     /// Number of fuzz cases
     pub fuzz_cases: u64,
 
-    /// Coverage database. Maps (module, offset) to `FuzzInput`s
-    pub coverage_db: HashMap<u64, FuzzInput>,
-
-    /// Counter of inputs
-    pub input_len: usize,
-
-    /// Set of all unique inputs
-    pub input_db: HashSet<FuzzInput>,
-
-    /// List of inputs
-    /* pub input_list: Vec<FuzzInput>, */
+    /// Inputs that reached new execution paths, the coverage points are the hashes of
+    /// the traces. Keeps the scheduling metadata of the inputs for the power schedules.
+    pub corpus: Corpus<Felt252>,
 
     /// List of all unique fuzzer actions
 
@@ -37,15 +33,62 @@ pub struct Statistics {
     /// Set of all unique crashes
     pub tx_crash_db: HashSet<FuzzInput>,
 
+    /// Counter of executions exceeding the steps threshold
+    pub exhaustions: u64,
+
+    /// Set of all unique inputs exceeding the steps threshold
+    pub exhaustion_db: HashSet<FuzzInput>,
+
+    /// Highest number of steps executed by an input
+    pub max_steps: u64,
+
+    /// Highest number of instances of each builtin used by an input
+    pub max_builtins: BTreeMap<String, u64>,
+
     // Number of threads that finished to run
     pub threads_finished: u64,
+
+    /// Set by the monitor when the fuzzing session is over, the workers stop at their next execution
+    pub stop: bool,
+
+    /// Results of each mutation strategy, by name
+    pub strategy_stats: BTreeMap<String, StrategyStats>,
+
+    /// Crashes deduplicated by error message
+    pub crash_triage: CrashTriage<Felt252>,
+
+    /// Worker, input and error of the last crash
+    pub last_crash: Option<String>,
+
+    /// Number of executions of each worker, by worker id
+    pub worker_executions: BTreeMap<i32, u64>,
+
+    /// Set when the dashboard is drawn, the workers do not print their findings
+    pub tui: bool,
 }
 
 impl Statistics {
-    pub fn get_input_by_index(&self, index: usize) -> &FuzzInput {
-        let mut iterator = self.input_db.iter();
-        iterator
-            .nth(index)
-            .expect("Could not get element from input_db")
+    /// Records a crash of a worker in the crash triage
+    pub fn record_crash(
+        &mut self,
+        worker_id: i32,
+        input: &FuzzInput,
+        error: String,
+        retdata: &[Felt252],
+    ) {
+        self.last_crash = Some(format!(
+            "WORKER {} -- INPUT => {:?} -- ERROR \"{}\"",
+            worker_id, input, error
+        ));
+        self.crash_triage.record(input, error, retdata);
     }
 }
+
+/// Error message of an execution that panicked, identified by its panic data
+pub fn panic_message(retdata: &[Felt252]) -> String {
+    let retdata: Vec<String> = retdata
+        .iter()
+        .map(|felt| format!("0x{}", felt.to_str_radix(16)))
+        .collect();
+    format!("panicked with [{}]", retdata.join(", "))
+}
@@ -0,0 +1,114 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::time::SystemTime;
+
+use cairo_fuzzer_core::stats_log::{parse_csv, StatsFormat, StatsRecord};
+use chrono::{DateTime, Utc};
+
+/// Periodic statistics of a fuzzing session written as CSV or JSON lines
+pub struct StatsLog {
+    file: File,
+    format: StatsFormat,
+    pub path: String,
+}
+
+impl StatsLog {
+    /// Creates the stats log, with the CSV header if needed
+    pub fn create(path: &str, format: StatsFormat) -> Result<Self, String> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Could not create {}: {}", parent.display(), e))?;
+        }
+        let file = File::create(path).map_err(|e| format!("Could not create {}: {}", path, e))?;
+        let mut log = StatsLog {
+            file,
+            format,
+            path: path.to_string(),
+        };
+        if format == StatsFormat::Csv {
+            log.write_line(&StatsRecord::csv_header())?;
+        }
+        Ok(log)
+    }
+
+    /// Appends a record to the log
+    pub fn write(&mut self, record: &StatsRecord) -> Result<(), String> {
+        let line = match self.format {
+            StatsFormat::Csv => record.to_csv(),
+            StatsFormat::Jsonl => {
+                serde_json::to_string(record).map_err(|e| format!("Failed to serialize: {}", e))?
+            }
+        };
+        self.write_line(&line)
+    }
+
+    fn write_line(&mut self, line: &str) -> Result<(), String> {
+        writeln!(self.file, "{}", line)
+            .and_then(|_| self.file.flush())
+            .map_err(|e| format!("Could not write {}: {}", self.path, e))
+    }
+}
+
+/// Path of the stats log of a session, in the `stats` folder of the function workspace
+pub fn stats_log_path(workspace: &str, function_name: &str, format: StatsFormat) -> String {
+    let timestamp = DateTime::<Utc>::from(SystemTime::now()).format("%Y-%m-%d--%H:%M:%S");
+    format!(
+        "{}/{}/stats/{}_{}.{}",
+        workspace,
+        function_name,
+        function_name,
+        timestamp,
+        format.extension()
+    )
+}
+
+/// Loads a stats log, the format is given by its extension
+pub fn load_stats(path: &str) -> Result<Vec<StatsRecord>, String> {
+    let contents =
+        fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+    match StatsFormat::from_path(path) {
+        Some(StatsFormat::Csv) => parse_csv(&contents),
+        Some(StatsFormat::Jsonl) => contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line).map_err(|e| format!("line {}: {}", i + 1, e))
+            })
+            .collect(),
+        None => Err("expected a .csv or .jsonl stats log".to_string()),
+    }
+    .map_err(|e| format!("Could not parse {}: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_log() {
+        let workspace = std::env::temp_dir().join(format!(
+            "cairo_fuzzer_test_stats_log_{}",
+            std::process::id()
+        ));
+        let workspace = workspace.to_str().unwrap();
+        let record = StatsRecord {
+            time: 1.0,
+            execs: 100,
+            coverage: 5,
+            ..Default::default()
+        };
+        for format in [StatsFormat::Csv, StatsFormat::Jsonl] {
+            // The log is created in the stats folder of the function workspace
+            let path = stats_log_path(workspace, "Fuzz_one", format);
+            let prefix = format!("{}/Fuzz_one/stats/Fuzz_one_", workspace);
+            assert!(path.starts_with(&prefix));
+            assert!(path.ends_with(&format!(".{}", format.extension())));
+            let mut stats_log = StatsLog::create(&path, format).unwrap();
+            stats_log.write(&record).unwrap();
+            assert_eq!(load_stats(&path).unwrap(), vec![record.clone()]);
+        }
+        let _ = fs::remove_dir_all(workspace);
+    }
+}
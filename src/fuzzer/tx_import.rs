@@ -0,0 +1,182 @@
+use std::collections::HashSet;
+use std::fs;
+
+use felt::Felt252;
+use serde::Deserialize;
+use serde_json::Value;
+use sha3::{Digest, Keccak256};
+
+use super::dict::parse_value;
+use super::stats::FuzzInput;
+use crate::json::json_parser::Function;
+
+/// An invoke transaction recorded from a node, calling a function of a contract
+///
+/// Felts are decimal or `0x` prefixed hex strings. The selector may also be the name of
+/// the called function.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordedTx {
+    /// Address of the called contract
+    #[serde(default, alias = "to")]
+    pub contract_address: Option<String>,
+    /// Selector of the called function
+    #[serde(alias = "entry_point_selector")]
+    pub selector: String,
+    /// Arguments of the call
+    pub calldata: Vec<String>,
+    /// Address of the caller
+    #[serde(default, alias = "sender_address")]
+    pub caller: Option<String>,
+}
+
+/// Inputs imported from recorded transactions
+#[derive(Debug, Clone, Default)]
+pub struct TxImport {
+    /// Calldata of the transactions calling the function, without duplicates
+    pub inputs: Vec<FuzzInput>,
+    /// Number of transactions calling the function
+    pub matched: usize,
+    /// Number of transactions calling the function whose calldata could not be used
+    pub skipped: usize,
+}
+
+/// Selector of a function: its starknet keccak, the keccak256 of its name truncated to 250 bits
+pub fn selector_from_name(name: &str) -> Felt252 {
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&Keccak256::digest(name.as_bytes()));
+    hash[0] &= 0x03;
+    Felt252::from_bytes_be(&hash)
+}
+
+/// Loads the transactions of a JSON array, a JSON object with a `transactions` array,
+/// or a JSON lines file with a transaction per line
+pub fn load_txs(path: &str) -> Result<Vec<RecordedTx>, String> {
+    let contents =
+        fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+    let values: Vec<Value> = match serde_json::from_str::<Value>(&contents) {
+        Ok(Value::Array(values)) => values,
+        Ok(Value::Object(mut object)) => match object.remove("transactions") {
+            Some(Value::Array(values)) => values,
+            Some(_) => return Err(format!("{}: transactions is not an array", path)),
+            // A single transaction
+            None => vec![Value::Object(object)],
+        },
+        Ok(_) => return Err(format!("{}: no transactions array", path)),
+        // JSON lines
+        Err(_) => contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| {
+                serde_json::from_str(line).map_err(|e| format!("{}: line {}: {}", path, idx + 1, e))
+            })
+            .collect::<Result<_, _>>()?,
+    };
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(idx, value)| {
+            serde_json::from_value(value)
+                .map_err(|e| format!("{}: transaction {}: {}", path, idx, e))
+        })
+        .collect()
+}
+
+/// Extracts the calldata of the transactions calling `function`, on the contract at
+/// `address` if provided. The calldata must have one felt per argument of the function.
+pub fn import_txs(
+    txs: &[RecordedTx],
+    function: &Function,
+    address: Option<&Felt252>,
+) -> Result<TxImport, String> {
+    let selector = selector_from_name(&function.name);
+    let mut import = TxImport::default();
+    let mut known: HashSet<FuzzInput> = HashSet::new();
+    for (idx, tx) in txs.iter().enumerate() {
+        let calls_function = match parse_value(&tx.selector) {
+            Ok(tx_selector) => tx_selector == selector,
+            Err(_) => tx.selector == function.name,
+        };
+        if !calls_function {
+            continue;
+        }
+        if let Some(address) = address {
+            let tx_address = match &tx.contract_address {
+                Some(tx_address) => Some(
+                    parse_value(tx_address).map_err(|e| format!("transaction {}: {}", idx, e))?,
+                ),
+                None => None,
+            };
+            if tx_address.as_ref() != Some(address) {
+                continue;
+            }
+        }
+        import.matched += 1;
+
+        let calldata: Result<FuzzInput, String> =
+            tx.calldata.iter().map(|value| parse_value(value)).collect();
+        match calldata {
+            Ok(calldata) if calldata.len() == function.inputs.len() => {
+                if known.insert(calldata.clone()) {
+                    import.inputs.push(calldata);
+                }
+            }
+            _ => import.skipped += 1,
+        }
+    }
+    Ok(import)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_txs() {
+        assert_eq!(
+            selector_from_name("transfer"),
+            Felt252::parse_bytes(
+                b"83afd3f4caedc6eebf44246fe54e38c95e3179a5ec9ea81740eca5b482d12e",
+                16
+            )
+            .unwrap()
+        );
+
+        let function = &Function {
+            name: "Fuzz_symbolic_execution".to_string(),
+            selector_idx: 0,
+            inputs: vec!["core::felt252".to_string(), "core::integer::u8".to_string()],
+            outputs: Vec::new(),
+        };
+        let calldata: Vec<String> = (0..function.inputs.len())
+            .map(|idx| format!("0x{:x}", idx))
+            .collect();
+        let tx = RecordedTx {
+            contract_address: Some("0x1234".to_string()),
+            selector: format!("0x{}", selector_from_name(&function.name).to_str_radix(16)),
+            calldata: calldata.clone(),
+            caller: None,
+        };
+        let by_name = RecordedTx {
+            selector: function.name.clone(),
+            ..tx.clone()
+        };
+        let other_function = RecordedTx {
+            selector: "transfer".to_string(),
+            ..tx.clone()
+        };
+        let short_calldata = RecordedTx {
+            calldata: vec!["1".to_string()],
+            ..tx.clone()
+        };
+        let txs = vec![tx, by_name, other_function, short_calldata];
+
+        let import = import_txs(&txs, function, None).unwrap();
+        assert_eq!(import.matched, 3);
+        assert_eq!(import.skipped, 1);
+        assert_eq!(import.inputs.len(), 1);
+        assert_eq!(import.inputs[0][1], Felt252::from(1));
+        let import = import_txs(&txs, function, Some(&Felt252::from(0x1235))).unwrap();
+        assert_eq!(import.matched, 0);
+    }
+}
@@ -14,92 +14,72 @@ pub struct AbiFunction {
     pub outputs: Vec<String>,
 }
 
-fn extract_function(tmp: &serde_json::Map<String, Value>) -> AbiFunction {
-    let name = tmp
-        .get("name")
-        .expect("Could not get name of function from the abi")
+/// Returns the string field `key` of an abi object
+fn get_str<'a>(obj: &'a serde_json::Map<String, Value>, key: &str) -> Result<&'a str, String> {
+    obj.get(key)
+        .ok_or_else(|| format!("Could not get {} from the abi", key))?
         .as_str()
-        .expect("Could not convert to str")
-        .to_string();
-    let inputs_data = tmp
-        .get("inputs")
-        .expect("Could not get inputs from the abi")
-        .as_array()
-        .expect("Could not convert inputs to array");
-    let mut inputs: Vec<String> = vec![];
-    for input in inputs_data {
-        inputs.push(
-            input
-                .get("type")
-                .expect("Could not get type from input")
-                .as_str()
-                .expect("Could not convert to str")
-                .to_string(),
-        );
-    }
-    let outputs_data = tmp
-        .get("outputs")
-        .expect("Could not get outputs from the abi")
+        .ok_or_else(|| format!("Could not convert {} to str", key))
+}
+
+/// Returns the types of the array field `key` (inputs or outputs) of an abi function
+fn get_types(obj: &serde_json::Map<String, Value>, key: &str) -> Result<Vec<String>, String> {
+    let data = obj
+        .get(key)
+        .ok_or_else(|| format!("Could not get {} from the abi", key))?
         .as_array()
-        .expect("Could not convert outputs to array");
-    let mut outputs: Vec<String> = vec![];
-    for output in outputs_data {
-        outputs.push(
-            output
-                .get("type")
-                .expect("Could not get type from input")
-                .as_str()
-                .expect("Could not convert to str")
-                .to_string(),
-        );
-    }
-    return AbiFunction {
-        name: name,
-        inputs: inputs,
-        outputs: outputs,
-    };
+        .ok_or_else(|| format!("Could not convert {} to array", key))?;
+    data.iter()
+        .map(|value| {
+            let value = value
+                .as_object()
+                .ok_or_else(|| format!("Could not convert {} to object", key))?;
+            Ok(get_str(value, "type")?.to_string())
+        })
+        .collect()
+}
+
+fn extract_function(tmp: &serde_json::Map<String, Value>) -> Result<AbiFunction, String> {
+    Ok(AbiFunction {
+        name: get_str(tmp, "name")?.to_string(),
+        inputs: get_types(tmp, "inputs")?,
+        outputs: get_types(tmp, "outputs")?,
+    })
 }
 
-fn search_for_function(data: &Vec<Value>) -> Vec<AbiFunction> {
+fn search_for_function(data: &Vec<Value>) -> Result<Vec<AbiFunction>, String> {
     let mut res: Vec<AbiFunction> = vec![];
     for obj in data {
         let tmp: &serde_json::Map<String, Value> = obj
             .as_object()
-            .expect("could not convert abi obj to object");
-        let obj_type = tmp
-            .get("type")
-            .expect("Could not get abi object type")
-            .as_str()
-            .expect("Could not convert to str");
-        if obj_type == "function" {
-            let state_mutability = tmp
-                .get("state_mutability")
-                .expect("Could not get state_mutability")
-                .as_str()
-                .expect("Could not convert to str");
-            if state_mutability == "external" {
-                res.push(extract_function(tmp));
-            }
+            .ok_or("Could not convert abi obj to object")?;
+        let obj_type = get_str(tmp, "type")?;
+        if obj_type == "function" && get_str(tmp, "state_mutability")? == "external" {
+            res.push(extract_function(tmp)?);
         }
         if obj_type == "interface" {
             let items = tmp
                 .get("items")
-                .expect("Could not get interface items")
+                .ok_or("Could not get interface items")?
                 .as_array()
-                .expect("Could not convert to str");
-            res.append(&mut search_for_function(items));
+                .ok_or("Could not convert interface items to array")?;
+            res.append(&mut search_for_function(items)?);
         }
     }
-    return res;
+    Ok(res)
 }
 
-fn get_abi(data: &Value) -> Vec<AbiFunction> {
+fn try_get_abi(data: &Value) -> Result<Vec<AbiFunction>, String> {
     let mut res: Vec<AbiFunction> = vec![];
     if let Some(abi) = data.get("abi") {
-        let abi = abi.as_array().expect("Could not convert abi to array");
-        res.append(&mut search_for_function(abi))
+        let abi = abi.as_array().ok_or("Could not convert abi to array")?;
+        res.append(&mut search_for_function(abi)?)
     }
-    res
+    Ok(res)
+}
+
+fn get_abi(data: &Value) -> Vec<AbiFunction> {
+    try_get_abi(data).unwrap_or_else(|e| panic!("{}", e))
 }
 
 pub fn get_function_from_json(data: &String, function_name: &String) -> Option<Function> {
@@ -122,6 +102,29 @@ pub fn get_function_from_json(data: &String, function_name: &String) -> Option<F
     return None;
 }
 
+/// Looks up `function_name` in the abi of a contract, a malformed contract is an error
+pub fn try_get_function_from_json(data: &str, function_name: &str) -> Result<Function, String> {
+    let data: Value =
+        serde_json::from_str(data).map_err(|e| format!("Could not parse the contract: {}", e))?;
+    if data.get("abi").is_none() {
+        return Err("The contract has no abi".to_string());
+    }
+    if data.get("entry_points_by_type").is_none() {
+        return Err("The contract has no entry points".to_string());
+    }
+    try_get_abi(&data)?
+        .into_iter()
+        .enumerate()
+        .find(|(_, function_abi)| function_abi.name == function_name)
+        .map(|(idx, function_abi)| Function {
+            name: function_abi.name,
+            selector_idx: idx,
+            inputs: function_abi.inputs,
+            outputs: function_abi.outputs,
+        })
+        .ok_or_else(|| format!("Function {} not found in the contract", function_name))
+}
+
 pub fn analyze_json(data: &String) {
     println!("Running json analyzer ... \n");
     let data: Value = serde_json::from_str(&data).expect("JSON was not well-formatted");
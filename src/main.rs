@@ -1,5 +1,14 @@
-use std::{fs, process};
+use std::{
+    fs,
+    path::Path,
+    process,
+    sync::{Arc, Mutex},
+};
 
+use cairo_fuzzer_core::dashboard::{FunctionProgress, FunctionState};
+use cairo_fuzzer_core::metrics::{serve, Metrics};
+use cairo_fuzzer_core::plot::{render_svg, Series};
+use cairo_lang_starknet::casm_contract_class::CasmContractClass;
 use clap::Parser;
 
 mod cli;
@@ -9,9 +18,17 @@ mod json;
 mod mutator;
 mod runner;
 
-use cli::args::Opt;
+use cli::args::{Command, Opt};
 use cli::config::Config;
-use fuzzer::fuzzer::Fuzzer;
+use fuzzer::corpus_input::InputFile;
+use fuzzer::corpus_store::{CorpusEntry, CorpusStore};
+use fuzzer::dict::parse_value;
+use fuzzer::fuzzer::{load_state, Fuzzer};
+use fuzzer::merge::{merge, MergeSource};
+use fuzzer::stats_log::load_stats;
+use fuzzer::tx_import::{import_txs, load_txs};
+use json::json_parser::Function;
+use runner::runner::Engine;
 
 use log::error;
 
@@ -23,6 +40,13 @@ fn main() {
         json::json_parser::analyze_json(&contents);
         return;
     }
+    if let Some(command) = &opt.command {
+        if let Err(e) = run_command(command, &opt) {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+        return;
+    }
     // create config file
     let mut config = match opt.config {
         // config file provided
@@ -47,6 +71,8 @@ fn main() {
                 input_folder: opt.inputfolder,
                 crash_folder: opt.crashfolder,
                 dict: opt.dict,
+                auto_dict: opt.auto_dict,
+                dump_dict: opt.dump_dict,
                 cores: opt.cores,
                 logs: opt.logs,
                 seed: opt.seed,
@@ -55,9 +81,49 @@ fn main() {
                 minimizer: opt.minimizer,
                 proptesting: opt.proptesting,
                 iter: opt.iter,
+                schedule: opt.schedule,
+                max_steps: opt.max_steps,
+                maximize_steps: opt.maximize_steps,
+                diff_dump: opt.diff_dump,
+                engine: opt.engine,
+                adapt_inputs: opt.adapt_inputs,
+                state_dump: opt.state_dump,
+                address_map: opt.map_address,
+                coverage_report: opt.coverage_report,
+                tui: opt.tui,
+                stats_format: opt.stats_format,
+                metrics: opt.metrics,
             }
         }
     };
+    // the native engine runs in its own binary
+    if config.engine == Engine::Native {
+        match runner::native::run_native_fuzzer(&config) {
+            Ok(code) => process::exit(code),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                process::exit(1);
+            }
+        }
+    }
+    // serve the metrics of the fuzzers
+    let metrics = match config.metrics.is_empty() {
+        true => None,
+        false => {
+            let metrics = Arc::new(Mutex::new(Metrics::default()));
+            match serve(&config.metrics, metrics.clone()) {
+                Ok(addr) => println!("\t\t\t\t\t\t\tMetrics served on http://{}/metrics", addr),
+                Err(e) => {
+                    eprintln!(
+                        "Error: Could not serve the metrics on {}: {}",
+                        config.metrics, e
+                    );
+                    process::exit(1);
+                }
+            }
+            Some(metrics)
+        }
+    };
     if config.proptesting {
         let contents = fs::read_to_string(&config.contract_file).unwrap();
         println!("\t\t\t\t\t\t\tSearching for Fuzzing functions ...");
@@ -66,26 +132,310 @@ fn main() {
             println!("\t\t\t\t\t\t\t!! No Fuzzing functions found !!");
             return;
         }
-        for func in functions {
+        let mut progress: Vec<FunctionProgress> = functions
+            .iter()
+            .map(|func| FunctionProgress::new(func))
+            .collect();
+        for (idx, func) in functions.into_iter().enumerate() {
             println!("\n\t\t\t\t\t\t\tFunction found => {}", &func);
             config.function_name = func;
-            let mut fuzzer = Fuzzer::new(&config);
+            let mut fuzzer = create_fuzzer(&config);
             println!(
                 "\t\t\t\t\t\t\t=== {} === is now running for {} iterations",
                 config.function_name, config.iter
             );
+            progress[idx].state = FunctionState::Running;
+            fuzzer.progress = progress.clone();
+            fuzzer.metrics = metrics.clone();
             fuzzer.fuzz();
+            progress[idx].state = FunctionState::Done;
+            progress[idx].executions = fuzzer
+                .stats
+                .lock()
+                .expect("Failed to lock stats mutex")
+                .fuzz_cases;
         }
     } else {
         // create the fuzzer
-        let mut fuzzer = Fuzzer::new(&config);
+        let mut fuzzer = create_fuzzer(&config);
+        fuzzer.metrics = metrics;
 
+        // dump the results of the corpus for differential fuzzing
+        if !config.diff_dump.is_empty() {
+            match fuzzer.dump_executions(&config.diff_dump) {
+                Ok(count) => println!(
+                    "\t\t\t\t\t\t\tExecution results of {} inputs dumped in {}",
+                    count, config.diff_dump
+                ),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    process::exit(1);
+                }
+            }
         // replay, minimizer mode
-        if opt.replay || opt.minimizer {
+        } else if opt.replay || opt.minimizer {
             fuzzer.replay();
         // launch fuzzing
         } else {
             fuzzer.fuzz();
         }
+
+        // write the source coverage of the corpus
+        if !config.coverage_report.is_empty() {
+            let report = fuzzer.source_coverage().and_then(|report| {
+                report.write(&config.coverage_report, &fuzzer.function.name)?;
+                Ok(report)
+            });
+            match report {
+                Ok(report) => println!(
+                    "\t\t\t\t\t\t\tSource coverage: {}/{} lines, report written in {}",
+                    report.lines_hit(),
+                    report.lines_found(),
+                    config.coverage_report
+                ),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
+    }
+}
+
+/// Creates the fuzzer of `config` and prints its banner, exits if it can't be created
+fn create_fuzzer(config: &Config) -> Fuzzer {
+    let fuzzer = match Fuzzer::new(config) {
+        Ok(fuzzer) => fuzzer,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+    };
+    for warning in &fuzzer.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    for note in &fuzzer.notes {
+        println!("\t\t\t\t\t\t\t{}", note);
+    }
+    println!(
+        "
+        =============================================================================================================================================================
+        ╔═╗ ┌─┐ ┬ ┬─┐ ┌───┐   ╔═╗ ┬ ┬ ┌─┐ ┌─┐ ┌─┐ ┬─┐      | Seed -- {}
+        ║   ├─┤ │ ├┬┘ │1.0│───╠╣  │ │ ┌─┘ ┌─┘ ├┤  ├┬┘      | Inputs loaded -- {}
+        ╚═╝ ┴ ┴ ┴ ┴└─ └───┘   ╚   └─┘ └─┘ └─┘ └─┘ ┴└─      | Threads to run -- {}",
+        fuzzer.seed,
+        fuzzer
+            .input_file
+            .lock()
+            .expect("Failed to lock inputs mutex")
+            .inputs
+            .len(),
+        config.cores,
+    );
+    fuzzer
+}
+
+/// Runs a subcommand
+fn run_command(command: &Command, opt: &Opt) -> Result<(), String> {
+    let workspace = &opt.workspace;
+    match command {
+        Command::ImportCorpus { inputfile, corpus } => {
+            let input_file = InputFile::load_from_file(inputfile, workspace)?;
+            let count = CorpusStore::import(&input_file, corpus)?;
+            println!("{} inputs imported in {}", count, corpus);
+        }
+        Command::ExportCorpus { corpus, inputfile } => {
+            let mut input_file = CorpusStore::export(corpus, workspace)?;
+            input_file.path = inputfile.clone();
+            input_file.write_json()?;
+            println!(
+                "{} inputs exported in {}",
+                input_file.inputs.len(),
+                inputfile
+            );
+        }
+        Command::Validate { files } => {
+            let contents = fs::read_to_string(&opt.contract)
+                .map_err(|e| format!("Could not read {}: {}", opt.contract, e))?;
+            let function = json::json_parser::get_function_from_json(&contents, &opt.function)
+                .ok_or_else(|| format!("Function {} not found in the contract", opt.function))?;
+            let mut errors = 0;
+            for file in files {
+                let loaded = match Path::new(file).is_dir() {
+                    true => InputFile::load_from_folder(file, workspace),
+                    false => InputFile::load_from_file(file, workspace),
+                };
+                // A file which can't be loaded is reported and the other files are checked
+                let input_file = match loaded {
+                    Ok(input_file) => input_file,
+                    Err(e) => {
+                        println!("error: {}", e);
+                        println!("{}: could not be loaded", file);
+                        errors += 1;
+                        continue;
+                    }
+                };
+                let diagnostics = input_file.check_function(&function);
+                let file_errors = diagnostics.iter().filter(|d| d.error).count();
+                for diagnostic in &diagnostics {
+                    match diagnostic.error {
+                        true => println!("error: {}", diagnostic.message),
+                        false => println!("warning: {}", diagnostic.message),
+                    }
+                }
+                println!(
+                    "{}: {} inputs, {} errors, {} warnings",
+                    file,
+                    input_file.inputs.len(),
+                    file_errors,
+                    diagnostics.len() - file_errors
+                );
+                errors += file_errors;
+            }
+            if errors > 0 {
+                return Err(format!("{} errors found", errors));
+            }
+        }
+        Command::ImportTxs {
+            txs,
+            address,
+            output,
+        } => {
+            let contents = fs::read_to_string(&opt.contract)
+                .map_err(|e| format!("Could not read {}: {}", opt.contract, e))?;
+            let function = json::json_parser::get_function_from_json(&contents, &opt.function)
+                .ok_or_else(|| format!("Function {} not found in the contract", opt.function))?;
+            let address = match address {
+                Some(address) => Some(parse_value(address)?),
+                None => None,
+            };
+            let recorded = load_txs(txs)?;
+            let import = import_txs(&recorded, &function, address.as_ref())?;
+            let entries: Vec<CorpusEntry> = import
+                .inputs
+                .into_iter()
+                .map(|input| {
+                    let mut entry = CorpusEntry::new(&function.name, input);
+                    entry.mutation = "import-txs".to_string();
+                    entry
+                })
+                .collect();
+            write_inputs(output, workspace, &function, &entries)?;
+            println!(
+                "{} transactions, {} calling {}, {} skipped: {} inputs imported in {}",
+                recorded.len(),
+                import.matched,
+                function.name,
+                import.skipped,
+                entries.len(),
+                output
+            );
+        }
+        Command::Merge { sources, output } => {
+            let contents = fs::read_to_string(&opt.contract)
+                .map_err(|e| format!("Could not read {}: {}", opt.contract, e))?;
+            let casm_content = fs::read_to_string(&opt.casm)
+                .map_err(|e| format!("Could not read {}: {}", opt.casm, e))?;
+            let contract_class: CasmContractClass = serde_json::from_str(&casm_content)
+                .map_err(|e| format!("Could not parse {}: {}", opt.casm, e))?;
+            let function = json::json_parser::get_function_from_json(&contents, &opt.function)
+                .ok_or_else(|| format!("Function {} not found in the contract", opt.function))?;
+            let sources: Vec<MergeSource> = sources
+                .iter()
+                .map(|path| {
+                    let input_file = match Path::new(path).is_dir() {
+                        true => InputFile::load_from_folder(path, workspace),
+                        false => InputFile::load_from_file(path, workspace),
+                    }?;
+                    Ok(MergeSource {
+                        path: path.clone(),
+                        inputs: input_file.inputs,
+                    })
+                })
+                .collect::<Result<_, String>>()?;
+            let mut warnings = Vec::new();
+            let state = load_state(&opt.state_dump, &opt.map_address, &mut warnings)?;
+            for warning in &warnings {
+                eprintln!("Warning: {}", warning);
+            }
+            let report = merge(&contract_class, &function, &state, &sources);
+
+            println!(
+                "{:>8} {:>8} {:>8} {:>13} {:>8}  source",
+                "inputs", "invalid", "edges", "unique edges", "kept"
+            );
+            for source in &report.sources {
+                println!(
+                    "{:>8} {:>8} {:>8} {:>13} {:>8}  {}",
+                    source.inputs,
+                    source.invalid,
+                    source.edges,
+                    source.unique_edges,
+                    source.kept,
+                    source.path
+                );
+            }
+            write_inputs(output, workspace, &function, &report.kept)?;
+            println!(
+                "{} inputs covering {} edges merged in {}",
+                report.kept.len(),
+                report.edges,
+                output
+            );
+        }
+        Command::Plot {
+            stats,
+            metric,
+            output,
+        } => {
+            if !output.ends_with(".svg") {
+                return Err(format!(
+                    "{}: only SVG charts are supported, the output must end with .svg",
+                    output
+                ));
+            }
+            let mut series = Vec::new();
+            for path in stats {
+                let label = Path::new(path)
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or(path.clone());
+                series.push(Series::from_records(&label, &load_stats(path)?, *metric));
+            }
+            let svg = render_svg(
+                &format!("{} over time", metric.name()),
+                "time (s)",
+                metric.name(),
+                &series,
+            );
+            fs::write(output, svg).map_err(|e| format!("Could not write {}: {}", output, e))?;
+            println!("{} stats logs charted in {}", series.len(), output);
+        }
+    }
+    Ok(())
+}
+
+/// Writes the inputs to `output`, a binary corpus if it ends with `.corpus`, a JSON inputs file otherwise
+fn write_inputs(
+    output: &String,
+    workspace: &String,
+    function: &Function,
+    entries: &[CorpusEntry],
+) -> Result<(), String> {
+    if output.ends_with(".corpus") {
+        if Path::new(output).exists() {
+            return Err(format!("{} already exists", output));
+        }
+        let mut store = CorpusStore::new(output, &function.name, &function.inputs);
+        store.append_all(entries)
+    } else {
+        let input_file = InputFile {
+            workspace: workspace.clone(),
+            path: output.clone(),
+            name: function.name.clone(),
+            args: function.inputs.clone(),
+            inputs: entries.iter().map(|entry| entry.calldata.clone()).collect(),
+        };
+        input_file.write_json()
     }
 }
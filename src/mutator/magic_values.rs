@@ -1,248 +0,0 @@
-//! A file containing a bunch of magic values, from honggfuzz
-/*
- *
- * Authors:
- * Robert Swiecki <swiecki@google.com>
- * Brandon Falk <bfalk@gamozolabs.com>
- *
- * Copyright 2010-2018 by Google Inc. All Rights Reserved.
- * Copyright 2020 by Brandon Falk <bfalk@gamozolabs.com>
- *
- * Licensed under the Apache License, Version 2.0 (the "License"); you may
- * not use this file except in compliance with the License. You may obtain
- * a copy of the License at
- *
- * http://www.apache.org/licenses/LICENSE-2.0
- *
- * Unless required by applicable law or agreed to in writing, software
- * distributed under the License is distributed on an "AS IS" BASIS,
- * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or
- * implied. See the License for the specific language governing
- * permissions and limitations under the License.
- *
- */
-
-/// Magic values of various sizes and endiannesses
-pub const MAGIC_VALUES: &[&[u8]] = &[
-    b"\x00",
-    b"\x01",
-    b"\x02",
-    b"\x03",
-    b"\x04",
-    b"\x05",
-    b"\x06",
-    b"\x07",
-    b"\x08",
-    b"\x09",
-    b"\x0a",
-    b"\x0b",
-    b"\x0c",
-    b"\x0d",
-    b"\x0e",
-    b"\x0f",
-    b"\x10",
-    b"\x20",
-    b"\x40",
-    b"\x7e",
-    b"\x7f",
-    b"\x80",
-    b"\x81",
-    b"\xc0",
-    b"\xfe",
-    b"\xff",
-    b"\x00\x00",
-    b"\x01\x01",
-    b"\x80\x80",
-    b"\xff\xff",
-    b"\x00\x01",
-    b"\x00\x02",
-    b"\x00\x03",
-    b"\x00\x04",
-    b"\x00\x05",
-    b"\x00\x06",
-    b"\x00\x07",
-    b"\x00\x08",
-    b"\x00\x09",
-    b"\x00\x0a",
-    b"\x00\x0b",
-    b"\x00\x0c",
-    b"\x00\x0d",
-    b"\x00\x0e",
-    b"\x00\x0f",
-    b"\x00\x10",
-    b"\x00\x20",
-    b"\x00\x40",
-    b"\x00\x7e",
-    b"\x00\x7f",
-    b"\x00\x80",
-    b"\x00\x81",
-    b"\x00\xc0",
-    b"\x00\xfe",
-    b"\x00\xff",
-    b"\x7e\xff",
-    b"\x7f\xff",
-    b"\x80\x00",
-    b"\x80\x01",
-    b"\xff\xfe",
-    b"\x00\x00",
-    b"\x01\x00",
-    b"\x02\x00",
-    b"\x03\x00",
-    b"\x04\x00",
-    b"\x05\x00",
-    b"\x06\x00",
-    b"\x07\x00",
-    b"\x08\x00",
-    b"\x09\x00",
-    b"\x0a\x00",
-    b"\x0b\x00",
-    b"\x0c\x00",
-    b"\x0d\x00",
-    b"\x0e\x00",
-    b"\x0f\x00",
-    b"\x10\x00",
-    b"\x20\x00",
-    b"\x40\x00",
-    b"\x7e\x00",
-    b"\x7f\x00",
-    b"\x80\x00",
-    b"\x81\x00",
-    b"\xc0\x00",
-    b"\xfe\x00",
-    b"\xff\x00",
-    b"\xff\x7e",
-    b"\xff\x7f",
-    b"\x00\x80",
-    b"\x01\x80",
-    b"\xfe\xff",
-    b"\x00\x00\x00\x00",
-    b"\x01\x01\x01\x01",
-    b"\x80\x80\x80\x80",
-    b"\xff\xff\xff\xff",
-    b"\x00\x00\x00\x01",
-    b"\x00\x00\x00\x02",
-    b"\x00\x00\x00\x03",
-    b"\x00\x00\x00\x04",
-    b"\x00\x00\x00\x05",
-    b"\x00\x00\x00\x06",
-    b"\x00\x00\x00\x07",
-    b"\x00\x00\x00\x08",
-    b"\x00\x00\x00\x09",
-    b"\x00\x00\x00\x0a",
-    b"\x00\x00\x00\x0b",
-    b"\x00\x00\x00\x0c",
-    b"\x00\x00\x00\x0d",
-    b"\x00\x00\x00\x0e",
-    b"\x00\x00\x00\x0f",
-    b"\x00\x00\x00\x10",
-    b"\x00\x00\x00\x20",
-    b"\x00\x00\x00\x40",
-    b"\x00\x00\x00\x7e",
-    b"\x00\x00\x00\x7f",
-    b"\x00\x00\x00\x80",
-    b"\x00\x00\x00\x81",
-    b"\x00\x00\x00\xc0",
-    b"\x00\x00\x00\xfe",
-    b"\x00\x00\x00\xff",
-    b"\x7e\xff\xff\xff",
-    b"\x7f\xff\xff\xff",
-    b"\x80\x00\x00\x00",
-    b"\x80\x00\x00\x01",
-    b"\xff\xff\xff\xfe",
-    b"\x00\x00\x00\x00",
-    b"\x01\x00\x00\x00",
-    b"\x02\x00\x00\x00",
-    b"\x03\x00\x00\x00",
-    b"\x04\x00\x00\x00",
-    b"\x05\x00\x00\x00",
-    b"\x06\x00\x00\x00",
-    b"\x07\x00\x00\x00",
-    b"\x08\x00\x00\x00",
-    b"\x09\x00\x00\x00",
-    b"\x0a\x00\x00\x00",
-    b"\x0b\x00\x00\x00",
-    b"\x0c\x00\x00\x00",
-    b"\x0d\x00\x00\x00",
-    b"\x0e\x00\x00\x00",
-    b"\x0f\x00\x00\x00",
-    b"\x10\x00\x00\x00",
-    b"\x20\x00\x00\x00",
-    b"\x40\x00\x00\x00",
-    b"\x7e\x00\x00\x00",
-    b"\x7f\x00\x00\x00",
-    b"\x80\x00\x00\x00",
-    b"\x81\x00\x00\x00",
-    b"\xc0\x00\x00\x00",
-    b"\xfe\x00\x00\x00",
-    b"\xff\x00\x00\x00",
-    b"\xff\xff\xff\x7e",
-    b"\xff\xff\xff\x7f",
-    b"\x00\x00\x00\x80",
-    b"\x01\x00\x00\x80",
-    b"\xfe\xff\xff\xff",
-    b"\x00\x00\x00\x00\x00\x00\x00\x00",
-    b"\x01\x01\x01\x01\x01\x01\x01\x01",
-    b"\x80\x80\x80\x80\x80\x80\x80\x80",
-    b"\xff\xff\xff\xff\xff\xff\xff\xff",
-    b"\x00\x00\x00\x00\x00\x00\x00\x01",
-    b"\x00\x00\x00\x00\x00\x00\x00\x02",
-    b"\x00\x00\x00\x00\x00\x00\x00\x03",
-    b"\x00\x00\x00\x00\x00\x00\x00\x04",
-    b"\x00\x00\x00\x00\x00\x00\x00\x05",
-    b"\x00\x00\x00\x00\x00\x00\x00\x06",
-    b"\x00\x00\x00\x00\x00\x00\x00\x07",
-    b"\x00\x00\x00\x00\x00\x00\x00\x08",
-    b"\x00\x00\x00\x00\x00\x00\x00\x09",
-    b"\x00\x00\x00\x00\x00\x00\x00\x0a",
-    b"\x00\x00\x00\x00\x00\x00\x00\x0b",
-    b"\x00\x00\x00\x00\x00\x00\x00\x0c",
-    b"\x00\x00\x00\x00\x00\x00\x00\x0d",
-    b"\x00\x00\x00\x00\x00\x00\x00\x0e",
-    b"\x00\x00\x00\x00\x00\x00\x00\x0f",
-    b"\x00\x00\x00\x00\x00\x00\x00\x10",
-    b"\x00\x00\x00\x00\x00\x00\x00\x20",
-    b"\x00\x00\x00\x00\x00\x00\x00\x40",
-    b"\x00\x00\x00\x00\x00\x00\x00\x7e",
-    b"\x00\x00\x00\x00\x00\x00\x00\x7f",
-    b"\x00\x00\x00\x00\x00\x00\x00\x80",
-    b"\x00\x00\x00\x00\x00\x00\x00\x81",
-    b"\x00\x00\x00\x00\x00\x00\x00\xc0",
-    b"\x00\x00\x00\x00\x00\x00\x00\xfe",
-    b"\x00\x00\x00\x00\x00\x00\x00\xff",
-    b"\x7e\xff\xff\xff\xff\xff\xff\xff",
-    b"\x7f\xff\xff\xff\xff\xff\xff\xff",
-    b"\x80\x00\x00\x00\x00\x00\x00\x00",
-    b"\x80\x00\x00\x00\x00\x00\x00\x01",
-    b"\xff\xff\xff\xff\xff\xff\xff\xfe",
-    b"\x00\x00\x00\x00\x00\x00\x00\x00",
-    b"\x01\x00\x00\x00\x00\x00\x00\x00",
-    b"\x02\x00\x00\x00\x00\x00\x00\x00",
-    b"\x03\x00\x00\x00\x00\x00\x00\x00",
-    b"\x04\x00\x00\x00\x00\x00\x00\x00",
-    b"\x05\x00\x00\x00\x00\x00\x00\x00",
-    b"\x06\x00\x00\x00\x00\x00\x00\x00",
-    b"\x07\x00\x00\x00\x00\x00\x00\x00",
-    b"\x08\x00\x00\x00\x00\x00\x00\x00",
-    b"\x09\x00\x00\x00\x00\x00\x00\x00",
-    b"\x0a\x00\x00\x00\x00\x00\x00\x00",
-    b"\x0b\x00\x00\x00\x00\x00\x00\x00",
-    b"\x0c\x00\x00\x00\x00\x00\x00\x00",
-    b"\x0d\x00\x00\x00\x00\x00\x00\x00",
-    b"\x0e\x00\x00\x00\x00\x00\x00\x00",
-    b"\x0f\x00\x00\x00\x00\x00\x00\x00",
-    b"\x10\x00\x00\x00\x00\x00\x00\x00",
-    b"\x20\x00\x00\x00\x00\x00\x00\x00",
-    b"\x40\x00\x00\x00\x00\x00\x00\x00",
-    b"\x7e\x00\x00\x00\x00\x00\x00\x00",
-    b"\x7f\x00\x00\x00\x00\x00\x00\x00",
-    b"\x80\x00\x00\x00\x00\x00\x00\x00",
-    b"\x81\x00\x00\x00\x00\x00\x00\x00",
-    b"\xc0\x00\x00\x00\x00\x00\x00\x00",
-    b"\xfe\x00\x00\x00\x00\x00\x00\x00",
-    b"\xff\x00\x00\x00\x00\x00\x00\x00",
-    b"\xff\xff\xff\xff\xff\xff\xff\x7e",
-    b"\xff\xff\xff\xff\xff\xff\xff\x7f",
-    b"\x00\x00\x00\x00\x00\x00\x00\x80",
-    b"\x01\x00\x00\x00\x00\x00\x00\x80",
-    b"\xfe\xff\xff\xff\xff\xff\xff\xff",
-];
@@ -1,4 +1,3 @@
-mod magic_values;
+//! Mutator of the fuzzed inputs, shared with the cairo-native fuzzer
 pub mod mutator_felt252;
-//pub mod mutator_u8;
-//mod mutator;
+pub mod strategy;
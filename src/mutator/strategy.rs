@@ -0,0 +1,7 @@
+//! Custom mutation strategies, shared with the cairo-native fuzzer
+use crate::runner::runner::Felt252Field;
+
+pub use cairo_fuzzer_core::mutator::MutationStrategy;
+
+/// Creates a new instance of a custom strategy for each fuzzing thread
+pub type StrategyFactory = cairo_fuzzer_core::mutator::StrategyFactory<Felt252Field>;
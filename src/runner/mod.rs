@@ -1,3 +1,5 @@
 //pub mod cairo_runner;
+pub mod native;
 pub mod runner;
 pub mod starknet_runner;
+pub mod state_dump;
@@ -0,0 +1,124 @@
+use std::env;
+use std::process::Command;
+
+use crate::cli::config::Config;
+
+/// Runs the campaign on cairo-native by launching the `cairo-native-fuzzer` binary
+///
+/// cairo-native and starknet_in_rust depend on incompatible versions of the cairo-lang
+/// crates, so they can't be linked in the same binary. The binary is taken from the
+/// `CAIRO_NATIVE_FUZZER` environment variable, or looked up in the `PATH`. Both fuzzers
+/// mutate and triage the inputs with `cairo-fuzzer-core`, the dictionary, power schedule
+/// and resource limits are forwarded; the options specific to the cairo-vm engine are
+/// rejected instead of being silently dropped.
+/// Returns the exit code of the native fuzzer.
+pub fn run_native_fuzzer(config: &Config) -> Result<i32, String> {
+    let unsupported = unsupported_options(config);
+    if !unsupported.is_empty() {
+        return Err(format!(
+            "{} not supported by the native engine",
+            unsupported.join(", ")
+        ));
+    }
+
+    let binary = env::var("CAIRO_NATIVE_FUZZER").unwrap_or("cairo-native-fuzzer".to_string());
+    let mut command = Command::new(&binary);
+    command
+        .arg("--sierra-program")
+        .arg(&config.contract_file)
+        .arg("--workspace")
+        .arg(&config.workspace)
+        .arg("--cores")
+        .arg(config.cores.max(1).to_string());
+    if config.proptesting {
+        command.arg("--proptesting");
+    } else {
+        command.arg("--entry-point").arg(&config.function_name);
+    }
+    if let Some(seed) = config.seed {
+        command.arg("--seed").arg(seed.to_string());
+    }
+    if config.iter > 0 {
+        command.arg("--iter").arg(config.iter.to_string());
+    }
+    if let Some(run_time) = config.run_time {
+        command.arg("--run-time").arg(run_time.to_string());
+    }
+    if config.tui {
+        command.arg("--tui");
+    }
+    if !config.dict.is_empty() {
+        command.arg("--dict").arg(&config.dict);
+    }
+    if !config.coverage_report.is_empty() {
+        command
+            .arg("--coverage-report")
+            .arg(&config.coverage_report);
+    }
+    // The schedules have the same names in both fuzzers
+    let schedule = serde_json::to_value(config.schedule)
+        .map_err(|e| format!("Could not serialize the schedule: {}", e))?;
+    if let Some(schedule) = schedule.as_str() {
+        command.arg("--schedule").arg(schedule);
+    }
+    // On cairo-native, the consumed gas stands for the steps
+    if config.max_steps > 0 {
+        command.arg("--max-gas").arg(config.max_steps.to_string());
+    }
+    if config.maximize_steps {
+        command.arg("--maximize-gas");
+    }
+
+    let status = command
+        .status()
+        .map_err(|e| format!("Could not run {}: {}", binary, e))?;
+    Ok(status.code().unwrap_or(1))
+}
+
+/// Options of the configuration only supported by the cairo-vm engine
+fn unsupported_options(config: &Config) -> Vec<&'static str> {
+    let options = [
+        ("--inputfile", !config.input_file.is_empty()),
+        ("--crashfile", !config.crash_file.is_empty()),
+        ("--inputfolder", !config.input_folder.is_empty()),
+        ("--crashfolder", !config.crash_folder.is_empty()),
+        ("--auto-dict", config.auto_dict),
+        ("--dump-dict", !config.dump_dict.is_empty()),
+        ("--state-dump", !config.state_dump.is_empty()),
+        ("--diff-dump", !config.diff_dump.is_empty()),
+        ("--logs", config.logs),
+        ("--metrics", !config.metrics.is_empty()),
+        ("--replay", config.replay),
+        ("--minimizer", config.minimizer),
+    ];
+    options
+        .into_iter()
+        .filter(|(_, used)| *used)
+        .map(|(option, _)| option)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unsupported_options() {
+        let mut config = Config {
+            dict: "dict.txt".to_string(),
+            max_steps: 1000,
+            coverage_report: "coverage".to_string(),
+            ..Default::default()
+        };
+        assert!(unsupported_options(&config).is_empty());
+        config.replay = true;
+        config.state_dump = "state.json".to_string();
+        assert_eq!(
+            unsupported_options(&config),
+            vec!["--state-dump", "--replay"]
+        );
+        assert!(run_native_fuzzer(&config)
+            .unwrap_err()
+            .contains("--state-dump, --replay"));
+    }
+}
@@ -1,8 +1,32 @@
+//! Engine independent runner interface, shared with the cairo-native fuzzer
+use cairo_fuzzer_core::felt::Field;
 use felt::Felt252;
-use starknet_rs::execution::CallInfo;
 
-pub trait Runner {
-    fn run(self, data: &Vec<Felt252>) -> Result<(Self, CallInfo), String>
-    where
-        Self: Sized;
+pub use cairo_fuzzer_core::runner::{Engine, ExecutionResult, Runner};
+
+/// Field arithmetic of the cairo-vm felts, used by the shared mutator and dictionaries
+pub struct Felt252Field;
+
+impl Field for Felt252Field {
+    type Felt = Felt252;
+
+    fn to_be_bytes(felt: &Felt252) -> [u8; 32] {
+        felt.to_be_bytes()
+    }
+
+    fn from_be_bytes(bytes: &[u8; 32]) -> Felt252 {
+        Felt252::from_bytes_be(bytes)
+    }
+
+    fn add(a: &Felt252, b: &Felt252) -> Felt252 {
+        a.clone() + b.clone()
+    }
+
+    fn sub(a: &Felt252, b: &Felt252) -> Felt252 {
+        a.clone() - b.clone()
+    }
+
+    fn neg(a: &Felt252) -> Felt252 {
+        -a.clone()
+    }
 }
@@ -18,7 +18,11 @@ use starknet_rs::{
 
 use std::{collections::HashMap, sync::Arc};
 
-use super::runner::Runner;
+use super::runner::{ExecutionResult, Felt252Field, Runner};
+use super::state_dump::StateDump;
+
+/// Address the fuzzed contract is deployed at
+pub const CONTRACT_ADDRESS: u64 = 1111;
 
 #[derive(Clone, Debug)]
 pub struct RunnerStarknet {
@@ -35,6 +39,19 @@ pub struct RunnerStarknet {
 
 impl RunnerStarknet {
     pub fn new(contract_class: &CasmContractClass, func_entrypoint_idx: usize) -> Self {
+        Self::new_with_state(contract_class, func_entrypoint_idx, &StateDump::default())
+    }
+
+    /// Creates a runner whose state is initialized with the contracts of `state_dump`
+    /// The fuzzed contract stays at `CONTRACT_ADDRESS`: the state of this address is loaded
+    /// into the fuzzed contract and its class hash is kept, so the dump address of the
+    /// fuzzed contract must be mapped to `CONTRACT_ADDRESS`. The class hashes of the other
+    /// contracts are only set if their class is loaded.
+    pub fn new_with_state(
+        contract_class: &CasmContractClass,
+        func_entrypoint_idx: usize,
+        state_dump: &StateDump,
+    ) -> Self {
         let entrypoints = contract_class.clone().entry_points_by_type;
         let entrypoint_selector = &entrypoints
             .external
@@ -45,18 +62,47 @@ impl RunnerStarknet {
         // Create state reader with class hash data
         let mut contract_class_cache: HashMap<[u8; 32], CasmContractClass> = HashMap::new();
 
-        let address = Address(1111.into()); //todo - make it configurable from the config
+        let address = Address(CONTRACT_ADDRESS.into());
         let class_hash: ClassHash = [1; 32];
-        let nonce = Felt252::zero(); //todo - make it configurable from the config
+        // Nonce of the fuzzed contract unless the state dump sets it
+        let nonce = Felt252::zero();
 
         contract_class_cache.insert(class_hash, contract_class.clone());
+        for (class_hash, class) in &state_dump.classes {
+            contract_class_cache.insert(class_hash.to_be_bytes(), class.clone());
+        }
         let mut state_reader = InMemoryStateReader::default();
+        for (contract_address, contract) in &state_dump.contracts {
+            let contract_address = Address(contract_address.clone());
+            // A contract without class would fail when called, `load_state` warns about it
+            if let Some(class_hash) = contract
+                .class_hash
+                .as_ref()
+                .filter(|class_hash| state_dump.classes.contains_key(class_hash))
+            {
+                state_reader
+                    .address_to_class_hash_mut()
+                    .insert(contract_address.clone(), class_hash.to_be_bytes());
+            }
+            if let Some(nonce) = &contract.nonce {
+                state_reader
+                    .address_to_nonce_mut()
+                    .insert(contract_address.clone(), nonce.clone());
+            }
+            for (key, value) in &contract.storage {
+                state_reader
+                    .address_to_storage_mut()
+                    .insert((contract_address.clone(), key.to_be_bytes()), value.clone());
+            }
+        }
+        // The fuzzed contract keeps its class whatever the class hash of its address in the dump
         state_reader
             .address_to_class_hash_mut()
             .insert(address.clone(), class_hash);
         state_reader
             .address_to_nonce_mut()
-            .insert(address.clone(), nonce);
+            .entry(address.clone())
+            .or_insert(nonce);
 
         // Create state from the state_reader and contract cache.
         let state = CachedState::new(Arc::new(state_reader), None, Some(contract_class_cache));
@@ -92,6 +138,16 @@ impl RunnerStarknet {
     pub fn get_state(self) -> CachedState<InMemoryStateReader> {
         return self.state;
     }
+    /// Returns the storage writes of the contract as (key, value)
+    pub fn storage_writes(&self) -> Vec<(Felt252, Felt252)> {
+        self.state
+            .cache
+            .storage_writes
+            .iter()
+            .filter(|((address, _), _)| *address == self.address)
+            .map(|((_, key), value)| (Felt252::from_bytes_be(key), value.clone()))
+            .collect()
+    }
     #[allow(dead_code)]
     pub fn set_state(mut self, state: StateCache) -> Self {
         self.state.cache = state;
@@ -100,7 +156,10 @@ impl RunnerStarknet {
 }
 
 impl Runner for RunnerStarknet {
-    fn run(mut self, data: &Vec<Felt252>) -> Result<(Self, CallInfo), String> {
+    type Felt = Felt252;
+    type Field = Felt252Field;
+
+    fn run(mut self, data: &[Felt252]) -> Result<(Self, ExecutionResult<Felt252>), String> {
         // Create an execution entry point
         let calldata = data.to_vec();
         let exec_entry_point = ExecutionEntryPoint::new(
@@ -128,9 +187,34 @@ impl Runner for RunnerStarknet {
                     .call_info
                     .clone()
                     .expect("Could not get call info");
-                return Ok((self, call_info));
+                let result = self.execution_result(call_info);
+                return Ok((self, result));
             }
             Err(e) => return Err(e.to_string()),
         };
     }
 }
+
+impl RunnerStarknet {
+    /// Converts the call info of an execution into an engine independent result
+    fn execution_result(&self, call_info: CallInfo) -> ExecutionResult<Felt252> {
+        let resources = &call_info.execution_resources;
+        ExecutionResult {
+            failure_flag: call_info.failure_flag,
+            error_msg: None,
+            coverage: call_info
+                .trace
+                .iter()
+                .map(|entry| entry.pc as u64)
+                .collect(),
+            steps: resources.n_steps as u64,
+            builtins: resources
+                .builtin_instance_counter
+                .iter()
+                .map(|(name, count)| (name.clone(), *count as u64))
+                .collect(),
+            storage_writes: self.storage_writes(),
+            retdata: call_info.retdata,
+        }
+    }
+}
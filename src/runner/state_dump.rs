@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use cairo_lang_starknet::casm_contract_class::CasmContractClass;
+use felt::Felt252;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::fuzzer::dict::parse_value;
+
+/// State of a contract loaded from a state dump
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContractState {
+    /// Class hash of the deployed contract
+    pub class_hash: Option<Felt252>,
+    /// Nonce of the contract
+    pub nonce: Option<Felt252>,
+    /// Storage of the contract as (key, value)
+    pub storage: HashMap<Felt252, Felt252>,
+}
+
+/// Contracts storage, nonces and class hashes loaded into the runner state before fuzzing
+#[derive(Debug, Clone, Default)]
+pub struct StateDump {
+    /// State of each contract address
+    pub contracts: HashMap<Felt252, ContractState>,
+    /// Classes of the contracts, by class hash
+    pub classes: HashMap<Felt252, CasmContractClass>,
+}
+
+/// Contract of a state dump, felts are decimal or `0x` prefixed hex strings
+#[derive(Deserialize, Default)]
+struct RawContract {
+    #[serde(default)]
+    class_hash: Option<String>,
+    #[serde(default)]
+    nonce: Option<String>,
+    #[serde(default)]
+    storage: HashMap<String, String>,
+}
+
+/// State diff of a state update, as returned by `starknet_getStateUpdate`
+#[derive(Deserialize)]
+struct StateDiff {
+    #[serde(default)]
+    storage_diffs: Vec<StorageDiff>,
+    #[serde(default)]
+    deployed_contracts: Vec<DeployedContract>,
+    #[serde(default)]
+    nonces: Vec<NonceUpdate>,
+}
+
+#[derive(Deserialize)]
+struct StorageDiff {
+    address: String,
+    storage_entries: Vec<StorageEntry>,
+}
+
+#[derive(Deserialize)]
+struct StorageEntry {
+    key: String,
+    value: String,
+}
+
+#[derive(Deserialize)]
+struct DeployedContract {
+    address: String,
+    class_hash: String,
+}
+
+#[derive(Deserialize)]
+struct NonceUpdate {
+    contract_address: String,
+    nonce: String,
+}
+
+impl StateDiff {
+    fn into_contracts(self) -> HashMap<String, RawContract> {
+        let mut contracts: HashMap<String, RawContract> = HashMap::new();
+        for deployed in self.deployed_contracts {
+            contracts.entry(deployed.address).or_default().class_hash = Some(deployed.class_hash);
+        }
+        for update in self.nonces {
+            contracts.entry(update.contract_address).or_default().nonce = Some(update.nonce);
+        }
+        for diff in self.storage_diffs {
+            let contract = contracts.entry(diff.address).or_default();
+            for entry in diff.storage_entries {
+                contract.storage.insert(entry.key, entry.value);
+            }
+        }
+        contracts
+    }
+}
+
+impl StateDump {
+    /// Loads a state dump, either a JSON object with a `contracts` object mapping the
+    /// addresses to their `class_hash`, `nonce` and `storage`, or a state update with
+    /// the `storage_diffs`, `deployed_contracts` and `nonces` of its `state_diff`
+    /// The optional `classes` object maps class hashes to CASM files, relative to the dump
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents =
+            fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+        let mut value: Value = serde_json::from_str(&contents)
+            .map_err(|e| format!("Could not parse {}: {}", path, e))?;
+        let raw_classes: HashMap<String, String> = match value.get_mut("classes") {
+            Some(classes) => serde_json::from_value(classes.take())
+                .map_err(|e| format!("Could not parse {}: classes: {}", path, e))?,
+            None => HashMap::new(),
+        };
+        if let Some(state_diff) = value.get_mut("state_diff") {
+            value = state_diff.take();
+        }
+        let raw_contracts: HashMap<String, RawContract> = match value.get_mut("contracts") {
+            Some(contracts) => serde_json::from_value(contracts.take()),
+            None => serde_json::from_value::<StateDiff>(value).map(StateDiff::into_contracts),
+        }
+        .map_err(|e| format!("Could not parse {}: {}", path, e))?;
+
+        let mut contracts = HashMap::new();
+        for (address, raw) in raw_contracts {
+            let parse = |value: &str| {
+                parse_value(value).map_err(|e| format!("{}: contract {}: {}", path, address, e))
+            };
+            let mut contract = ContractState {
+                class_hash: raw.class_hash.as_deref().map(parse).transpose()?,
+                nonce: raw.nonce.as_deref().map(parse).transpose()?,
+                storage: HashMap::new(),
+            };
+            for (key, value) in &raw.storage {
+                contract.storage.insert(parse(key)?, parse(value)?);
+            }
+            contracts.insert(parse(&address)?, contract);
+        }
+
+        let mut classes = HashMap::new();
+        let folder = Path::new(path).parent().unwrap_or(Path::new(""));
+        for (class_hash, class_path) in raw_classes {
+            let class_path = folder.join(class_path);
+            let class_contents = fs::read_to_string(&class_path)
+                .map_err(|e| format!("Could not read {}: {}", class_path.display(), e))?;
+            let class: CasmContractClass = serde_json::from_str(&class_contents)
+                .map_err(|e| format!("Could not parse {}: {}", class_path.display(), e))?;
+            let class_hash = parse_value(&class_hash)
+                .map_err(|e| format!("{}: class {}: {}", path, class_hash, e))?;
+            classes.insert(class_hash, class);
+        }
+        Ok(StateDump { contracts, classes })
+    }
+
+    /// Returns the addresses and class hashes of the contracts whose class is not loaded
+    pub fn missing_classes(&self) -> Vec<(Felt252, Felt252)> {
+        let mut missing: Vec<(Felt252, Felt252)> = self
+            .contracts
+            .iter()
+            .filter_map(|(address, contract)| {
+                let class_hash = contract.class_hash.as_ref()?;
+                match self.classes.contains_key(class_hash) {
+                    true => None,
+                    false => Some((address.clone(), class_hash.clone())),
+                }
+            })
+            .collect();
+        missing.sort();
+        missing
+    }
+
+    /// Moves the state of the `from` addresses to the `to` addresses
+    /// The addresses are all mapped at once, so two addresses can be swapped
+    pub fn map_addresses(&mut self, map: &[(Felt252, Felt252)]) {
+        let mut mapped = HashMap::new();
+        for (from, to) in map {
+            if let Some(contract) = self.contracts.remove(from) {
+                mapped.insert(to.clone(), contract);
+            }
+        }
+        self.contracts.extend(mapped);
+    }
+}
+
+/// Parses an address mapping formatted as `FROM=TO`
+pub fn parse_address_map(mapping: &str) -> Result<(Felt252, Felt252), String> {
+    let (from, to) = mapping
+        .split_once('=')
+        .ok_or_else(|| format!("invalid address mapping {}, expected FROM=TO", mapping))?;
+    Ok((parse_value(from.trim())?, parse_value(to.trim())?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_dump() {
+        let path = std::env::temp_dir().join("cairo_fuzzer_test_state_dump.json");
+        let path = path.to_str().unwrap();
+
+        fs::write(
+            path,
+            r#"{"contracts": {"0x10": {"class_hash": "0x20", "nonce": "3", "storage": {"0x1": "0x64"}}}}"#,
+        )
+        .unwrap();
+        let mut state = StateDump::load(path).unwrap();
+        let contract = &state.contracts[&Felt252::from(0x10)];
+        assert_eq!(contract.class_hash, Some(Felt252::from(0x20)));
+        assert_eq!(contract.nonce, Some(Felt252::from(3)));
+        assert_eq!(contract.storage[&Felt252::from(1)], Felt252::from(100));
+
+        let mapping = parse_address_map("0x10=1111").unwrap();
+        assert_eq!(mapping, (Felt252::from(0x10), Felt252::from(1111)));
+        assert!(parse_address_map("0x10").is_err());
+        state.map_addresses(&[mapping]);
+        assert!(state.contracts.contains_key(&Felt252::from(1111)));
+        assert!(!state.contracts.contains_key(&Felt252::from(0x10)));
+
+        fs::write(
+            path,
+            r#"{"state_diff": {
+                "storage_diffs": [{"address": "0x10", "storage_entries": [{"key": "0x1", "value": "0x2"}]}],
+                "deployed_contracts": [{"address": "0x10", "class_hash": "0x20"}],
+                "nonces": [{"contract_address": "0x11", "nonce": "0x1"}]
+            }}"#,
+        )
+        .unwrap();
+        let state = StateDump::load(path).unwrap();
+        assert_eq!(state.contracts.len(), 2);
+        let contract = &state.contracts[&Felt252::from(0x10)];
+        assert_eq!(contract.class_hash, Some(Felt252::from(0x20)));
+        assert_eq!(contract.storage[&Felt252::from(1)], Felt252::from(2));
+        assert_eq!(
+            state.contracts[&Felt252::from(0x11)].nonce,
+            Some(Felt252::from(1))
+        );
+
+        // The class of 0x10 is loaded, 0x12 has no class
+        let casm = std::env::current_dir()
+            .unwrap()
+            .join("tests1.0/fuzzinglabs.casm");
+        fs::write(
+            path,
+            format!(
+                r#"{{"contracts": {{"0x10": {{"class_hash": "0x20"}}, "0x12": {{"class_hash": "0x21"}}}},
+                "classes": {{"0x20": "{}"}}}}"#,
+                casm.display()
+            ),
+        )
+        .unwrap();
+        let state = StateDump::load(path).unwrap();
+        assert!(state.classes.contains_key(&Felt252::from(0x20)));
+        assert_eq!(
+            state.missing_classes(),
+            vec![(Felt252::from(0x12), Felt252::from(0x21))]
+        );
+
+        fs::write(path, r#"{"contracts": {"0x10": {"nonce": "nonce"}}}"#).unwrap();
+        assert!(StateDump::load(path).is_err());
+        let _ = fs::remove_file(path);
+    }
+}